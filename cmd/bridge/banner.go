@@ -0,0 +1,37 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"log"
+	"os"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+// printBanner displays the bridge UID prominently along with the connection QR code.
+// With --no-banner only a single log line is written, which suits log aggregation.
+func printBanner(cfg *config.Config) {
+	if flags.noBanner {
+		writeBanner(log.Writer(), cfg, true)
+		return
+	}
+
+	writeBanner(os.Stdout, cfg, false)
+	printConnectionQRCode(cfg.WebhookURL, cfg.UID)
+}
+
+// writeBanner writes the UID banner to w. A plain banner is one line formatted like the
+// standard logger's output, without box drawing or blank lines.
+func writeBanner(w io.Writer, cfg *config.Config, plain bool) {
+	if plain {
+		log.New(w, log.Prefix(), log.Flags()).Printf("[Main] %s", config.GetDisplayUID(cfg))
+		return
+	}
+
+	fmt.Fprintln(w)
+	fmt.Fprintln(w, "╔══════════════════════════════════════════════════════════╗")
+	fmt.Fprintf(w, "║  %-50s                                         ║\n", config.GetDisplayUID(cfg))
+	fmt.Fprintln(w, "╚══════════════════════════════════════════════════════════╝")
+	fmt.Fprintln(w)
+}
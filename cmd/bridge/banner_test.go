@@ -0,0 +1,47 @@
+package main
+
+import (
+	"bytes"
+	"log"
+	"strings"
+	"testing"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+func TestPrintBannerNoBanner(t *testing.T) {
+	var buf bytes.Buffer
+	defer log.SetOutput(log.Writer())
+	log.SetOutput(&buf)
+	defer func(saved bool) { flags.noBanner = saved }(flags.noBanner)
+	flags.noBanner = true
+
+	printBanner(&config.Config{UID: "uid-123", WebhookURL: "ws://h/ws"})
+
+	out := buf.String()
+	if !strings.Contains(out, "uid-123") {
+		t.Errorf("banner %q doesn't show the UID", out)
+	}
+	if strings.ContainsAny(out, "╔═╗║╚╝") {
+		t.Errorf("banner %q contains box drawing", out)
+	}
+	lines := strings.Split(strings.TrimSuffix(out, "\n"), "\n")
+	if len(lines) != 1 {
+		t.Errorf("banner is %d lines, want 1: %q", len(lines), out)
+	}
+	for _, line := range lines {
+		if strings.TrimSpace(line) == "" {
+			t.Errorf("banner %q contains a blank line", out)
+		}
+	}
+}
+
+func TestWriteBannerBox(t *testing.T) {
+	var buf bytes.Buffer
+	writeBanner(&buf, &config.Config{UID: "uid-123"}, false)
+
+	out := buf.String()
+	if !strings.Contains(out, "uid-123") || !strings.Contains(out, "╔") {
+		t.Errorf("boxed banner = %q, want the UID in a box", out)
+	}
+}
@@ -0,0 +1,20 @@
+package main
+
+import (
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/bridge"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+// coalesceWindows converts the coalesce section of bridge.json into bridge windows
+func coalesceWindows(cfg map[string]config.CoalesceConfig) map[string]bridge.CoalesceWindow {
+	windows := make(map[string]bridge.CoalesceWindow, len(cfg))
+	for kind, c := range cfg {
+		windows[kind] = bridge.CoalesceWindow{
+			Interval:  time.Duration(c.IntervalMs) * time.Millisecond,
+			MaxBuffer: c.MaxBuffer,
+		}
+	}
+	return windows
+}
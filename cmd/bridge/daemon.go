@@ -0,0 +1,237 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+// pidFileEnv tells a daemon child where to write its PID file once it has started
+const pidFileEnv = "OPENCLAW_BRIDGE_PID_FILE"
+
+// daemonStartTimeout bounds how long start waits for the daemon to write its PID file
+const daemonStartTimeout = 10 * time.Second
+
+// startDaemon re-executes the binary in run mode as a detached background process with
+// output sent to out, and waits until the child has written its PID to pidPath.
+// The child runs from the config directory so it doesn't depend on the caller's shell.
+func startDaemon(pidPath string, out *os.File) (int, error) {
+	devNull, err := os.Open(os.DevNull)
+	if err != nil {
+		return 0, fmt.Errorf("failed to open %s: %w", os.DevNull, err)
+	}
+	defer devNull.Close()
+
+	exe, err := os.Executable()
+	if err != nil {
+		return 0, fmt.Errorf("failed to get executable path: %w", err)
+	}
+
+	// Clear a stale PID file so we only accept one written by the new child
+	os.Remove(pidPath)
+
+	p, err := os.StartProcess(exe, append([]string{exe, "run"}, daemonArgs()...), &os.ProcAttr{
+		Dir:   filepath.Dir(pidPath),
+		Env:   append(os.Environ(), pidFileEnv+"="+pidPath),
+		Files: []*os.File{devNull, out, out},
+		Sys:   daemonSysProcAttr(),
+	})
+	if err != nil {
+		return 0, fmt.Errorf("failed to start daemon: %w", err)
+	}
+
+	exited := make(chan struct{})
+	go func() {
+		p.Wait()
+		close(exited)
+	}()
+
+	ticker := time.NewTicker(100 * time.Millisecond)
+	defer ticker.Stop()
+	deadline := time.After(daemonStartTimeout)
+	for {
+		select {
+		case <-exited:
+			return 0, fmt.Errorf("daemon exited during startup")
+		case <-deadline:
+			p.Kill()
+			return 0, fmt.Errorf("daemon did not write %s within %v", pidPath, daemonStartTimeout)
+		case <-ticker.C:
+			if pid, err := readPID(pidPath); err == nil && pid == p.Pid {
+				return pid, nil
+			}
+		}
+	}
+}
+
+// writeDaemonPID records our PID when started by startDaemon and returns a function
+// that removes the PID file again on shutdown. It does nothing in the foreground.
+func writeDaemonPID() func() {
+	pidPath := os.Getenv(pidFileEnv)
+	if pidPath == "" {
+		return func() {}
+	}
+	if err := os.WriteFile(pidPath, []byte(strconv.Itoa(os.Getpid())), 0644); err != nil {
+		fmt.Fprintf(os.Stderr, "Failed to write PID file %s: %v\n", pidPath, err)
+		os.Exit(1)
+	}
+	return func() {
+		// Only remove it if it still names us (a newer daemon may have replaced it)
+		if pid, err := readPID(pidPath); err == nil && pid == os.Getpid() {
+			os.Remove(pidPath)
+		}
+	}
+}
+
+// defaultStopGrace is how long stop waits after SIGTERM before killing the daemon
+const defaultStopGrace = 10 * time.Second
+
+// stopResult describes what stopDaemon did
+type stopResult int
+
+const (
+	stopNotRunning stopResult = iota // No daemon was running (a stale PID file was removed)
+	stopStopped                      // The daemon exited after SIGTERM
+	stopKilled                       // The daemon ignored SIGTERM and was killed
+)
+
+func (r stopResult) String() string {
+	switch r {
+	case stopStopped:
+		return "Stopped"
+	case stopKilled:
+		return "Stopped (killed after the grace period)"
+	default:
+		return "Not running"
+	}
+}
+
+// readPID reads the PID recorded in pidPath
+func readPID(pidPath string) (int, error) {
+	data, err := os.ReadFile(pidPath)
+	if err != nil {
+		return 0, err
+	}
+	return strconv.Atoi(strings.TrimSpace(string(data)))
+}
+
+// isRunning reports whether the daemon recorded in pidPath is alive
+func isRunning(pidPath string) bool {
+	pid, err := readPID(pidPath)
+	if err != nil {
+		return false
+	}
+	return isProcessRunning(pid)
+}
+
+// stopDaemon asks the daemon recorded in pidPath to exit, waits up to grace for it, then
+// kills it. The PID file is removed once the process is gone, or if it was stale.
+func stopDaemon(pidPath string, grace time.Duration) (stopResult, error) {
+	pid, err := readPID(pidPath)
+	if err != nil {
+		return stopNotRunning, nil
+	}
+
+	if !isProcessRunning(pid) {
+		os.Remove(pidPath)
+		return stopNotRunning, nil
+	}
+
+	if err := stopProcess(pid); err != nil {
+		return stopNotRunning, fmt.Errorf("failed to signal process %d: %w", pid, err)
+	}
+	if waitForExit(pid, grace) {
+		os.Remove(pidPath)
+		return stopStopped, nil
+	}
+
+	if err := killProcess(pid); err != nil {
+		return stopNotRunning, fmt.Errorf("process %d did not exit within %v and could not be killed: %w", pid, grace, err)
+	}
+	if !waitForExit(pid, 2*time.Second) {
+		return stopNotRunning, fmt.Errorf("process %d is still running after being killed", pid)
+	}
+	os.Remove(pidPath)
+	return stopKilled, nil
+}
+
+// errInvalidRestartConfig means restart refused to stop the bridge because the config doesn't load
+var errInvalidRestartConfig = errors.New("invalid config, not restarting")
+
+// stopForRestart loads the config with the command-line overrides and only then stops the
+// running daemon with stop, so a broken config never takes down a working bridge
+func stopForRestart(stop func(pidPath string, grace time.Duration) (stopResult, error)) (stopResult, error) {
+	if _, err := config.LoadWithOverrides(flags.overrides()); err != nil {
+		return stopNotRunning, fmt.Errorf("%w: %v", errInvalidRestartConfig, err)
+	}
+
+	dir, err := config.Dir()
+	if err != nil {
+		return stopNotRunning, err
+	}
+
+	result, err := stop(filepath.Join(dir, "bridge.pid"), defaultStopGrace)
+	if err != nil {
+		return stopNotRunning, fmt.Errorf("failed to stop running bridge: %w", err)
+	}
+	return result, nil
+}
+
+// waitForExit polls until the process is gone or the timeout elapses
+func waitForExit(pid int, timeout time.Duration) bool {
+	deadline := time.Now().Add(timeout)
+	for isProcessRunning(pid) {
+		if time.Now().After(deadline) {
+			return false
+		}
+		time.Sleep(200 * time.Millisecond)
+	}
+	return true
+}
+
+// cmdStop stops the running daemon. "--grace SECONDS" sets how long to wait for a
+// clean exit before killing it.
+func cmdStop(args []string) {
+	grace := defaultStopGrace
+	for i := 0; i < len(args); i++ {
+		value := ""
+		switch {
+		case args[i] == "--grace" && i+1 < len(args):
+			i++
+			value = args[i]
+		case strings.HasPrefix(args[i], "--grace="):
+			value = strings.TrimPrefix(args[i], "--grace=")
+		default:
+			fmt.Fprintf(os.Stderr, "Unknown stop argument: %s\n\n%s", args[i], usage)
+			os.Exit(1)
+		}
+		seconds, err := strconv.Atoi(value)
+		if err != nil || seconds < 0 {
+			fmt.Fprintf(os.Stderr, "Invalid --grace value %q (expected seconds)\n", value)
+			os.Exit(1)
+		}
+		grace = time.Duration(seconds) * time.Second
+	}
+
+	dir, err := config.Dir()
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	result, err := stopDaemon(filepath.Join(dir, "bridge.pid"), grace)
+	if err != nil {
+		fmt.Printf("Failed to stop: %v\n", err)
+		os.Exit(1)
+	}
+	fmt.Println(result)
+	if result == stopNotRunning {
+		os.Exit(1)
+	}
+}
@@ -0,0 +1,73 @@
+package main
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+func TestStopForRestart(t *testing.T) {
+	const gatewayConfig = `{"gateway": {"port": 19000, "auth": {"token": "gw-token"}}}`
+
+	tests := []struct {
+		name     string
+		bridge   string
+		wantStop bool
+		wantErr  error
+	}{
+		{name: "valid config stops the bridge", bridge: `{"webhook_url": "ws://h/ws"}`, wantStop: true},
+		{name: "invalid config aborts before stopping", bridge: `{"webhook_url": "ws://h/ws", "session_scope": "per-user"}`, wantErr: errInvalidRestartConfig},
+		{name: "missing webhook url aborts before stopping", bridge: `{}`, wantErr: errInvalidRestartConfig},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			home := t.TempDir()
+			t.Setenv("HOME", home)
+			t.Setenv("USERPROFILE", home)
+			for _, env := range []string{config.EnvWebhookURL, config.EnvUID, config.EnvWebhookToken} {
+				t.Setenv(env, "")
+			}
+			dir := filepath.Join(home, ".openclaw")
+			if err := os.MkdirAll(dir, 0755); err != nil {
+				t.Fatal(err)
+			}
+			for name, contents := range map[string]string{"openclaw.json": gatewayConfig, "bridge.json": tt.bridge} {
+				if err := os.WriteFile(filepath.Join(dir, name), []byte(contents), 0600); err != nil {
+					t.Fatal(err)
+				}
+			}
+
+			var stoppedPID string
+			stopped := false
+			result, err := stopForRestart(func(pidPath string, grace time.Duration) (stopResult, error) {
+				stopped = true
+				stoppedPID = pidPath
+				return stopStopped, nil
+			})
+
+			if stopped != tt.wantStop {
+				t.Fatalf("stop called = %v, want %v", stopped, tt.wantStop)
+			}
+			if tt.wantErr != nil {
+				if !errors.Is(err, tt.wantErr) {
+					t.Fatalf("stopForRestart error = %v, want %v", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("stopForRestart: %v", err)
+			}
+			if result != stopStopped {
+				t.Errorf("result = %v, want %v", result, stopStopped)
+			}
+			if want := filepath.Join(dir, "bridge.pid"); stoppedPID != want {
+				t.Errorf("stopped %s, want %s", stoppedPID, want)
+			}
+		})
+	}
+}
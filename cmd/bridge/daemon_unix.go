@@ -26,3 +26,11 @@ func stopProcess(pid int) error {
 	}
 	return proc.Signal(syscall.SIGTERM)
 }
+
+func killProcess(pid int) error {
+	proc, err := os.FindProcess(pid)
+	if err != nil {
+		return err
+	}
+	return proc.Signal(syscall.SIGKILL)
+}
@@ -31,3 +31,8 @@ func stopProcess(pid int) error {
 	}
 	return proc.Kill()
 }
+
+// killProcess is the same as stopProcess: Windows has no graceful signal to escalate from
+func killProcess(pid int) error {
+	return stopProcess(pid)
+}
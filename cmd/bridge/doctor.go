@@ -0,0 +1,163 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"net"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strconv"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+// checkStatus is the outcome of a single doctor check
+type checkStatus string
+
+const (
+	checkPass checkStatus = "PASS"
+	checkWarn checkStatus = "WARN"
+	checkFail checkStatus = "FAIL"
+)
+
+// doctorReport prints check results and remembers whether any failed
+type doctorReport struct {
+	failed bool
+}
+
+func (r *doctorReport) add(status checkStatus, name, detail, hint string) {
+	fmt.Printf("[%s] %s: %s\n", status, name, detail)
+	if hint != "" && status != checkPass {
+		fmt.Printf("       -> %s\n", hint)
+	}
+	if status == checkFail {
+		r.failed = true
+	}
+}
+
+// cmdDoctor runs a series of setup checks and exits non-zero if any of them fail
+func cmdDoctor() {
+	r := &doctorReport{}
+
+	dir, err := config.Dir()
+	if err != nil {
+		r.add(checkFail, "home directory", err.Error(), "Make sure $HOME is set for this user")
+		os.Exit(1)
+	}
+	r.add(checkPass, "home directory", dir, "")
+
+	cfg, err := config.LoadWithOverrides(flags.overrides())
+	if err != nil {
+		r.add(checkFail, "config", err.Error(),
+			"Fix ~/.openclaw/openclaw.json and ~/.openclaw/bridge.json, or run: openclaw-bridge start webhook_url=ws://...")
+		os.Exit(1)
+	}
+	r.add(checkPass, "config", "openclaw.json and bridge.json parsed", "")
+
+	checkWebhookURL(r, cfg.WebhookURL)
+	checkGatewayToken(r, cfg)
+	checkGatewayReachable(r, cfg.OpenClaw.GatewayHost, cfg.OpenClaw.GatewayPort)
+	checkStoreWritable(r, cfg.SessionStorePath)
+	checkClock(r, cfg.SessionStorePath)
+
+	if r.failed {
+		fmt.Println("\nSome checks failed")
+		os.Exit(1)
+	}
+	fmt.Println("\nAll checks passed")
+}
+
+func checkWebhookURL(r *doctorReport, raw string) {
+	const name = "webhook URL"
+	u, err := url.Parse(raw)
+	if err != nil {
+		r.add(checkFail, name, err.Error(), "Set webhook_url in bridge.json to a ws:// or wss:// URL")
+		return
+	}
+	switch u.Scheme {
+	case "wss":
+		r.add(checkPass, name, raw, "")
+	case "ws":
+		r.add(checkWarn, name, raw+" (unencrypted)", "Use wss:// when the webhook server is not on localhost")
+	default:
+		r.add(checkFail, name, fmt.Sprintf("unsupported scheme %q", u.Scheme), "Set webhook_url in bridge.json to a ws:// or wss:// URL")
+	}
+}
+
+func checkGatewayToken(r *doctorReport, cfg *config.Config) {
+	const name = "gateway token"
+	if cfg.OpenClaw.GatewayToken == "" {
+		if cfg.OpenClaw.AuthMode == "challenge" {
+			r.add(checkWarn, name, "empty (challenge auth uses gateway_auth_secret)", "")
+			return
+		}
+		r.add(checkFail, name, "empty", "Set gateway.auth.token in ~/.openclaw/openclaw.json")
+		return
+	}
+	r.add(checkPass, name, "set", "")
+}
+
+func checkGatewayReachable(r *doctorReport, host string, port int) {
+	const name = "gateway reachable"
+	addr := net.JoinHostPort(host, strconv.Itoa(port))
+	conn, err := net.DialTimeout("tcp", addr, 3*time.Second)
+	if err != nil {
+		r.add(checkFail, name, err.Error(), "Start the OpenClaw gateway or fix gateway.port in openclaw.json / gateway_host in bridge.json")
+		return
+	}
+	conn.Close()
+	r.add(checkPass, name, addr, "")
+}
+
+func checkStoreWritable(r *doctorReport, storePath string) {
+	const name = "session store"
+	f, err := os.CreateTemp(filepath.Dir(storePath), ".doctor-*")
+	if err != nil {
+		r.add(checkFail, name, err.Error(), "Check ownership and permissions of "+filepath.Dir(storePath))
+		return
+	}
+	f.Close()
+	os.Remove(f.Name())
+
+	if data, err := os.ReadFile(storePath); err == nil {
+		var store map[string]*sessions.SessionEntry
+		if err := json.Unmarshal(data, &store); err != nil {
+			r.add(checkFail, name, "unparseable: "+err.Error(), "Move "+storePath+" aside; the bridge will start a fresh store")
+			return
+		}
+		r.add(checkPass, name, fmt.Sprintf("%s (%d sessions)", storePath, len(store)), "")
+		return
+	}
+	r.add(checkPass, name, storePath+" (not created yet)", "")
+}
+
+// checkClock flags clocks that are obviously wrong or behind the newest session timestamp
+func checkClock(r *doctorReport, storePath string) {
+	const name = "clock"
+	now := time.Now()
+	if now.Year() < 2024 {
+		r.add(checkFail, name, now.Format(time.RFC3339), "Sync the system clock (e.g. enable NTP)")
+		return
+	}
+
+	var newest int64
+	if data, err := os.ReadFile(storePath); err == nil {
+		var store map[string]*sessions.SessionEntry
+		if json.Unmarshal(data, &store) == nil {
+			for _, entry := range store {
+				if entry != nil && entry.UpdatedAt > newest {
+					newest = entry.UpdatedAt
+				}
+			}
+		}
+	}
+	if ahead := time.Duration(newest-now.UnixMilli()) * time.Millisecond; ahead > 5*time.Minute {
+		r.add(checkWarn, name, fmt.Sprintf("session store has timestamps %v in the future", ahead.Round(time.Second)),
+			"The system clock may have been set back; sync it (e.g. enable NTP)")
+		return
+	}
+	r.add(checkPass, name, now.Format(time.RFC3339), "")
+}
@@ -0,0 +1,52 @@
+package main
+
+import (
+	"log"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+// startSessionEviction removes sessions idle longer than the TTL for their kind
+// (session_ttl in bridge.json, in seconds) every interval and returns a func that stops it
+func startSessionEviction(store sessions.SessionBackend, cfg map[string]int, interval time.Duration) func() {
+	ttls := make(sessions.SessionTTLs, len(cfg))
+	enabled := false
+	for kind, seconds := range cfg {
+		ttls[kind] = time.Duration(seconds) * time.Second
+		if seconds > 0 {
+			enabled = true
+		}
+	}
+	if !enabled {
+		return func() {}
+	}
+	log.Printf("[Main] Evicting idle sessions every %v (TTL by kind: %v)", interval, ttls)
+
+	sweep := func() {
+		evicted, err := store.EvictOlderThan(ttls)
+		if err != nil {
+			log.Printf("[Main] Session eviction failed: %v", err)
+			return
+		}
+		if len(evicted) > 0 {
+			log.Printf("[Main] Evicted %d idle session(s)", len(evicted))
+		}
+	}
+
+	done := make(chan struct{})
+	go func() {
+		sweep()
+		ticker := time.NewTicker(interval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-done:
+				return
+			case <-ticker.C:
+				sweep()
+			}
+		}
+	}()
+	return func() { close(done) }
+}
@@ -0,0 +1,163 @@
+package main
+
+import (
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+const usage = `Usage:
+  openclaw-bridge start [webhook_url=ws://...] [--webhook-url URL] [--uid UID]
+  openclaw-bridge stop [--grace SECONDS]
+  openclaw-bridge status [--webhook-url URL] [--uid UID]
+  openclaw-bridge restart [--webhook-url URL] [--uid UID]
+  openclaw-bridge run [--webhook-url URL] [--uid UID]
+  openclaw-bridge doctor
+  openclaw-bridge sessions show <key>
+  openclaw-bridge sessions reset <key> [--delete]
+  openclaw-bridge regenerate-uid
+
+Global flags:
+  --no-banner           Don't print the UID box and QR code on startup
+  --dump-events PATH    Append every raw gateway frame to PATH as NDJSON (for debugging)
+
+Config overrides (take precedence over OPENCLAW_BRIDGE_WEBHOOK_URL / OPENCLAW_BRIDGE_UID and bridge.json):
+  --webhook-url URL     Connect to URL for this run without saving it
+  --uid UID             Use UID and save it to bridge.json so restarts keep it
+  --session-scope SCOPE Use "per-sender", "global" or "per-chat" session scope for this run
+  --max-message-age SECONDS
+                        Drop queued outbound messages older than SECONDS instead of sending them
+`
+
+// globalFlags holds flags that are accepted anywhere on the command line
+type globalFlags struct {
+	noBanner   bool
+	dumpEvents string // Absolute path of the frame capture file, empty = off
+	webhookURL string // --webhook-url override, empty = from env/bridge.json
+	uid        string // --uid override, empty = from env/bridge.json
+	scope      string // --session-scope override, empty = from config
+	maxAge     *int   // --max-message-age override in seconds, nil = from config
+}
+
+var flags globalFlags
+
+// parseGlobalFlags extracts global flags from args and returns the remaining arguments
+func parseGlobalFlags(args []string) []string {
+	rest := make([]string, 0, len(args))
+	for i := 0; i < len(args); i++ {
+		arg := args[i]
+		switch {
+		case arg == "--no-banner":
+			flags.noBanner = true
+		case arg == "--dump-events":
+			if i+1 >= len(args) {
+				fmt.Fprintf(os.Stderr, "--dump-events requires a file path\n\n%s", usage)
+				os.Exit(1)
+			}
+			i++
+			flags.dumpEvents = absPath(args[i])
+		case strings.HasPrefix(arg, "--dump-events="):
+			flags.dumpEvents = absPath(strings.TrimPrefix(arg, "--dump-events="))
+		case arg == "--webhook-url" || arg == "--uid" || arg == "--session-scope" || arg == "--max-message-age":
+			if i+1 >= len(args) {
+				fmt.Fprintf(os.Stderr, "%s requires a value\n\n%s", arg, usage)
+				os.Exit(1)
+			}
+			i++
+			switch arg {
+			case "--uid":
+				flags.uid = args[i]
+			case "--session-scope":
+				flags.scope = parseScopeFlag(args[i])
+			case "--max-message-age":
+				flags.maxAge = parseAgeFlag(args[i])
+			default:
+				flags.webhookURL = args[i]
+			}
+		case strings.HasPrefix(arg, "--webhook-url="):
+			flags.webhookURL = strings.TrimPrefix(arg, "--webhook-url=")
+		case strings.HasPrefix(arg, "--uid="):
+			flags.uid = strings.TrimPrefix(arg, "--uid=")
+		case strings.HasPrefix(arg, "--session-scope="):
+			flags.scope = parseScopeFlag(strings.TrimPrefix(arg, "--session-scope="))
+		case strings.HasPrefix(arg, "--max-message-age="):
+			flags.maxAge = parseAgeFlag(strings.TrimPrefix(arg, "--max-message-age="))
+		default:
+			rest = append(rest, arg)
+		}
+	}
+	return rest
+}
+
+// parseScopeFlag validates a --session-scope value, exiting on unknown scopes
+func parseScopeFlag(value string) string {
+	scope, err := sessions.ParseSessionScope(value)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "%v\n\n%s", err, usage)
+		os.Exit(1)
+	}
+	return string(scope)
+}
+
+// parseAgeFlag validates a --max-message-age value, exiting unless it is a whole number of seconds
+func parseAgeFlag(value string) *int {
+	age, err := strconv.Atoi(value)
+	if err != nil || age < 0 {
+		fmt.Fprintf(os.Stderr, "invalid --max-message-age %q (expected seconds, 0 = no limit)\n\n%s", value, usage)
+		os.Exit(1)
+	}
+	return &age
+}
+
+// absPath resolves a flag path against the current directory, since the daemon may not share it
+func absPath(path string) string {
+	if abs, err := filepath.Abs(path); err == nil {
+		return abs
+	}
+	return path
+}
+
+// overrides returns the command-line config overrides
+func (f globalFlags) overrides() config.Overrides {
+	return config.Overrides{WebhookURL: f.webhookURL, UID: f.uid, SessionScope: f.scope, MaxMessageAge: f.maxAge}
+}
+
+// saveUIDFlag persists --uid to bridge.json so later restarts keep the same UID
+func saveUIDFlag() {
+	if flags.uid == "" {
+		return
+	}
+	path, err := config.UpdateBridgeConfig(map[string]string{"uid": flags.uid})
+	if err != nil {
+		log.Fatalf("Failed to save uid: %v", err)
+	}
+	fmt.Printf("Saved uid to %s\n", path)
+}
+
+// daemonArgs returns the global flags to pass on to the re-executed daemon process
+func daemonArgs() []string {
+	var args []string
+	if flags.noBanner {
+		args = append(args, "--no-banner")
+	}
+	if flags.dumpEvents != "" {
+		args = append(args, "--dump-events", flags.dumpEvents)
+	}
+	// --uid has already been saved to bridge.json by the parent
+	if flags.webhookURL != "" {
+		args = append(args, "--webhook-url", flags.webhookURL)
+	}
+	if flags.scope != "" {
+		args = append(args, "--session-scope", flags.scope)
+	}
+	if flags.maxAge != nil {
+		args = append(args, "--max-message-age", strconv.Itoa(*flags.maxAge))
+	}
+	return args
+}
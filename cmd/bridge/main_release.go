@@ -11,56 +11,54 @@ import (
 	"os"
 	"os/signal"
 	"path/filepath"
-	"strconv"
 	"strings"
 	"syscall"
 	"time"
 
 	"github.com/google/uuid"
 	"github.com/skip2/go-qrcode"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/admin"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/bridge"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/capture"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/openclaw"
-	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
-	"github.com/sternelee/openclaw-webhook-bridge/internal/webhook"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/schema"
 )
 
 func main() {
+	args := parseGlobalFlags(os.Args[1:])
+
 	cmd := "run"
-	if len(os.Args) > 1 {
-		cmd = os.Args[1]
+	if len(args) > 0 {
+		cmd = args[0]
 	}
 
 	switch cmd {
 	case "start":
-		applyConfigArgs(os.Args[2:])
+		applyConfigArgs(args[1:])
 		cmdStartRelease()
 	case "stop":
-		cmdStop()
+		cmdStop(args[1:])
 	case "status":
 		cmdStatus()
 	case "restart":
-		applyConfigArgs(os.Args[2:])
-		dir, _ := config.Dir()
-		pidPath := filepath.Join(dir, "bridge.pid")
-		if pid, err := readPID(pidPath); err == nil {
-			stopProcess(pid)
-			for i := 0; i < 10; i++ {
-				time.Sleep(200 * time.Millisecond)
-				if !isProcessRunning(pid) {
-					break
-				}
-			}
-			os.Remove(pidPath)
-		}
-		cmdStartRelease()
+		cmdRestart(args[1:])
+	case "doctor":
+		cmdDoctor()
+	case "sessions":
+		cmdSessions(args[1:])
+	case "regenerate-uid":
+		cmdRegenerateUID()
 	case "run":
-		if len(os.Args) > 2 {
-			applyConfigArgs(os.Args[2:])
+		if len(args) > 1 {
+			applyConfigArgs(args[1:])
+		} else {
+			saveUIDFlag()
 		}
 		cmdRunRelease()
 	default:
-		fmt.Fprintf(os.Stderr, "Unknown command: %s\n\nUsage:\n  openclaw-bridge start [webhook_url=ws://...]\n  openclaw-bridge stop\n  openclaw-bridge status\n  openclaw-bridge restart\n  openclaw-bridge run\n", cmd)
+		fmt.Fprintf(os.Stderr, "Unknown command: %s\n\n%s", cmd, usage)
 		os.Exit(1)
 	}
 }
@@ -81,49 +79,27 @@ func cmdStartRelease() {
 	}
 
 	// Validate config before daemonizing so errors are visible
-	cfg, err := config.Load()
+	cfg, err := config.LoadWithOverrides(flags.overrides())
 	if err != nil {
 		log.Fatalf("Config error: %v", err)
 	}
 
 	// Display UID prominently before daemonizing
-	fmt.Println()
-	fmt.Println("╔══════════════════════════════════════════════════════════╗")
-	fmt.Printf("║  %-50s                                         ║\n", config.GetDisplayUID(cfg))
-	fmt.Println("╚══════════════════════════════════════════════════════════╝")
-	fmt.Println()
-	printConnectionQRCode(cfg.WebhookURL, cfg.UID)
+	printBanner(cfg)
 
 	// Open /dev/null for both stdout and stderr (no logging in release mode)
-	devNull, err := os.Open(os.DevNull)
+	devNull, err := os.OpenFile(os.DevNull, os.O_WRONLY, 0)
 	if err != nil {
 		log.Fatalf("Failed to open %s: %v", os.DevNull, err)
 	}
 	defer devNull.Close()
 
-	// Re-exec self as daemon
-	exe, err := os.Executable()
-	if err != nil {
-		log.Fatalf("Failed to get executable path: %v", err)
-	}
-
-	p, err := os.StartProcess(exe, []string{exe, "run"}, &os.ProcAttr{
-		Files: []*os.File{devNull, devNull, devNull}, // stdin, stdout, stderr all go to /dev/null
-		Sys:   daemonSysProcAttr(),
-	})
+	// Re-exec self as daemon and wait for it to come up
+	pid, err := startDaemon(pidPath, devNull)
 	if err != nil {
 		log.Fatalf("Failed to start daemon: %v", err)
 	}
 
-	pid := p.Pid
-
-	// Write PID file
-	if err := os.WriteFile(pidPath, []byte(strconv.Itoa(pid)), 0644); err != nil {
-		p.Kill()
-		log.Fatalf("Failed to write PID file: %v", err)
-	}
-
-	p.Release()
 	fmt.Printf("Started (PID %d), logging disabled in release mode\n", pid)
 }
 
@@ -133,20 +109,18 @@ func cmdRunRelease() {
 	log.SetOutput(os.Stderr)
 	log.SetFlags(0)
 
-	cfg, err := config.Load()
+	cfg, err := config.LoadWithOverrides(flags.overrides())
 	if err != nil {
 		log.Fatalf("[Main] Failed to load config: %v", err)
 	}
 
+	// When started by "start", tell the waiting parent we're up
+	defer writeDaemonPID()()
+
 	// ==========================================
 	// DISPLAY BRIDGE UID (prominently)
 	// ==========================================
-	fmt.Println()
-	fmt.Println("╔══════════════════════════════════════════════════════════╗")
-	fmt.Printf("║  %-50s                                         ║\n", config.GetDisplayUID(cfg))
-	fmt.Println("╚══════════════════════════════════════════════════════════╝")
-	fmt.Println()
-	printConnectionQRCode(cfg.WebhookURL, cfg.UID)
+	printBanner(cfg)
 
 	// Create OpenClaw client
 	clawdbotClient := openclaw.NewClient(
@@ -154,39 +128,170 @@ func cmdRunRelease() {
 		cfg.OpenClaw.GatewayToken,
 		cfg.OpenClaw.AgentID,
 	)
+	if cfg.OpenClaw.AuthMode == "challenge" {
+		clawdbotClient.SetChallengeAuth(cfg.OpenClaw.AuthSecret)
+	}
+	if cfg.OpenClaw.RedirectTTL > 0 {
+		clawdbotClient.SetRedirectTTL(time.Duration(cfg.OpenClaw.RedirectTTL) * time.Second)
+	}
+	clawdbotClient.SetGateway(cfg.OpenClaw.GatewayHost, cfg.OpenClaw.GatewayTLS)
+	clawdbotClient.SetHandshakeTimeout(time.Duration(cfg.OpenClaw.HandshakeTimeout) * time.Second)
+	clawdbotClient.SetKeepalive(
+		time.Duration(cfg.OpenClaw.KeepaliveInterval)*time.Second,
+		time.Duration(cfg.OpenClaw.KeepaliveTimeout)*time.Second,
+	)
+
+	// Continue counters from the last run so dashboards see monotonic values
+	if cfg.PersistMetrics {
+		if err := metrics.Default.LoadFile(cfg.MetricsPath); err != nil {
+			log.Printf("[Main] Failed to restore metrics: %v", err)
+		}
+		// Registered first so it runs after the connections are closed
+		defer func() {
+			if err := metrics.Default.SaveFile(cfg.MetricsPath); err != nil {
+				log.Printf("[Main] Failed to persist metrics: %v", err)
+			}
+		}()
+	}
 
 	// Create session store
-	sessionStore := sessions.NewStore(sessions.DefaultStoreConfig(cfg.SessionStorePath))
+	sessionStore, stopSessionStore := startSessionStore(cfg)
+	defer stopSessionStore()
 
 	// Create bridge
 	bridgeInstance := bridge.NewBridge(nil, clawdbotClient)
 	bridgeInstance.SetUID(cfg.UID)               // Set UID for message routing
 	bridgeInstance.SetSessionStore(sessionStore) // Configure session store
+	bridgeInstance.SetKnownAgents(cfg.OpenClaw.Agents)
 
 	// Set session scope from config
-	var scope sessions.SessionScope
-	switch cfg.SessionScope {
-	case "global":
-		scope = sessions.SessionScopeGlobal
-	case "per-sender":
-		fallthrough
-	default:
-		scope = sessions.SessionScopePerSender
+	bridgeInstance.SetSessionScope(cfg.SessionScope)
+	if cfg.PreferPeer {
+		bridgeInstance.SetPreferPeerSession(true)
+	}
+	bridgeInstance.SetMaxSessionKeyLength(cfg.MaxSessionKeyLength)
+	bridgeInstance.SetDropWhitespaceDeltas(cfg.WhitespaceDeltas == "drop")
+	bridgeInstance.SetSequenceNumbers(cfg.SequenceNumbers)
+	bridgeInstance.SetMessageDedup(cfg.DedupSize, time.Duration(cfg.DedupTTL)*time.Second)
+	bridgeInstance.SetEchoMode(cfg.EchoMode)
+	if cfg.ReplayHistory > 0 {
+		bridgeInstance.SetReplayHistory(cfg.ReplayHistory)
+	}
+	if cfg.SessionPreamble != "" {
+		bridgeInstance.SetSessionPreamble(cfg.SessionPreamble)
+	}
+	bridgeInstance.SetResetPreservesRouting(cfg.ResetPreservesRouting)
+	bridgeInstance.SetResetPreservesLabel(cfg.ResetPreservesLabel)
+	if cfg.ResetConfirmation != nil {
+		bridgeInstance.SetResetConfirmation(*cfg.ResetConfirmation)
+	}
+	bridgeInstance.SetResetConfirmWindow(time.Duration(cfg.ResetConfirmWindow) * time.Second)
+	bridgeInstance.SetUnknownEventMode(bridge.UnknownEventMode(cfg.UnknownEvents))
+	if cfg.UnknownSessions != "forward" {
+		var quarantine *capture.Recorder
+		if cfg.UnknownSessions == "quarantine" {
+			recorder, err := capture.Open(cfg.QuarantinePath)
+			if err != nil {
+				log.Fatalf("[Main] Failed to open quarantine file: %v", err)
+			}
+			defer recorder.Close()
+			quarantine = recorder
+		}
+		bridgeInstance.SetUnknownSessionMode(bridge.UnknownSessionMode(cfg.UnknownSessions), quarantine)
+	}
+	if cfg.MissingKeyMode != "forward" {
+		bridgeInstance.SetMissingSessionKeyMode(bridge.MissingSessionKeyMode(cfg.MissingKeyMode), cfg.FallbackKey)
+	}
+	bridgeInstance.SetReasoningMode(bridge.ReasoningMode(cfg.ReasoningStream))
+	bridgeInstance.SetEventAllowlist(cfg.EventAllowlist)
+	if len(cfg.RequestContext) > 0 {
+		bridgeInstance.SetRequestContext(cfg.RequestContext)
+	}
+	bridgeInstance.SetCoalescing(coalesceWindows(cfg.Coalesce))
+	if cfg.OutboundBatch != nil {
+		bridgeInstance.SetOutboundBatching(
+			cfg.OutboundBatch.MaxCount,
+			time.Duration(cfg.OutboundBatch.MaxDelayMs)*time.Millisecond,
+		)
+	}
+	bridgeInstance.SetResponseTimeout(
+		time.Duration(cfg.ResponseNoticeAfter)*time.Second,
+		time.Duration(cfg.ResponseTimeout)*time.Second,
+	)
+
+	// Validate inbound webhook messages if configured
+	if cfg.SchemaValidation != "off" {
+		webhookSchema := schema.DefaultWebhookSchema()
+		if cfg.SchemaPath != "" {
+			if webhookSchema, err = schema.LoadFile(cfg.SchemaPath); err != nil {
+				log.Fatalf("[Main] Failed to load webhook schema: %v", err)
+			}
+		}
+		bridgeInstance.SetSchemaValidation(webhookSchema, cfg.SchemaValidation == "drop")
 	}
-	bridgeInstance.SetSessionScope(scope)
+	bridgeInstance.SetMessageLogging(
+		bridge.MessageLogLevel(cfg.WebhookLogLevel),
+		bridge.MessageLogLevel(cfg.OpenClawLogLevel),
+		cfg.LogSampleEvery,
+	)
+	bridgeInstance.SetLogTruncation(cfg.LogTruncateLength)
+	bridgeInstance.SetLogMinInterval(time.Duration(cfg.LogMinInterval) * time.Second)
+
+	// Set OpenClaw event callback to forward to webhook, capturing raw frames if requested
+	if flags.dumpEvents != "" {
+		recorder, err := capture.Open(flags.dumpEvents)
+		if err != nil {
+			log.Fatalf("[Main] Failed to open event dump: %v", err)
+		}
+		defer recorder.Close()
+		log.Printf("[Main] Dumping raw gateway frames to %s", flags.dumpEvents)
 
-	// Set OpenClaw event callback to forward to webhook
-	clawdbotClient.SetEventCallback(bridgeInstance.HandleOpenClawEvent)
+		clawdbotClient.SetEventCallback(func(data []byte) {
+			if err := recorder.Write(data); err != nil {
+				log.Printf("[Main] Failed to dump gateway frame: %v", err)
+			}
+			bridgeInstance.HandleOpenClawEvent(data)
+		})
+	} else {
+		clawdbotClient.SetEventCallback(bridgeInstance.HandleOpenClawEvent)
+	}
 
-	// Create webhook client with bridge message handler
-	webhookClient := webhook.NewClient(
-		cfg.WebhookURL,
-		bridgeInstance.HandleWebhookMessage,
-		cfg.UID, // Pass UID for message identification
-	)
+	// Create the webhook clients (webhook_url and any extra webhooks) with the bridge message handler
+	webhookClients := newWebhookClients(cfg, bridgeInstance.HandleWebhookMessage)
+	bridgeInstance.SetWebhookClient(webhookClients[0])
+	if len(webhookClients) > 1 {
+		bridgeInstance.SetWebhookFanout(webhookTargets(cfg, webhookClients), bridge.WebhookDeliveryMode(cfg.WebhookDeliveryMode))
+	}
+
+	// Let "status" see the connection states of a daemon
+	defer startStatusWriter(clawdbotClient.IsConnected, webhookConnected(webhookClients))()
 
-	// Set webhook client on bridge
-	bridgeInstance.SetWebhookClient(webhookClient)
+	// Serve the admin endpoint for dashboards if configured
+	if cfg.AdminAddr != "" {
+		// The endpoints are optional extras: a taken port must not stop the bridge
+		adminServer := admin.NewServer(bridgeInstance)
+		if err := adminServer.Start(cfg.AdminAddr); err != nil {
+			log.Printf("[Main] Warning: admin endpoint disabled: %v", err)
+		} else {
+			defer adminServer.Close()
+		}
+	}
+
+	if cfg.MetricsPort > 0 {
+		metricsServer := metrics.NewServer(metrics.Default)
+		metricsServer.AddGauge("sessions", func() (float64, bool) {
+			store, err := sessionStore.Load()
+			if err != nil {
+				return 0, false
+			}
+			return float64(len(store)), true
+		})
+		if err := metricsServer.Start(cfg.MetricsPort); err != nil {
+			log.Printf("[Main] Warning: metrics endpoint disabled: %v", err)
+		} else {
+			defer metricsServer.Close()
+		}
+	}
 
 	ctx, cancel := context.WithCancel(context.Background())
 	defer cancel()
@@ -198,15 +303,13 @@ func cmdRunRelease() {
 	defer clawdbotClient.Close()
 
 	// Start Webhook persistent connection
-	if err := webhookClient.Connect(ctx); err != nil {
-		log.Fatalf("[Main] Failed to connect to Webhook server: %v", err)
-	}
-	defer webhookClient.Close()
+	connectWebhookClients(ctx, webhookClients)
+	defer closeWebhookClients(webhookClients)
 
 	// Make sure to close connections on shutdown
 	go func() {
 		<-ctx.Done()
-		webhookClient.Close()
+		closeWebhookClients(webhookClients)
 		clawdbotClient.Close()
 	}()
 
@@ -219,99 +322,53 @@ func cmdRunRelease() {
 	select {
 	case <-sigChan:
 		fmt.Println("\nShutting down...")
+		// Stop taking gateway events, then deliver anything coalescing still holds
+		// before the webhook connection goes away
+		clawdbotClient.Close()
+		bridgeInstance.Flush()
 		cancel()
 	}
 }
 
-func cmdStop() {
-	dir, err := config.Dir()
-	if err != nil {
-		log.Fatal(err)
-	}
-
-	pidPath := filepath.Join(dir, "bridge.pid")
-	pid, err := readPID(pidPath)
-	if err != nil {
-		fmt.Println("Not running")
-		os.Exit(1)
-	}
-
-	if err := stopProcess(pid); err != nil {
-		fmt.Println("Not running")
-		os.Remove(pidPath)
-		os.Exit(1)
-	}
-
-	for i := 0; i < 10; i++ {
-		time.Sleep(200 * time.Millisecond)
-		if !isProcessRunning(pid) {
-			break
-		}
-	}
-
-	os.Remove(pidPath)
-	fmt.Println("Stopped")
-}
-
-func cmdStatus() {
-	dir, err := config.Dir()
+// cmdRestart applies the config overrides from args, then stops the running daemon (if
+// any), waits for it to exit and starts a new one. A config that doesn't load leaves the
+// running daemon alone.
+func cmdRestart(args []string) {
+	applyConfigArgs(args)
+	result, err := stopForRestart(stopDaemon)
 	if err != nil {
 		log.Fatal(err)
 	}
-
-	pidPath := filepath.Join(dir, "bridge.pid")
-	if isRunning(pidPath) {
-		pid, _ := readPID(pidPath)
-		fmt.Printf("Running (PID %d)\n", pid)
+	if result == stopNotRunning {
+		fmt.Println("Not running, starting")
 	} else {
-		fmt.Println("Not running")
-		os.Exit(1)
+		fmt.Println(result)
 	}
-}
 
-// Helper functions
-func isRunning(pidPath string) bool {
-	pid, err := readPID(pidPath)
-	if err != nil {
-		return false
-	}
-	return isProcessRunning(pid)
-}
-
-func readPID(pidPath string) (int, error) {
-	data, err := os.ReadFile(pidPath)
-	if err != nil {
-		return 0, err
-	}
-	return strconv.Atoi(strings.TrimSpace(string(data)))
+	cmdStartRelease()
 }
 
 func applyConfigArgs(args []string) {
 	kv := parseKeyValue(args)
 	webhookURL := kv["webhook_url"]
 	uid := kv["uid"]
+	if flags.uid != "" {
+		uid = flags.uid
+	}
+	// An override from the command line or environment is used without being saved
+	webhookOverride := flags.webhookURL
+	if webhookOverride == "" {
+		webhookOverride = os.Getenv(config.EnvWebhookURL)
+	}
 
-	dir, err := config.Dir()
+	// The saved config (bridge.json or a merged config.json) provides the defaults
+	defaultWebhookURL, defaultUID, err := config.SavedBridgeIdentity()
 	if err != nil {
 		log.Fatal(err)
 	}
 
-	// Read existing config if present to use as defaults
-	var cfg bridgeConfigJSON
-	defaultWebhookURL := ""
-	defaultUID := ""
-	if data, err := os.ReadFile(filepath.Join(dir, "bridge.json")); err == nil {
-		json.Unmarshal(data, &cfg)
-		defaultWebhookURL = cfg.WebhookURL
-		if cfg.UID != "" {
-			defaultUID = cfg.UID
-		} else {
-			defaultUID = cfg.AgentID // Using agent_id field as uid for compatibility
-		}
-	}
-
 	// If no webhook_url provided, prompt for it with default value
-	if webhookURL == "" {
+	if webhookURL == "" && webhookOverride == "" {
 		reader := bufio.NewReader(os.Stdin)
 		if defaultWebhookURL != "" {
 			fmt.Printf("Enter WebSocket URL [%s]: ", defaultWebhookURL)
@@ -346,23 +403,17 @@ func applyConfigArgs(args []string) {
 		}
 	}
 
-	cfg.WebhookURL = webhookURL
-	cfg.UID = uid
-
-	data, _ := json.MarshalIndent(cfg, "", "  ")
-	path := filepath.Join(dir, "bridge.json")
-	if err := os.WriteFile(path, data, 0600); err != nil {
+	fields := map[string]string{"uid": uid}
+	if webhookURL != "" {
+		fields["webhook_url"] = webhookURL
+	}
+	path, err := config.UpdateBridgeConfig(fields)
+	if err != nil {
 		log.Fatalf("Failed to save config: %v", err)
 	}
 	fmt.Printf("Saved config to %s\n", path)
 }
 
-type bridgeConfigJSON struct {
-	WebhookURL string `json:"webhook_url"`
-	AgentID    string `json:"agent_id,omitempty"`
-	UID        string `json:"uid,omitempty"`
-}
-
 func generateUID() string {
 	return uuid.NewString()
 }
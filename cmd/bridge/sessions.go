@@ -0,0 +1,216 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+// cmdSessions dispatches the "sessions" subcommands
+func cmdSessions(args []string) {
+	if len(args) == 0 {
+		fmt.Fprintf(os.Stderr, "Missing sessions subcommand\n\n%s", usage)
+		os.Exit(1)
+	}
+
+	// Store operations log at info level; keep CLI output clean
+	log.SetOutput(io.Discard)
+
+	switch args[0] {
+	case "show":
+		if len(args) != 2 {
+			fmt.Fprintln(os.Stderr, "Usage: openclaw-bridge sessions show <key>")
+			os.Exit(1)
+		}
+		cmdSessionsShow(args[1])
+	case "reset":
+		key, del := "", false
+		for _, arg := range args[1:] {
+			switch {
+			case arg == "--delete":
+				del = true
+			case key == "" && !strings.HasPrefix(arg, "--"):
+				key = arg
+			default:
+				fmt.Fprintln(os.Stderr, "Usage: openclaw-bridge sessions reset <key> [--delete]")
+				os.Exit(1)
+			}
+		}
+		if key == "" {
+			fmt.Fprintln(os.Stderr, "Usage: openclaw-bridge sessions reset <key> [--delete]")
+			os.Exit(1)
+		}
+		cmdSessionsReset(key, del)
+	default:
+		fmt.Fprintf(os.Stderr, "Unknown sessions subcommand: %s\n\n%s", args[0], usage)
+		os.Exit(1)
+	}
+}
+
+// newSessionStore opens the configured sessions.json store
+func newSessionStore(cfg *config.Config) sessions.SessionBackend {
+	storeConfig := sessions.DefaultStoreConfig(cfg.SessionStorePath)
+	storeConfig.Pretty = !cfg.CompactStore
+	storeConfig.Format = sessions.StoreFormat(cfg.StoreFormat)
+	store := sessions.NewStore(storeConfig)
+	store.SetWriteBatching(time.Duration(cfg.SessionBatchMs) * time.Millisecond)
+	return store
+}
+
+// startSessionStore opens the configured session store for the bridge and starts idle
+// session eviction. The returned func stops eviction and closes the store.
+func startSessionStore(cfg *config.Config) (sessions.SessionBackend, func()) {
+	store := newSessionStore(cfg)
+	log.Printf("[Main] Session store configured: %s", cfg.SessionStorePath)
+	stopEviction := startSessionEviction(store, cfg.SessionTTL, time.Duration(cfg.SessionPruneInterval)*time.Second)
+	return store, func() {
+		stopEviction()
+		store.Close()
+	}
+}
+
+// openSessionStore opens the configured session store, falling back to sessions.json
+// when there is no complete bridge config
+func openSessionStore() sessions.SessionBackend {
+	if cfg, err := config.Load(); err == nil {
+		return newSessionStore(cfg)
+	}
+
+	dir, err := config.Dir()
+	if err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+	return sessions.NewStore(sessions.DefaultStoreConfig(filepath.Join(dir, "sessions.json")))
+}
+
+// lookupSession finds an entry by key as given, then by its normalized form
+func lookupSession(store sessions.SessionBackend, key string) (string, *sessions.SessionEntry, error) {
+	entry, err := store.GetEntry(key)
+	if err != nil || entry != nil {
+		return key, entry, err
+	}
+	normalized := sessions.NormalizeSessionKey(key)
+	entry, err = store.GetEntry(normalized)
+	return normalized, entry, err
+}
+
+// cmdSessionsShow pretty-prints a single session entry
+func cmdSessionsShow(key string) {
+	key, entry, err := lookupSession(openSessionStore(), key)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Failed to load sessions: %v\n", err)
+		os.Exit(1)
+	}
+	if entry == nil {
+		fmt.Fprintf(os.Stderr, "Session not found: %s\n", key)
+		os.Exit(1)
+	}
+
+	fmt.Print(formatSessionEntry(key, entry))
+}
+
+// cmdSessionsReset gives a session a fresh session ID (keeping its routing, agent and
+// label) or, with del, removes it from the store
+func cmdSessionsReset(key string, del bool) {
+	store := openSessionStore()
+	key, entry, err := lookupSession(store, key)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Failed to load sessions: %v\n", err)
+		os.Exit(1)
+	}
+	if entry == nil {
+		fmt.Fprintf(os.Stderr, "Session not found: %s\n", key)
+		os.Exit(1)
+	}
+
+	if del {
+		if _, err := store.DeleteEntry(key); err != nil {
+			fmt.Fprintf(os.Stderr, "Failed to delete session: %v\n", err)
+			os.Exit(1)
+		}
+		fmt.Printf("Deleted session %s\n", key)
+	} else {
+		sessionID := sessions.GenerateSessionID()
+		err := store.Update(func(entries map[string]*sessions.SessionEntry) error {
+			existing := entries[key]
+			if existing == nil {
+				return fmt.Errorf("session %s disappeared", key)
+			}
+			updated := *existing
+			updated.SessionID = sessionID
+			updated.UpdatedAt = time.Now().UnixMilli()
+			updated.SystemSent = false
+			updated.SessionFile = ""
+			entries[key] = &updated
+			return nil
+		})
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Failed to reset session: %v\n", err)
+			os.Exit(1)
+		}
+		fmt.Printf("Reset session %s (new session ID %s)\n", key, sessionID)
+	}
+
+	// The daemon rereads sessions.json when it changes, but keeps per-session state in memory
+	if dir, err := config.Dir(); err == nil && isRunning(filepath.Join(dir, "bridge.pid")) {
+		fmt.Println("Note: the running bridge sees the change on its next session lookup, but in-memory")
+		fmt.Println("state for this session (sequence numbers, buffered output) is only cleared by a restart")
+		fmt.Println("or by resetting through the bridge itself (/reset, session.reset).")
+	}
+}
+
+// formatSessionEntry renders all fields of a session entry, skipping empty ones
+func formatSessionEntry(key string, entry *sessions.SessionEntry) string {
+	out := fmt.Sprintf("Session: %s\n", key)
+	field := func(name, value string) {
+		if value != "" {
+			out += fmt.Sprintf("  %-18s %s\n", name+":", value)
+		}
+	}
+
+	field("Session ID", entry.SessionID)
+	if entry.UpdatedAt > 0 {
+		updated := time.UnixMilli(entry.UpdatedAt)
+		field("Updated", fmt.Sprintf("%s (%s ago)",
+			updated.Format("2006-01-02 15:04:05 MST"), time.Since(updated).Round(time.Second)))
+	}
+	field("Session file", entry.SessionFile)
+	field("Agent", entry.AgentID)
+	field("Label", entry.Label)
+
+	if dc := entry.DeliveryContext; dc != nil {
+		out += "  Delivery context:\n"
+		field("  Channel", dc.Channel)
+		field("  To", dc.To)
+		field("  Account", dc.AccountId)
+		field("  Thread", dc.ThreadId)
+	}
+
+	field("Last channel", entry.LastChannel)
+	field("Last to", entry.LastTo)
+	field("Last account", entry.LastAccountId)
+	field("Last thread", entry.LastThreadId)
+	field("Message ID", entry.WebhookMessageID)
+	field("Webhook session", entry.WebhookSessionID)
+	field("Thinking level", entry.ThinkingLevel)
+	field("Verbose level", entry.VerboseLevel)
+	field("Reasoning level", entry.ReasoningLevel)
+	field("Send policy", entry.SendPolicy)
+	field("Model override", entry.ModelOverride)
+	field("Provider override", entry.ProviderOverride)
+	if entry.SystemSent {
+		field("System sent", "yes")
+	}
+	if entry.AbortedLastRun {
+		field("Aborted last run", "yes")
+	}
+	return out
+}
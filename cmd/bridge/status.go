@@ -0,0 +1,137 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+// statusFileName is written next to the PID file by a running daemon
+const statusFileName = "bridge.status"
+
+// statusInterval is how often the daemon refreshes its status file
+const statusInterval = 5 * time.Second
+
+// daemonStatus is the content of the status file
+type daemonStatus struct {
+	PID               int       `json:"pid"`
+	StartedAt         time.Time `json:"startedAt"`
+	UpdatedAt         time.Time `json:"updatedAt"`
+	OpenClawConnected bool      `json:"openclawConnected"`
+	WebhookConnected  bool      `json:"webhookConnected"`
+}
+
+// startStatusWriter periodically records the connection states of a daemon in the status
+// file and returns a func that stops writing and removes it. It does nothing when not
+// running as a daemon.
+func startStatusWriter(openclawConnected, webhookConnected func() bool) func() {
+	pidPath := os.Getenv(pidFileEnv)
+	if pidPath == "" {
+		return func() {}
+	}
+	statusPath := filepath.Join(filepath.Dir(pidPath), statusFileName)
+
+	status := daemonStatus{PID: os.Getpid(), StartedAt: time.Now()}
+	write := func() {
+		status.UpdatedAt = time.Now()
+		status.OpenClawConnected = openclawConnected()
+		status.WebhookConnected = webhookConnected()
+		data, err := json.Marshal(status)
+		if err != nil {
+			return
+		}
+		if err := os.WriteFile(statusPath, data, 0644); err != nil {
+			log.Printf("[Main] Failed to write status file: %v", err)
+		}
+	}
+	write()
+
+	done := make(chan struct{})
+	stopped := make(chan struct{})
+	go func() {
+		defer close(stopped)
+		ticker := time.NewTicker(statusInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-done:
+				return
+			case <-ticker.C:
+				write()
+			}
+		}
+	}()
+
+	return func() {
+		close(done)
+		<-stopped
+		os.Remove(statusPath)
+	}
+}
+
+// readDaemonStatus returns the status file written by pid, or nil if it is missing,
+// belongs to another process or has gone stale
+func readDaemonStatus(dir string, pid int) *daemonStatus {
+	data, err := os.ReadFile(filepath.Join(dir, statusFileName))
+	if err != nil {
+		return nil
+	}
+	var status daemonStatus
+	if err := json.Unmarshal(data, &status); err != nil || status.PID != pid {
+		return nil
+	}
+	if time.Since(status.UpdatedAt) > 3*statusInterval {
+		return nil
+	}
+	return &status
+}
+
+func cmdStatus() {
+	dir, err := config.Dir()
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	pidPath := filepath.Join(dir, "bridge.pid")
+	pid, err := readPID(pidPath)
+	if err != nil || !isProcessRunning(pid) {
+		fmt.Println("Not running")
+		os.Exit(1)
+	}
+	fmt.Printf("Running (PID %d)\n", pid)
+
+	status := readDaemonStatus(dir, pid)
+	if status != nil {
+		fmt.Printf("Uptime:       %s\n", time.Since(status.StartedAt).Round(time.Second))
+	} else if info, err := os.Stat(pidPath); err == nil {
+		fmt.Printf("Uptime:       %s\n", time.Since(info.ModTime()).Round(time.Second))
+	}
+
+	// Report the config the way start/run resolve it (CLI > env > file)
+	if cfg, err := config.LoadWithOverrides(flags.overrides()); err == nil {
+		fmt.Printf("Webhook URL:  %s\n", cfg.WebhookURL)
+		fmt.Printf("Gateway host: %s\n", cfg.OpenClaw.GatewayHost)
+		fmt.Printf("Gateway port: %d\n", cfg.OpenClaw.GatewayPort)
+	} else {
+		fmt.Printf("Config:       %v\n", err)
+	}
+
+	if status != nil {
+		fmt.Printf("Gateway:      %s\n", connectionState(status.OpenClawConnected))
+		fmt.Printf("Webhook:      %s\n", connectionState(status.WebhookConnected))
+	} else {
+		fmt.Println("Connections:  unknown (no recent status file)")
+	}
+}
+
+func connectionState(connected bool) string {
+	if connected {
+		return "connected"
+	}
+	return "disconnected"
+}
@@ -0,0 +1,29 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/google/uuid"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+)
+
+// cmdRegenerateUID replaces the bridge UID in bridge.json, keeping all other settings
+func cmdRegenerateUID() {
+	uid := uuid.New().String()
+
+	path, err := config.UpdateBridgeConfig(map[string]string{"uid": uid})
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Failed to save new UID: %v\n", err)
+		os.Exit(1)
+	}
+
+	fmt.Printf("New Bridge UID: %s\n", uid)
+	fmt.Printf("Saved to %s\n", path)
+	fmt.Println("Clients paired with the old UID must be paired again.")
+
+	if dir, err := config.Dir(); err == nil && isRunning(filepath.Join(dir, "bridge.pid")) {
+		fmt.Println("Warning: the bridge is running with the old UID; run 'openclaw-bridge restart' to apply it.")
+	}
+}
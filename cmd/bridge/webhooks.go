@@ -0,0 +1,128 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/bridge"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/config"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/webhook"
+)
+
+// newWebhookClients creates a client for every configured webhook server (webhook_url
+// first), all passing inbound messages to handler and sharing the settings from cfg
+func newWebhookClients(cfg *config.Config, handler webhook.MessageHandler) []*webhook.Client {
+	clients := make([]*webhook.Client, len(cfg.Webhooks))
+	for i, target := range cfg.Webhooks {
+		clients[i] = newWebhookClient(cfg, target.URL, handler, webhookSpoolPath(cfg.WebhookSpoolPath, i))
+	}
+	return clients
+}
+
+// newWebhookClient creates a webhook client for url with the connection settings from cfg
+func newWebhookClient(cfg *config.Config, url string, handler webhook.MessageHandler, spoolPath string) *webhook.Client {
+	client := webhook.NewClient(
+		url,
+		handler,
+		cfg.UID, // Pass UID for message identification
+	)
+
+	client.SetClientIdentification(webhook.ClientIdentification(cfg.ClientIdentification))
+	if cfg.WebhookToken != "" {
+		client.SetAuthToken(cfg.WebhookToken)
+	}
+	client.SetKeepalive(
+		time.Duration(cfg.WebhookPingInterval)*time.Second,
+		time.Duration(cfg.WebhookIdleTimeout)*time.Second,
+	)
+	if cfg.WebhookSendQueue > 0 {
+		client.SetSendQueue(cfg.WebhookSendQueue)
+	}
+	if cfg.MaxMessageAge > 0 {
+		client.SetMaxQueuedAge(time.Duration(cfg.MaxMessageAge) * time.Second)
+	}
+	if cfg.WebhookSendIdleCheck > 0 {
+		client.SetIdleSendCheck(time.Duration(cfg.WebhookSendIdleCheck) * time.Second)
+	}
+	if cfg.WebhookSendTimeoutCeilingMs > 0 {
+		client.SetAdaptiveSendTimeout(
+			time.Duration(cfg.WebhookSendTimeoutFloorMs)*time.Millisecond,
+			time.Duration(cfg.WebhookSendTimeoutCeilingMs)*time.Millisecond,
+		)
+	}
+	if spoolPath != "" {
+		if err := client.SetSendSpool(spoolPath); err != nil {
+			log.Fatalf("[Main] Failed to open send spool: %v", err)
+		}
+	}
+	if err := client.SetTLSConfig(webhook.TLSConfig{CAFile: cfg.WebhookCAFile, RequireTLS: cfg.WebhookRequireTLS}); err != nil {
+		log.Fatalf("[Main] Invalid webhook TLS settings: %v", err)
+	}
+
+	// Announce ourselves to the webhook server on every (re)connect if configured
+	if registration := cfg.RegisterOnConnect; registration != nil {
+		client.SetOnConnect(func() {
+			if err := client.Send(registration); err != nil {
+				log.Printf("[Main] Failed to send registration message: %v", err)
+				return
+			}
+			log.Printf("[Main] Sent registration message to webhook server")
+		})
+	}
+	return client
+}
+
+// webhookSpoolPath gives the i-th webhook its own spool file next to the first one's
+// (outbound-spool.ndjson, outbound-spool-2.ndjson, ...)
+func webhookSpoolPath(base string, i int) string {
+	if base == "" || i == 0 {
+		return base
+	}
+	ext := filepath.Ext(base)
+	return fmt.Sprintf("%s-%d%s", strings.TrimSuffix(base, ext), i+1, ext)
+}
+
+// webhookTargets pairs the clients with their configured weights for the bridge fan-out
+func webhookTargets(cfg *config.Config, clients []*webhook.Client) []bridge.WebhookTarget {
+	targets := make([]bridge.WebhookTarget, len(clients))
+	for i, client := range clients {
+		targets[i] = bridge.WebhookTarget{
+			Name:   cfg.Webhooks[i].URL,
+			Sender: client,
+			Weight: cfg.Webhooks[i].Weight,
+		}
+	}
+	return targets
+}
+
+// connectWebhookClients connects every webhook client, exiting if one can't connect
+func connectWebhookClients(ctx context.Context, clients []*webhook.Client) {
+	for _, client := range clients {
+		if err := client.Connect(ctx); err != nil {
+			log.Fatalf("[Main] Failed to connect to Webhook server: %v", err)
+		}
+	}
+}
+
+// closeWebhookClients closes every webhook client
+func closeWebhookClients(clients []*webhook.Client) {
+	for _, client := range clients {
+		client.Close()
+	}
+}
+
+// webhookConnected reports whether any webhook client is connected
+func webhookConnected(clients []*webhook.Client) func() bool {
+	return func() bool {
+		for _, client := range clients {
+			if client.IsConnected() {
+				return true
+			}
+		}
+		return false
+	}
+}
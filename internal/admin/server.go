@@ -0,0 +1,219 @@
+// Package admin serves a local WebSocket endpoint for live introspection of a running
+// bridge: it streams bridge events and answers admin commands.
+package admin
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net"
+	"net/http"
+	"net/url"
+
+	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/bridge"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
+)
+
+// eventBuffer is how many events a slow admin client may fall behind before missing some
+const eventBuffer = 64
+
+// Server is the admin WebSocket endpoint.
+//
+// Every connected client receives {"type":"event","event":{...}} frames and may send
+// commands as {"id":"1","cmd":"sessions.list"}, {"cmd":"sessions.reset","sessionKey":"..."}
+// or {"cmd":"metrics"}. Each command is answered with a {"type":"result",...} frame
+// carrying the same id.
+type Server struct {
+	bridge   *bridge.Bridge
+	upgrader websocket.Upgrader
+	server   *http.Server
+	listener net.Listener
+}
+
+// command is a request sent by an admin client
+type command struct {
+	ID         string `json:"id,omitempty"`
+	Cmd        string `json:"cmd"`
+	SessionKey string `json:"sessionKey,omitempty"`
+}
+
+// result answers a command
+type result struct {
+	Type  string      `json:"type"`
+	ID    string      `json:"id,omitempty"`
+	Cmd   string      `json:"cmd"`
+	OK    bool        `json:"ok"`
+	Data  interface{} `json:"data,omitempty"`
+	Error string      `json:"error,omitempty"`
+}
+
+// eventFrame wraps a streamed bridge event
+type eventFrame struct {
+	Type  string             `json:"type"`
+	Event bridge.BridgeEvent `json:"event"`
+}
+
+// NewServer creates an admin server for the bridge
+func NewServer(b *bridge.Bridge) *Server {
+	s := &Server{bridge: b}
+	s.upgrader.CheckOrigin = allowLocalOrigin
+	return s
+}
+
+// ErrNotLoopback is returned by Start for an address other hosts could reach. The
+// endpoint has no authentication and can reset sessions, so it is local-only.
+var ErrNotLoopback = errors.New("admin endpoint must listen on a loopback address")
+
+// Start listens on addr and serves admin connections in the background.
+// An address without a host (":9090") is bound to localhost only; other hosts must be
+// loopback addresses or "localhost".
+func (s *Server) Start(addr string) error {
+	host, port, err := net.SplitHostPort(addr)
+	if err != nil {
+		return fmt.Errorf("invalid admin address %q: %w", addr, err)
+	}
+	if host == "" {
+		host = "127.0.0.1"
+	}
+	if ip := net.ParseIP(host); host != "localhost" && (ip == nil || !ip.IsLoopback()) {
+		return fmt.Errorf("%w, got %q", ErrNotLoopback, addr)
+	}
+
+	listener, err := net.Listen("tcp", net.JoinHostPort(host, port))
+	if err != nil {
+		return fmt.Errorf("failed to listen on %s: %w", addr, err)
+	}
+	s.listener = listener
+
+	mux := http.NewServeMux()
+	mux.HandleFunc("/", s.handleConn)
+	s.server = &http.Server{Handler: mux}
+
+	go func() {
+		if err := s.server.Serve(listener); err != nil && !errors.Is(err, http.ErrServerClosed) {
+			log.Printf("[Admin] Server stopped: %v", err)
+		}
+	}()
+	log.Printf("[Admin] Listening on ws://%s", listener.Addr())
+	return nil
+}
+
+// Addr returns the address the server is listening on
+func (s *Server) Addr() net.Addr {
+	return s.listener.Addr()
+}
+
+// Close stops the server and drops all admin connections
+func (s *Server) Close() error {
+	if s.server == nil {
+		return nil
+	}
+	return s.server.Close()
+}
+
+// allowLocalOrigin accepts non-browser clients and pages served from localhost
+func allowLocalOrigin(r *http.Request) bool {
+	origin := r.Header.Get("Origin")
+	if origin == "" {
+		return true
+	}
+	u, err := url.Parse(origin)
+	if err != nil {
+		return false
+	}
+	if u.Hostname() == "localhost" {
+		return true
+	}
+	ip := net.ParseIP(u.Hostname())
+	return ip != nil && ip.IsLoopback()
+}
+
+// handleConn serves one admin client until it disconnects
+func (s *Server) handleConn(w http.ResponseWriter, r *http.Request) {
+	conn, err := s.upgrader.Upgrade(w, r, nil)
+	if err != nil {
+		log.Printf("[Admin] Upgrade failed: %v", err)
+		return
+	}
+	defer conn.Close()
+
+	events, unsubscribe := s.bridge.Subscribe(eventBuffer)
+	defer unsubscribe()
+
+	// All writes go through this goroutine; gorilla/websocket allows one writer at a time
+	out := make(chan interface{}, eventBuffer)
+	done := make(chan struct{})
+	writerDone := make(chan struct{})
+	defer close(done)
+	go func() {
+		defer close(writerDone)
+		for {
+			var frame interface{}
+			select {
+			case ev, ok := <-events:
+				if !ok {
+					return
+				}
+				frame = eventFrame{Type: "event", Event: ev}
+			case frame = <-out:
+			case <-done:
+				return
+			}
+			if err := conn.WriteJSON(frame); err != nil {
+				conn.Close()
+				return
+			}
+		}
+	}()
+
+	for {
+		_, data, err := conn.ReadMessage()
+		if err != nil {
+			return
+		}
+
+		var res result
+		var cmd command
+		if err := json.Unmarshal(data, &cmd); err != nil {
+			res = result{Type: "result", Error: fmt.Sprintf("invalid command: %v", err)}
+		} else {
+			res = s.run(cmd)
+		}
+
+		select {
+		case out <- res:
+		case <-writerDone:
+			return
+		}
+	}
+}
+
+// run executes an admin command
+func (s *Server) run(cmd command) result {
+	res := result{Type: "result", ID: cmd.ID, Cmd: cmd.Cmd}
+
+	var err error
+	switch cmd.Cmd {
+	case "sessions.list":
+		res.Data, err = s.bridge.Sessions()
+	case "sessions.reset":
+		if cmd.SessionKey == "" {
+			err = fmt.Errorf("sessionKey is required")
+		} else {
+			err = s.bridge.ResetSession(cmd.SessionKey)
+		}
+	case "metrics":
+		res.Data = metrics.Default.Snapshot()
+	default:
+		err = fmt.Errorf("unknown command: %s", cmd.Cmd)
+	}
+
+	if err != nil {
+		res.Error = err.Error()
+	} else {
+		res.OK = true
+	}
+	return res
+}
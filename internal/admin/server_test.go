@@ -0,0 +1,89 @@
+package admin
+
+import (
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/bridge"
+)
+
+// frame is any frame the admin endpoint sends
+type frame struct {
+	Type  string             `json:"type"`
+	Cmd   string             `json:"cmd"`
+	OK    bool               `json:"ok"`
+	Event bridge.BridgeEvent `json:"event"`
+}
+
+func TestStreamsProcessedMessages(t *testing.T) {
+	b := bridge.NewBridge(nil, nil)
+	srv := httptest.NewServer(http.HandlerFunc(NewServer(b).handleConn))
+	defer srv.Close()
+
+	conn, _, err := websocket.DefaultDialer.Dial("ws"+strings.TrimPrefix(srv.URL, "http"), nil)
+	if err != nil {
+		t.Fatalf("Dial: %v", err)
+	}
+	defer conn.Close()
+	conn.SetReadDeadline(time.Now().Add(5 * time.Second))
+
+	// The connection is subscribed to events before any command is answered
+	if err := conn.WriteJSON(command{ID: "1", Cmd: "metrics"}); err != nil {
+		t.Fatalf("WriteJSON: %v", err)
+	}
+	var res frame
+	if err := conn.ReadJSON(&res); err != nil || res.Type != "result" || !res.OK {
+		t.Fatalf("metrics command = %+v, %v, want an ok result", res, err)
+	}
+
+	if _, err := b.ProcessMessage([]byte(`{"id":"m1","content":""}`)); err != nil {
+		t.Fatalf("ProcessMessage: %v", err)
+	}
+	var ev frame
+	if err := conn.ReadJSON(&ev); err != nil {
+		t.Fatalf("ReadJSON: %v", err)
+	}
+	if ev.Type != "event" || ev.Event.Type != bridge.EventMessageProcessed || ev.Event.Outcome != string(bridge.OutcomeSkipped) {
+		t.Errorf("got %+v, want a %s event with outcome %s", ev, bridge.EventMessageProcessed, bridge.OutcomeSkipped)
+	}
+}
+
+func TestStartOnlyListensOnLoopback(t *testing.T) {
+	tests := []struct {
+		name    string
+		addr    string
+		wantErr error
+	}{
+		{name: "port only binds localhost", addr: ":0"},
+		{name: "loopback IPv4", addr: "127.0.0.1:0"},
+		{name: "localhost", addr: "localhost:0"},
+		{name: "all interfaces", addr: "0.0.0.0:0", wantErr: ErrNotLoopback},
+		{name: "unspecified IPv6", addr: "[::]:0", wantErr: ErrNotLoopback},
+		{name: "other host", addr: "example.com:0", wantErr: ErrNotLoopback},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := NewServer(bridge.NewBridge(nil, nil))
+			err := s.Start(tt.addr)
+			if !errors.Is(err, tt.wantErr) {
+				t.Fatalf("Start(%q) error = %v, want %v", tt.addr, err, tt.wantErr)
+			}
+			if err != nil {
+				return
+			}
+			defer s.Close()
+
+			conn, _, err := websocket.DefaultDialer.Dial("ws://"+s.Addr().String(), nil)
+			if err != nil {
+				t.Fatalf("Dial %s: %v", s.Addr(), err)
+			}
+			conn.Close()
+		})
+	}
+}
@@ -0,0 +1,68 @@
+package bridge
+
+import (
+	"bytes"
+	"sync"
+	"time"
+)
+
+// outboundBatcher groups outbound messages into a single webhook frame holding a JSON
+// array. A batch is sent once it holds maxCount messages or maxDelay after its first
+// message, whichever comes first.
+type outboundBatcher struct {
+	maxCount int
+	maxDelay time.Duration
+	send     func([]byte)
+
+	// mu is held while sending so batches go out in order
+	mu      sync.Mutex
+	pending [][]byte
+	timer   *time.Timer
+}
+
+func newOutboundBatcher(maxCount int, maxDelay time.Duration, send func([]byte)) *outboundBatcher {
+	return &outboundBatcher{
+		maxCount: maxCount,
+		maxDelay: maxDelay,
+		send:     send,
+	}
+}
+
+// add queues a message, sending the batch when it is full
+func (o *outboundBatcher) add(data []byte) {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+
+	o.pending = append(o.pending, data)
+	if o.maxCount > 0 && len(o.pending) >= o.maxCount {
+		o.flushLocked()
+		return
+	}
+	if o.timer == nil {
+		o.timer = time.AfterFunc(o.maxDelay, o.flush)
+	}
+}
+
+// flush sends the pending batch, if any
+func (o *outboundBatcher) flush() {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+	o.flushLocked()
+}
+
+func (o *outboundBatcher) flushLocked() {
+	if o.timer != nil {
+		o.timer.Stop()
+		o.timer = nil
+	}
+	if len(o.pending) == 0 {
+		return
+	}
+
+	frame := make([]byte, 0, 2+len(o.pending)*128)
+	frame = append(frame, '[')
+	frame = append(frame, bytes.Join(o.pending, []byte{','})...)
+	frame = append(frame, ']')
+	o.pending = nil
+	o.send(frame)
+}
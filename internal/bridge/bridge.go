@@ -7,25 +7,159 @@ import (
 	"strings"
 	"time"
 
+	"github.com/sternelee/openclaw-webhook-bridge/internal/capture"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/commands"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/openclaw"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/schema"
 	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
-	"github.com/sternelee/openclaw-webhook-bridge/internal/webhook"
 )
 
+// UnknownEventMode controls what happens to gateway events with an unrecognized type
+type UnknownEventMode string
+
+const (
+	UnknownEventsForward UnknownEventMode = "forward"  // Send the raw event to the webhook
+	UnknownEventsDrop    UnknownEventMode = "drop"     // Discard silently (default)
+	UnknownEventsLogOnly UnknownEventMode = "log-only" // Discard and log the event type
+)
+
+// UnknownSessionMode controls what happens to gateway events for sessions missing from the store
+type UnknownSessionMode string
+
+const (
+	UnknownSessionsForward    UnknownSessionMode = "forward"    // Deliver them anyway (default)
+	UnknownSessionsDrop       UnknownSessionMode = "drop"       // Discard and log the session key
+	UnknownSessionsQuarantine UnknownSessionMode = "quarantine" // Discard and append the raw event to a file
+)
+
+// MissingSessionKeyMode controls what happens to agent/chat events that carry no sessionKey
+type MissingSessionKeyMode string
+
+const (
+	MissingSessionKeyForward  MissingSessionKeyMode = "forward"  // Send them with an empty session (default)
+	MissingSessionKeyDrop     MissingSessionKeyMode = "drop"     // Discard and log
+	MissingSessionKeyFallback MissingSessionKeyMode = "fallback" // Send them under a fixed fallback session key
+)
+
+// ReasoningMode controls what happens to the agent "reasoning" stream
+type ReasoningMode string
+
+const (
+	ReasoningSuppress ReasoningMode = "suppress" // Discard reasoning text (default)
+	ReasoningThinking ReasoningMode = "thinking" // Send it as a separate "thinking" message type
+	ReasoningProgress ReasoningMode = "progress" // Send it as ordinary "progress" messages
+)
+
+// GatewayClient is the part of the OpenClaw gateway client the bridge sends through,
+// e.g. a *openclaw.Client
+type GatewayClient interface {
+	commands.GatewayClient
+	AgentID() string
+	SendAgentRequestTo(agentID, message, sessionKey string) error
+	SendAgentRequestWithOptions(agentID, message, sessionKey string, opts openclaw.AgentRequestOptions) error
+}
+
 // Bridge is a simple passthrough between Webhook and OpenClaw with session management
 type Bridge struct {
-	webhookClient  *webhook.Client
-	clawdbotClient *openclaw.Client
+	webhookClient  WebhookSender
+	clawdbotClient GatewayClient
 	commandHandler *commands.CommandHandler
 	agentID        string
 	uid            string // Unique ID for this bridge instance
-	sessionStore   *sessions.Store
+	sessionStore   sessions.SessionBackend
 	sessionScope   sessions.SessionScope
+
+	// preferPeerSession derives the key from peer info even when an explicit session is given
+	preferPeerSession bool
+
+	// maxSessionKeyLength caps resolved session keys, hashing the tail of longer ones (0 = unlimited)
+	maxSessionKeyLength int
+
+	// dropWhitespaceDeltas skips streaming deltas that contain only whitespace
+	dropWhitespaceDeltas bool
+
+	// Per-direction message logging
+	webhookLog  *directionLogger
+	openclawLog *directionLogger
+
+	// logTruncate caps logged message content in bytes (0 = unlimited)
+	logTruncate int
+
+	// events streams BridgeEvents to subscribers (see Subscribe)
+	events eventBus
+
+	// seq adds a per-session "seq" field to outbound messages (nil = disabled)
+	seq *sequencer
+
+	// coalescer batches streaming progress per session kind (nil = disabled)
+	coalescer *coalescer
+
+	// batcher groups outbound messages into JSON-array frames (nil = disabled)
+	batcher *outboundBatcher
+
+	// fanout delivers outbound frames to several webhooks (nil = webhookClient only)
+	fanout *webhookFanout
+
+	// watchdog notifies users when the gateway goes quiet (nil = disabled)
+	watchdog *responseWatchdog
+
+	// resetWipesRouting makes resets drop delivery/route fields along with the session ID
+	resetWipesRouting bool
+
+	// resetKeepsLabel carries a session's label over to the fresh session on reset
+	resetKeepsLabel bool
+
+	// resetConfirmation is sent to the webhook after a bare reset trigger (empty = none)
+	resetConfirmation string
+
+	// resetConfirm holds bare reset triggers until they are repeated (nil = reset right away)
+	resetConfirm *resetConfirmer
+
+	// replayHistory is how many forwarded prompts each session keeps for replay (0 = none)
+	replayHistory int
+
+	// preamble is prepended to the first prompt of each new session (empty = none)
+	preamble string
+
+	// requestContext lists the message fields sent to the gateway as a "context" object (nil = none)
+	requestContext []string
+
+	// echoMode sends every inbound message straight back instead of to the gateway
+	echoMode bool
+
+	// converters reformat replies by the inbound message type (see RegisterReplyConverter)
+	converters replyConverters
+
+	// dedup skips webhook messages whose ID was recently processed (nil = disabled)
+	dedup *dedupCache
+
+	// unknownEvents decides the fate of unrecognized gateway event types
+	unknownEvents UnknownEventMode
+
+	// unknownSessions decides the fate of events for sessions the store has no record of;
+	// quarantine receives them in quarantine mode (empty mode = forward)
+	unknownSessions UnknownSessionMode
+	quarantine      *capture.Recorder
+
+	// reasoning decides the fate of the agent "reasoning" stream (empty = suppress)
+	reasoning ReasoningMode
+
+	// missingSessionKey decides the fate of agent/chat events without a sessionKey;
+	// fallbackSessionKey is used in fallback mode (empty mode = forward)
+	missingSessionKey  MissingSessionKeyMode
+	fallbackSessionKey string
+
+	// eventAllowlist holds the gateway event types that may reach the webhook (nil = all)
+	eventAllowlist map[string]bool
+
+	// Inbound schema validation (nil schema = disabled)
+	schema         *schema.Schema
+	dropInvalidMsg bool
 }
 
 // NewBridge creates a new bridge
-func NewBridge(webhookClient *webhook.Client, clawdbotClient *openclaw.Client) *Bridge {
+func NewBridge(webhookClient WebhookSender, clawdbotClient GatewayClient) *Bridge {
 	agentID := ""
 	if clawdbotClient != nil {
 		agentID = clawdbotClient.AgentID()
@@ -33,19 +167,34 @@ func NewBridge(webhookClient *webhook.Client, clawdbotClient *openclaw.Client) *
 	// Create command handler with openclaw client as gateway client
 	cmdHandler := commands.NewCommandHandler(clawdbotClient)
 	return &Bridge{
-		webhookClient:  webhookClient,
-		clawdbotClient: clawdbotClient,
-		commandHandler: cmdHandler,
-		agentID:        agentID,
-		sessionScope:   sessions.SessionScopePerSender, // Default
+		webhookClient:     webhookClient,
+		clawdbotClient:    clawdbotClient,
+		commandHandler:    cmdHandler,
+		agentID:           agentID,
+		sessionScope:      sessions.SessionScopePerSender, // Default
+		unknownEvents:     UnknownEventsDrop,
+		eventAllowlist:    map[string]bool{"agent": true, "chat": true},
+		resetConfirmation: DefaultResetConfirmation,
+		resetKeepsLabel:   true,
+		dedup:             newDedupCache(DefaultDedupSize, DefaultDedupTTL),
+		webhookLog:        newDirectionLogger("Webhook -> OpenClaw"),
+		openclawLog:       newDirectionLogger("OpenClaw -> Webhook"),
 	}
 }
 
 // SetWebhookClient sets the webhook client after construction
-func (b *Bridge) SetWebhookClient(client *webhook.Client) {
+func (b *Bridge) SetWebhookClient(client WebhookSender) {
 	b.webhookClient = client
 }
 
+// SetWebhookFanout delivers outbound frames to all of targets instead of the webhook
+// client, broadcasting each frame or balancing them by weight depending on mode.
+// Inbound messages from every target should be passed to HandleWebhookMessage.
+func (b *Bridge) SetWebhookFanout(targets []WebhookTarget, mode WebhookDeliveryMode) {
+	b.fanout = newWebhookFanout(targets, mode)
+	log.Printf("[Bridge] Delivering to %d webhooks (%s)", len(targets), mode)
+}
+
 // SetUID sets the unique ID for this bridge
 func (b *Bridge) SetUID(uid string) {
 	b.uid = uid
@@ -53,8 +202,9 @@ func (b *Bridge) SetUID(uid string) {
 }
 
 // SetSessionStore configures the session store
-func (b *Bridge) SetSessionStore(store *sessions.Store) {
+func (b *Bridge) SetSessionStore(store sessions.SessionBackend) {
 	b.sessionStore = store
+	b.commandHandler.SetSessionLister(b)
 	log.Printf("[Bridge] Session store configured")
 }
 
@@ -64,6 +214,319 @@ func (b *Bridge) SetSessionScope(scope sessions.SessionScope) {
 	log.Printf("[Bridge] Session scope set to: %s", scope)
 }
 
+// SetPreferPeerSession makes peer info (peerKind/peerId etc.) take precedence over an explicit
+// session in the message; the explicit session is then only used when there is no peer info
+func (b *Bridge) SetPreferPeerSession(prefer bool) {
+	b.preferPeerSession = prefer
+	log.Printf("[Bridge] Prefer peer info over explicit session: %v", prefer)
+}
+
+// SetMaxSessionKeyLength caps the length of resolved session keys; longer keys (e.g. built
+// from long peer or thread IDs) keep their prefix and get a stable hash of the tail.
+// 0 disables the limit; limits below sessions.MinSessionKeyLength are raised to it.
+func (b *Bridge) SetMaxSessionKeyLength(limit int) {
+	b.maxSessionKeyLength = limit
+}
+
+// SetDropWhitespaceDeltas controls whether whitespace-only streaming deltas are forwarded
+func (b *Bridge) SetDropWhitespaceDeltas(drop bool) {
+	b.dropWhitespaceDeltas = drop
+	log.Printf("[Bridge] Drop whitespace-only deltas: %v", drop)
+}
+
+// SetMessageLogging configures how relayed messages are logged in each direction.
+// sampleEvery > 1 logs only every Nth message; failures are always logged.
+func (b *Bridge) SetMessageLogging(webhookLevel, openclawLevel MessageLogLevel, sampleEvery int) {
+	b.webhookLog.configure(webhookLevel, sampleEvery)
+	b.openclawLog.configure(openclawLevel, sampleEvery)
+	log.Printf("[Bridge] Message logging: webhook=%s, openclaw=%s, sample every %d",
+		webhookLevel, openclawLevel, b.webhookLog.every)
+}
+
+// SetLogMinInterval limits byte-count message logs to one per interval and direction,
+// summarizing the messages in between (0 = log every message)
+func (b *Bridge) SetLogMinInterval(interval time.Duration) {
+	b.webhookLog.minInterval = interval
+	b.openclawLog.minInterval = interval
+	if interval > 0 {
+		log.Printf("[Bridge] Message size logs limited to one per %v per direction", interval)
+	}
+}
+
+// SetLogTruncation caps how much of any message content is written to the log, in bytes
+// (0 = no truncation). Longer content is cut at a UTF-8 boundary and marked with the
+// number of bytes left out.
+func (b *Bridge) SetLogTruncation(maxBytes int) {
+	b.logTruncate = maxBytes
+	b.webhookLog.truncate = maxBytes
+	b.openclawLog.truncate = maxBytes
+	log.Printf("[Bridge] Logged content truncated to %d bytes (0 = unlimited)", maxBytes)
+}
+
+// SetSequenceNumbers enables per-session "seq" fields on outbound progress/complete/error messages
+func (b *Bridge) SetSequenceNumbers(enabled bool) {
+	if enabled {
+		b.seq = newSequencer()
+	} else {
+		b.seq = nil
+	}
+	log.Printf("[Bridge] Outbound sequence numbers: %v", enabled)
+}
+
+// SetMessageDedup sets how many recent webhook message IDs are remembered, and for how
+// long, to skip redelivered duplicates. size 0 disables deduplication; ttl 0 keeps IDs
+// until they are evicted by newer ones.
+func (b *Bridge) SetMessageDedup(size int, ttl time.Duration) {
+	if size <= 0 {
+		b.dedup = nil
+		log.Printf("[Bridge] Inbound message deduplication disabled")
+		return
+	}
+	b.dedup = newDedupCache(size, ttl)
+	log.Printf("[Bridge] Inbound message deduplication: last %d IDs, TTL %v", size, ttl)
+}
+
+// SetCoalescing configures progress coalescing windows keyed by session kind
+// (CoalesceDM, CoalesceGroup, CoalesceChannel, CoalesceGlobal, CoalesceDefault).
+// Coalescing stays off unless at least one window has a positive interval.
+func (b *Bridge) SetCoalescing(windows map[string]CoalesceWindow) {
+	if !coalesceEnabled(windows) {
+		b.coalescer = nil
+		return
+	}
+	b.coalescer = newCoalescer(windows, b.sendToWebhook)
+	for kind, w := range windows {
+		log.Printf("[Bridge] Coalescing %s sessions: interval=%v, max buffer=%d", kind, w.Interval, w.MaxBuffer)
+	}
+}
+
+// SetOutboundBatching groups converted events into JSON-array frames of up to maxCount
+// messages, sent at most maxDelay after the first one. A zero maxDelay disables batching.
+func (b *Bridge) SetOutboundBatching(maxCount int, maxDelay time.Duration) {
+	if maxDelay <= 0 {
+		b.batcher = nil
+		return
+	}
+	b.batcher = newOutboundBatcher(maxCount, maxDelay, b.writeToWebhook)
+	log.Printf("[Bridge] Outbound batching: max %d messages, max delay %v", maxCount, maxDelay)
+}
+
+// SetResponseTimeout notifies a session when the gateway sends nothing for it after a prompt:
+// a "still working" progress message after noticeAfter (0 = none) and a single error after timeout.
+// A zero timeout disables the watchdog.
+func (b *Bridge) SetResponseTimeout(noticeAfter, timeout time.Duration) {
+	if timeout <= 0 {
+		b.watchdog = nil
+		return
+	}
+	b.watchdog = newResponseWatchdog(noticeAfter, timeout, func(msgType, content, sessionKey string) {
+		log.Printf("[Bridge] Gateway quiet on session %s, sending %s", sessionKey, msgType)
+		b.deliverToWebhook(msgType, sessionKey, b.buildWebhookResponse(msgType, content, sessionKey))
+	})
+	log.Printf("[Bridge] Response timeout: notice after %v, give up after %v", noticeAfter, timeout)
+}
+
+// SetResetPreservesRouting controls whether session resets keep delivery context and
+// last-route fields (the default) so replies still route, or wipe the entry entirely
+func (b *Bridge) SetResetPreservesRouting(preserve bool) {
+	b.resetWipesRouting = !preserve
+	log.Printf("[Bridge] Session reset preserves routing: %v", preserve)
+}
+
+// SetEchoMode makes the bridge answer each inbound message with a "complete" message
+// echoing its content, without contacting the gateway. For testing the webhook link.
+func (b *Bridge) SetEchoMode(enabled bool) {
+	b.echoMode = enabled
+	if enabled {
+		log.Printf("[Bridge] Echo mode enabled: inbound messages are echoed back, the gateway is not used")
+	}
+}
+
+// SetResetPreservesLabel controls whether a session's label survives a reset (the default)
+func (b *Bridge) SetResetPreservesLabel(preserve bool) {
+	b.resetKeepsLabel = preserve
+	log.Printf("[Bridge] Session reset preserves label: %v", preserve)
+}
+
+// DefaultResetConfirmation is the reply to a bare reset trigger unless configured otherwise
+const DefaultResetConfirmation = "New session started."
+
+// SetResetConfirmation sets the reply sent when a message is only a reset trigger
+// (empty = reset silently)
+func (b *Bridge) SetResetConfirmation(text string) {
+	b.resetConfirmation = text
+	log.Printf("[Bridge] Reset confirmation: %q", text)
+}
+
+// SetResetConfirmWindow makes a message that is only a reset trigger ask for confirmation:
+// the session is reset only if the trigger is sent again within window (0 = reset right away)
+func (b *Bridge) SetResetConfirmWindow(window time.Duration) {
+	if window <= 0 {
+		b.resetConfirm = nil
+		return
+	}
+	b.resetConfirm = newResetConfirmer(window)
+	log.Printf("[Bridge] Bare reset triggers need confirmation within %v", window)
+}
+
+// SetReplayHistory records the last n prompts of each session so they can be re-sent with
+// session.replay or /replay. This stores message content in the session store.
+func (b *Bridge) SetReplayHistory(n int) {
+	b.replayHistory = n
+	if n > 0 {
+		b.commandHandler.SetReplayer(b)
+	}
+	log.Printf("[Bridge] Replay history: %d prompt(s) per session", n)
+}
+
+// SetSessionPreamble sets text prepended to the first message of every new session. A
+// session is primed once (tracked by its SystemSent flag) until it is reset, and only
+// when a session store is configured.
+func (b *Bridge) SetSessionPreamble(preamble string) {
+	b.preamble = preamble
+	log.Printf("[Bridge] Session preamble: %d bytes", len(preamble))
+}
+
+// SetRequestContext sends the named message fields (see messageContext) with every agent
+// request as a "context" object. The gateway must accept the extra parameter.
+func (b *Bridge) SetRequestContext(fields []string) {
+	b.requestContext = fields
+	log.Printf("[Bridge] Request context fields: %s", strings.Join(fields, ", "))
+}
+
+// messageContext builds the request context for a message from the configured fields,
+// leaving out empty values. It returns nil when nothing is configured or set.
+func (b *Bridge) messageContext(msg *WebhookMessage) map[string]string {
+	if len(b.requestContext) == 0 {
+		return nil
+	}
+	values := map[string]string{
+		"messageId": msg.ID,
+		"senderId":  msg.SenderID,
+		"chatId":    msg.ChatID,
+		"chatType":  msg.ChatType,
+		"peerKind":  msg.PeerKind,
+		"peerId":    msg.PeerID,
+		"topicId":   msg.TopicID,
+		"threadId":  msg.ThreadID,
+		"bridgeUid": b.uid,
+	}
+	context := make(map[string]string, len(b.requestContext))
+	for _, field := range b.requestContext {
+		if value := values[field]; value != "" {
+			context[field] = value
+		}
+	}
+	if len(context) == 0 {
+		return nil
+	}
+	return context
+}
+
+// SetUnknownEventMode sets how unrecognized gateway event types are handled
+func (b *Bridge) SetUnknownEventMode(mode UnknownEventMode) {
+	b.unknownEvents = mode
+	log.Printf("[Bridge] Unrecognized gateway events: %s", mode)
+}
+
+// SetUnknownSessionMode sets how gateway events for sessions missing from the store are
+// handled. quarantine is only used (and required) in UnknownSessionsQuarantine mode.
+func (b *Bridge) SetUnknownSessionMode(mode UnknownSessionMode, quarantine *capture.Recorder) {
+	b.unknownSessions = mode
+	b.quarantine = quarantine
+	log.Printf("[Bridge] Responses for unknown sessions: %s", mode)
+}
+
+// SetMissingSessionKeyMode sets how agent/chat events without a sessionKey are handled.
+// fallbackKey is only used in MissingSessionKeyFallback mode.
+func (b *Bridge) SetMissingSessionKeyMode(mode MissingSessionKeyMode, fallbackKey string) {
+	b.missingSessionKey = mode
+	b.fallbackSessionKey = fallbackKey
+	if mode == MissingSessionKeyFallback {
+		log.Printf("[Bridge] Events without a sessionKey: sent to session %s", fallbackKey)
+	} else {
+		log.Printf("[Bridge] Events without a sessionKey: %s", mode)
+	}
+}
+
+// eventSessionKey applies the missing-sessionKey policy to an agent/chat event's key and
+// reports whether the event should still be converted
+func (b *Bridge) eventSessionKey(eventType, sessionKey string) (string, bool) {
+	if sessionKey != "" {
+		return sessionKey, true
+	}
+	switch b.missingSessionKey {
+	case MissingSessionKeyDrop:
+		log.Printf("[Bridge] Dropping %s event without a sessionKey", eventType)
+		return "", false
+	case MissingSessionKeyFallback:
+		return b.fallbackSessionKey, true
+	}
+	return "", true
+}
+
+// isUnknownSession reports whether the session store has no entry for sessionKey.
+// Store errors count as known so a read failure never swallows a response.
+func (b *Bridge) isUnknownSession(sessionKey string) bool {
+	if b.sessionStore == nil {
+		return false
+	}
+	entry, err := b.sessionStore.GetEntry(sessionKey)
+	return err == nil && entry == nil
+}
+
+// holdUnknownSessionEvent drops or quarantines an event for an unknown session, reporting
+// false when the event should be delivered as usual
+func (b *Bridge) holdUnknownSessionEvent(sessionKey string, data []byte) bool {
+	if b.unknownSessions != UnknownSessionsDrop && b.unknownSessions != UnknownSessionsQuarantine {
+		return false
+	}
+	if sessionKey == "" || !b.isUnknownSession(sessionKey) {
+		return false
+	}
+
+	if b.unknownSessions == UnknownSessionsQuarantine && b.quarantine != nil {
+		if err := b.quarantine.Write(data); err != nil {
+			log.Printf("[Bridge] Failed to quarantine event for unknown session %s: %v", sessionKey, err)
+		}
+	}
+	log.Printf("[Bridge] Event for unknown session %s not delivered (%s)", sessionKey, b.unknownSessions)
+	return true
+}
+
+// SetReasoningMode sets how the agent "reasoning" stream is forwarded
+func (b *Bridge) SetReasoningMode(mode ReasoningMode) {
+	b.reasoning = mode
+	log.Printf("[Bridge] Reasoning stream: %s", mode)
+}
+
+// SetEventAllowlist restricts which gateway event types are forwarded. For gateway
+// event frames ({"type":"event"}) the event name is matched, otherwise the top-level type.
+// An entry of "*" allows everything.
+func (b *Bridge) SetEventAllowlist(types []string) {
+	allow := make(map[string]bool, len(types))
+	for _, t := range types {
+		if t == "*" {
+			b.eventAllowlist = nil
+			log.Printf("[Bridge] Forwarding all gateway event types")
+			return
+		}
+		allow[t] = true
+	}
+	b.eventAllowlist = allow
+	log.Printf("[Bridge] Gateway event allowlist: %v", types)
+}
+
+// SetSchemaValidation validates inbound webhook messages against s. Failing messages
+// are dropped when drop is true, otherwise forwarded with a warning. A nil schema disables validation.
+func (b *Bridge) SetSchemaValidation(s *schema.Schema, drop bool) {
+	b.schema = s
+	b.dropInvalidMsg = drop
+	if s != nil {
+		log.Printf("[Bridge] Webhook schema validation enabled (drop invalid: %v)", drop)
+	}
+}
+
 // WebhookMessage represents a message from the webhook
 // Optimized to parse JSON once with json.RawMessage for deferred parsing
 type WebhookMessage struct {
@@ -78,15 +541,55 @@ type WebhookMessage struct {
 	TopicID  string `json:"topicId"`
 	ThreadID string `json:"threadId"`
 	Type     string `json:"type,omitempty"` // For control messages
+
+	// Explicit reply target, overriding the peer/message ID in the delivery context
+	ReplyTo   string `json:"replyTo,omitempty"`
+	DeliverTo string `json:"deliverTo,omitempty"` // Alias of ReplyTo
 }
 
-// HandleWebhookMessage handles a message from the webhook and forwards to OpenClaw
+// HandleWebhookMessage handles a message from the webhook and forwards to OpenClaw.
+// It runs on the webhook client's read loop and returns before the next frame is read,
+// so a flood is held back by the connection itself rather than piling up goroutines.
 func (b *Bridge) HandleWebhookMessage(data []byte) error {
-	log.Printf("[Bridge] Webhook -> OpenClaw: %s", string(data))
+	_, err := b.ProcessMessage(data)
+	return err
+}
+
+// ProcessMessage handles a message from the webhook like HandleWebhookMessage and
+// reports what was done with it, for embedders and custom flows
+func (b *Bridge) ProcessMessage(data []byte) (ProcessOutcome, error) {
+	outcome, err := b.processMessage(data)
+
+	ev := BridgeEvent{
+		Type:       EventMessageProcessed,
+		SessionKey: outcome.SessionKey,
+		Outcome:    string(outcome.Kind),
+		Reason:     outcome.Reason,
+	}
+	if err != nil {
+		ev.Error = err.Error()
+	}
+	b.publish(ev)
+	return outcome, err
+}
+
+func (b *Bridge) processMessage(data []byte) (ProcessOutcome, error) {
+	b.webhookLog.log(data)
+	metrics.Inc(metrics.MessagesTotal)
+
+	if b.schema != nil {
+		if err := b.schema.Validate(data); err != nil {
+			if b.dropInvalidMsg {
+				log.Printf("[Bridge] Dropping webhook message that fails schema validation: %v", err)
+				return skipped("schema validation failed: " + err.Error()), nil
+			}
+			log.Printf("[Bridge] Warning: webhook message fails schema validation: %v", err)
+		}
+	}
 
 	// Check for session control messages first
 	if sessions.IsSessionControlMessage(data) {
-		return b.handleSessionControlMessage(data)
+		return ProcessOutcome{Kind: OutcomeControl}, b.handleSessionControlMessage(data)
 	}
 
 	// Parse the message once - all fields are extracted in one pass
@@ -94,69 +597,98 @@ func (b *Bridge) HandleWebhookMessage(data []byte) error {
 	if err := json.Unmarshal(data, &msg); err != nil {
 		log.Printf("[Bridge] Failed to parse webhook message: %v", err)
 		// Skip unparseable messages
-		return nil
+		return skipped("unparseable message"), nil
 	}
 
 	// Skip control messages like "connected", "error", "event"
 	if msg.Type == "connected" || msg.Type == "error" || msg.Type == "event" {
 		log.Printf("[Bridge] Skipping control message: type=%s", msg.Type)
-		return nil
+		return skipped("message type " + msg.Type), nil
+	}
+
+	// Skip redeliveries of a message that was already handled
+	if b.dedup != nil && msg.ID != "" && b.dedup.Seen(msg.ID) {
+		log.Printf("[Bridge] Skipping duplicate message: id=%s", msg.ID)
+		return skipped("duplicate message id"), nil
 	}
 
 	// Skip empty messages
 	if msg.Content == "" {
 		log.Printf("[Bridge] Skipping empty message")
-		return nil
-	}
-
-	// Check if this is a command (starts with /)
-	if commands.IsCommand(msg.Content) {
-		return b.handleCommand(msg.Content, msg.Session, msg.ID)
+		return skipped("empty content"), nil
 	}
 
 	// Resolve session key using session scope
 	webhookMsg := &sessions.WebhookMessage{
-		ID:      msg.ID,
-		Content: msg.Content,
-		Session: msg.Session,
+		ID:       msg.ID,
+		Content:  msg.Content,
+		Session:  msg.Session,
+		ChatID:   msg.ChatID,
+		ChatType: msg.ChatType,
 	}
 
 	// Extract peer info with optimized string handling
 	sessionKey := b.resolveSessionKey(&msg, webhookMsg)
 
 	log.Printf("[Bridge] Resolved session key: %s (scope: %s)", sessionKey, b.sessionScope)
+	b.converters.noteInboundType(sessionKey, msg.Type)
+
+	// Echo mode answers every message itself to test the webhook round-trip in isolation
+	if b.echoMode {
+		b.deliverToWebhook("complete", sessionKey, b.buildWebhookResponse("complete", "echo: "+msg.Content, sessionKey))
+		return ProcessOutcome{Kind: OutcomeEcho, SessionKey: sessionKey}, nil
+	}
 
-	// Check for reset triggers before creating new session
+	// Check for reset triggers before commands, since they also start with /
 	resetTriggered := b.isResetTrigger(msg.Content)
+
+	// Check if this is a command (starts with /). Commands use the same session key as
+	// normal messages so forwarded-command responses route back to the same conversation.
+	if !resetTriggered && commands.IsCommand(msg.Content) {
+		return ProcessOutcome{Kind: OutcomeCommand, SessionKey: sessionKey}, b.handleCommand(msg.Content, sessionKey, msg.ID)
+	}
+
+	outcome := ProcessOutcome{Kind: OutcomeForwarded, SessionKey: sessionKey}
 	if resetTriggered {
-		log.Printf("[Bridge] Reset trigger detected, will create new session")
+		trigger := strings.TrimSpace(msg.Content)
 		// Strip reset command from content
 		msg.Content = b.stripResetTrigger(msg.Content)
+		if msg.Content == "" && b.resetConfirm != nil && b.sessionStore != nil && !b.resetConfirm.confirm(sessionKey) {
+			log.Printf("[Bridge] Reset trigger detected, waiting for confirmation")
+			prompt := fmt.Sprintf("Send %s again within %v to start a new session.", trigger, b.resetConfirm.window)
+			b.deliverToWebhook("complete", sessionKey, b.buildWebhookResponse("complete", prompt, sessionKey))
+			return ProcessOutcome{Kind: OutcomeResetPending, SessionKey: sessionKey}, nil
+		}
+		log.Printf("[Bridge] Reset trigger detected, will create new session")
+		outcome.Kind = OutcomeReset
 	}
 
 	// Record session metadata if session store is configured
+	agentID := b.agentID
+	primed := true
+	sessionFile := ""
 	if b.sessionStore != nil {
 		deliveryTo := msg.ID
 		if msg.PeerID != "" {
 			deliveryTo = msg.PeerID
 		}
+		if msg.ReplyTo != "" {
+			deliveryTo = msg.ReplyTo
+		} else if msg.DeliverTo != "" {
+			deliveryTo = msg.DeliverTo
+		}
 		deliveryThreadID := b.resolveDeliveryThreadID(&msg)
 		deliveryCtx := &sessions.DeliveryContext{
 			Channel:   "webhook",
 			To:        deliveryTo,
 			AccountId: b.uid,
 			ThreadId:  deliveryThreadID,
+			PeerKind:  strings.ToLower(b.coalesceString(msg.PeerKind, msg.ChatType, "")),
 		}
 
 		// If reset was triggered, we need to reset the session first
 		if resetTriggered {
-			_, err := b.sessionStore.UpdateEntry(sessionKey, func(existing *sessions.SessionEntry) (*sessions.SessionEntry, error) {
-				return &sessions.SessionEntry{
-					SessionID: sessions.GenerateSessionID(),
-					UpdatedAt: getCurrentTimestamp(),
-				}, nil
-			})
-			if err != nil {
+			if err := b.resetSession(sessionKey); err != nil {
 				log.Printf("[Bridge] Failed to reset session: %v", err)
 			} else {
 				log.Printf("[Bridge] Session reset successfully")
@@ -169,20 +701,249 @@ func (b *Bridge) HandleWebhookMessage(data []byte) error {
 		} else {
 			log.Printf("[Bridge] Session metadata recorded: sessionId=%s",
 				entry.SessionID)
+			agentID = b.pinSessionAgent(sessionKey, entry)
+			primed = entry.SystemSent
+			sessionFile = entry.SessionFile
+		}
+	}
+
+	// A bare reset trigger has nothing left to send; confirm the reset instead
+	if msg.Content == "" {
+		if resetTriggered && b.sessionStore != nil && b.resetConfirmation != "" {
+			b.deliverToWebhook("complete", sessionKey, b.buildWebhookResponse("complete", b.resetConfirmation, sessionKey))
+		}
+		return outcome, nil
+	}
+
+	// The first prompt of a new session carries the preamble
+	prompt := msg.Content
+	needsPreamble := b.preamble != "" && !primed
+	if needsPreamble {
+		prompt = b.preamble + "\n\n" + msg.Content
+	}
+
+	// Forward as agent request to the session's agent
+	opts := openclaw.AgentRequestOptions{Context: b.messageContext(&msg), SessionFile: sessionFile}
+	if err := b.clawdbotClient.SendAgentRequestWithOptions(agentID, prompt, sessionKey, opts); err != nil {
+		metrics.Inc(metrics.GatewaySendErrorsTotal)
+		// Let a redelivery retry the message that didn't get through
+		if b.dedup != nil && msg.ID != "" {
+			b.dedup.Forget(msg.ID)
+		}
+		return outcome, gatewayError("forward message", err)
+	}
+	metrics.Inc(metrics.MessagesForwardedTotal)
+	if needsPreamble {
+		b.markPrimed(sessionKey)
+	}
+	if b.watchdog != nil {
+		b.watchdog.start(sessionKey)
+	}
+	b.recordPrompt(sessionKey, msg.Content)
+	return outcome, nil
+}
+
+// markPrimed records that a session has received the preamble
+func (b *Bridge) markPrimed(sessionKey string) {
+	err := b.sessionStore.Update(func(store map[string]*sessions.SessionEntry) error {
+		entry := store[sessionKey]
+		if entry == nil {
+			return nil
+		}
+		updated := *entry
+		updated.SystemSent = true
+		store[sessionKey] = &updated
+		return nil
+	})
+	if err != nil {
+		log.Printf("[Bridge] Failed to mark session as primed: %v", err)
+	}
+}
+
+// recordSessionFile stores the session file the gateway reported for a session so later
+// requests can resume it
+func (b *Bridge) recordSessionFile(sessionKey, sessionFile string) {
+	if b.sessionStore == nil {
+		return
+	}
+	// Streaming events may repeat the reference; only rewrite the store when it changes
+	if entry, err := b.sessionStore.GetEntry(sessionKey); err == nil && (entry == nil || entry.SessionFile == sessionFile) {
+		return
+	}
+	err := b.sessionStore.Update(func(store map[string]*sessions.SessionEntry) error {
+		entry := store[sessionKey]
+		if entry == nil || entry.SessionFile == sessionFile {
+			return nil
+		}
+		updated := *entry
+		updated.SessionFile = sessionFile
+		store[sessionKey] = &updated
+		return nil
+	})
+	if err != nil {
+		log.Printf("[Bridge] Failed to record session file: %v", err)
+	}
+}
+
+// recordPrompt appends a forwarded prompt to the session's replay history
+func (b *Bridge) recordPrompt(sessionKey, content string) {
+	if b.replayHistory <= 0 || b.sessionStore == nil {
+		return
+	}
+	err := b.sessionStore.Update(func(store map[string]*sessions.SessionEntry) error {
+		entry := store[sessionKey]
+		if entry == nil {
+			return nil
+		}
+		updated := *entry
+		prompts := append(append([]string{}, entry.RecentPrompts...), content)
+		if len(prompts) > b.replayHistory {
+			prompts = prompts[len(prompts)-b.replayHistory:]
+		}
+		updated.RecentPrompts = prompts
+		store[sessionKey] = &updated
+		return nil
+	})
+	if err != nil {
+		log.Printf("[Bridge] Failed to record prompt for replay: %v", err)
+	}
+}
+
+// ReplaySession re-sends the last count recorded prompts of a session to its agent, oldest
+// first (count 0 = all recorded), and returns how many were sent
+func (b *Bridge) ReplaySession(sessionKey string, count int) (int, error) {
+	if b.sessionStore == nil {
+		return 0, storeError("replay session", errNoStore)
+	}
+	entry, err := b.sessionStore.GetEntry(sessionKey)
+	if err != nil {
+		return 0, storeError("replay session", err)
+	}
+	if entry == nil {
+		return 0, nil
+	}
+
+	prompts := entry.RecentPrompts
+	if count > 0 && count < len(prompts) {
+		prompts = prompts[len(prompts)-count:]
+	}
+	agentID := b.sessionAgent(sessionKey)
+	opts := openclaw.AgentRequestOptions{SessionFile: entry.SessionFile}
+	for i, prompt := range prompts {
+		if err := b.clawdbotClient.SendAgentRequestWithOptions(agentID, prompt, sessionKey, opts); err != nil {
+			metrics.Inc(metrics.GatewaySendErrorsTotal)
+			return i, gatewayError("replay session", err)
+		}
+	}
+	if len(prompts) > 0 && b.watchdog != nil {
+		b.watchdog.start(sessionKey)
+	}
+	log.Printf("[Bridge] Replayed %d prompt(s) for session %s", len(prompts), sessionKey)
+	return len(prompts), nil
+}
+
+// pinSessionAgent returns the agent a session is routed to. A session without one is
+// pinned to the default agent so later messages keep going to the same agent.
+func (b *Bridge) pinSessionAgent(sessionKey string, entry *sessions.SessionEntry) string {
+	if entry.AgentID != "" {
+		return entry.AgentID
+	}
+	if b.agentID == "" {
+		return ""
+	}
+	if _, err := b.sessionStore.UpdateEntry(sessionKey, func(existing *sessions.SessionEntry) (*sessions.SessionEntry, error) {
+		return &sessions.SessionEntry{AgentID: b.agentID}, nil
+	}); err != nil {
+		log.Printf("[Bridge] Failed to pin session agent: %v", err)
+	}
+	return b.agentID
+}
+
+// SetKnownAgents enables the /agent command for switching a session between these agents.
+// The default agent is always included.
+func (b *Bridge) SetKnownAgents(agents []string) {
+	known := []string{}
+	if b.agentID != "" {
+		known = append(known, b.agentID)
+	}
+	for _, id := range agents {
+		if id != "" && id != b.agentID {
+			known = append(known, id)
 		}
 	}
+	b.commandHandler.SetAgents(b, known)
+	log.Printf("[Bridge] Known agents: %s", strings.Join(known, ", "))
+}
 
-	// Forward as agent request
-	return b.clawdbotClient.SendAgentRequest(msg.Content, sessionKey)
+// ListSessions lists stored sessions for the /sessions command, most recently active first
+func (b *Bridge) ListSessions(limit int) ([]commands.SessionSummary, error) {
+	entries, err := b.sessionStore.ListEntries(sessions.ListOptions{NewestFirst: true, Limit: limit})
+	if err != nil {
+		return nil, err
+	}
+	list := make([]commands.SessionSummary, 0, len(entries))
+	for _, e := range entries {
+		list = append(list, commands.SessionSummary{
+			Key:       e.Key,
+			SessionID: e.Entry.SessionID,
+			UpdatedAt: time.UnixMilli(e.Entry.UpdatedAt),
+		})
+	}
+	return list, nil
 }
 
-// resolveSessionKey resolves the session key from message fields
+// SessionAgent returns the agent a session is routed to
+func (b *Bridge) SessionAgent(sessionKey string) string {
+	return b.sessionAgent(sessionKey)
+}
+
+// SetSessionAgent pins a session to an agent; later messages in the session go to it
+func (b *Bridge) SetSessionAgent(sessionKey, agentID string) error {
+	if b.sessionStore == nil {
+		return storeError("set session agent", errNoStore)
+	}
+	_, err := b.sessionStore.UpdateEntry(sessionKey, func(existing *sessions.SessionEntry) (*sessions.SessionEntry, error) {
+		patch := &sessions.SessionEntry{AgentID: agentID}
+		if existing == nil {
+			patch.SessionID = sessions.GenerateSessionID()
+			patch.UpdatedAt = getCurrentTimestamp()
+		}
+		return patch, nil
+	})
+	return storeError("set session agent", err)
+}
+
+// sessionAgent looks up the agent a session is routed to, falling back to the default agent
+func (b *Bridge) sessionAgent(sessionKey string) string {
+	if b.sessionStore == nil {
+		return b.agentID
+	}
+	entry, err := b.sessionStore.GetEntry(sessionKey)
+	if err != nil || entry == nil || entry.AgentID == "" {
+		return b.agentID
+	}
+	return entry.AgentID
+}
+
+// resolveSessionKey resolves the session key from message fields, within maxSessionKeyLength
 func (b *Bridge) resolveSessionKey(msg *WebhookMessage, webhookMsg *sessions.WebhookMessage) string {
-	// Use explicit session if provided
-	if msg.Session != "" {
+	return sessions.LimitSessionKey(b.buildSessionKey(msg, webhookMsg), b.maxSessionKeyLength)
+}
+
+// buildSessionKey derives the session key from message fields
+func (b *Bridge) buildSessionKey(msg *WebhookMessage, webhookMsg *sessions.WebhookMessage) string {
+	// Use explicit session if provided, unless peer info should win
+	if msg.Session != "" && !b.preferPeerSession {
 		return sessions.NormalizeSessionKey(msg.Session)
 	}
 
+	// Per-chat scope keys by the chat, not by whoever in it sent the message
+	if b.sessionScope == sessions.SessionScopePerChat {
+		if key, ok := sessions.ChatSessionKey(b.agentID, msg.ChatID, msg.ChatType); ok {
+			return key
+		}
+	}
+
 	// Extract peer info with optimized string handling
 	peerKind := b.coalesceString(msg.PeerKind, msg.ChatType, "")
 	peerID := b.coalesceString(msg.PeerID, msg.ChatID, msg.SenderID, "")
@@ -207,7 +968,7 @@ func (b *Bridge) resolveSessionKey(msg *WebhookMessage, webhookMsg *sessions.Web
 		}
 	}
 
-	// Fallback to scope-based resolution
+	// Fallback to the explicit session or scope-based resolution
 	return sessions.ResolveSessionKey(b.sessionScope, webhookMsg)
 }
 
@@ -243,33 +1004,47 @@ func (b *Bridge) coalesceString(values ...string) string {
 	return ""
 }
 
-// HandleOpenClawEvent handles an event from OpenClaw and forwards to webhook
+// HandleOpenClawEvent handles an event from OpenClaw and forwards to webhook. Like
+// HandleWebhookMessage it runs inline on the gateway read loop, one event at a time.
 func (b *Bridge) HandleOpenClawEvent(data []byte) {
-	log.Printf("[Bridge] OpenClaw -> Webhook: %s", string(data))
+	b.openclawLog.log(data)
+	metrics.Inc(metrics.EventsTotal)
 
-	// Parse the event to determine its type
-	var baseEvent struct {
-		Type  string `json:"type,omitempty"`
-		Event string `json:"event,omitempty"` // For lifecycle events
-	}
-	if err := json.Unmarshal(data, &baseEvent); err != nil {
+	eventType, forward, err := b.classifyEvent(data)
+	if err != nil {
 		log.Printf("[Bridge] Failed to parse event type: %v", err)
-		// Send raw data anyway
-		b.sendToWebhook(data)
+		// Unparseable events are treated like unrecognized ones
+		if raw := b.handleUnknownEvent(data, ""); raw != nil {
+			b.sendToWebhook(raw)
+		}
 		return
 	}
-
-	// Check if this is a lifecycle event that needs special handling
-	if baseEvent.Event == "lifecycle" || baseEvent.Event == "tick" || baseEvent.Event == "presence" || baseEvent.Event == "health" {
-		// Skip internal lifecycle events
+	if !forward {
 		return
 	}
 
-	// Extract session key from event for route tracking
+	// Extract session key (and any session file reference) from event for route tracking
 	var sessionEvent struct {
-		SessionKey string `json:"sessionKey,omitempty"`
+		SessionKey  string `json:"sessionKey,omitempty"`
+		SessionFile string `json:"sessionFile,omitempty"`
+		Data        struct {
+			SessionFile string `json:"sessionFile,omitempty"`
+		} `json:"data,omitempty"`
+	}
+	if err := json.Unmarshal(data, &sessionEvent); err != nil {
+		sessionEvent.SessionKey = ""
+	}
+
+	// Keep replies for sessions we never saw (e.g. wiped by a restart) from reaching the webhook
+	if b.holdUnknownSessionEvent(sessionEvent.SessionKey, data) {
+		return
+	}
+
+	if sessionFile := b.coalesceString(sessionEvent.SessionFile, sessionEvent.Data.SessionFile); sessionFile != "" && sessionEvent.SessionKey != "" {
+		b.recordSessionFile(sessionEvent.SessionKey, sessionFile)
 	}
-	if err := json.Unmarshal(data, &sessionEvent); err == nil && sessionEvent.SessionKey != "" && b.sessionStore != nil {
+
+	if sessionEvent.SessionKey != "" && b.sessionStore != nil {
 		// Update last route for this session
 		_, err := b.sessionStore.UpdateLastRoute(sessionEvent.SessionKey, &sessions.DeliveryContext{
 			Channel:   "webhook",
@@ -281,15 +1056,119 @@ func (b *Bridge) HandleOpenClawEvent(data []byte) {
 	}
 
 	// Convert OpenClaw event format to webhook format
-	convertedData := b.convertEventToWebhookFormat(data, baseEvent.Type)
+	convertedData := b.convertEventToWebhookFormat(data, eventType)
 	if convertedData != nil {
-		b.sendToWebhook(convertedData)
+		metrics.Inc(metrics.EventsForwardedTotal)
+		b.forwardToWebhook(convertedData)
+	}
+}
+
+// classifyEvent returns the top-level type of a gateway frame and whether it may reach
+// the webhook at all (allowlisted and not an internal lifecycle event)
+func (b *Bridge) classifyEvent(data []byte) (string, bool, error) {
+	var baseEvent struct {
+		Type  string `json:"type,omitempty"`
+		Event string `json:"event,omitempty"` // For lifecycle events
 	}
+	if err := json.Unmarshal(data, &baseEvent); err != nil {
+		return "", false, err
+	}
+
+	// Only allowlisted event types reach the webhook
+	if b.eventAllowlist != nil {
+		name := baseEvent.Type
+		if name == "event" && baseEvent.Event != "" {
+			name = baseEvent.Event
+		}
+		if !b.eventAllowlist[name] {
+			return baseEvent.Type, false, nil
+		}
+	}
+
+	// Skip internal lifecycle events
+	switch baseEvent.Event {
+	case "lifecycle", "tick", "presence", "health":
+		return baseEvent.Type, false, nil
+	}
+	return baseEvent.Type, true, nil
+}
+
+// ConvertRaw runs a gateway frame through the same parsing, filtering and conversion as
+// HandleOpenClawEvent and returns the webhook payload it would send, or nil if the frame
+// would be dropped. Nothing is sent or recorded, though the session's sequence number
+// advances when sequence numbers are enabled. Useful for tests and embedders.
+func (b *Bridge) ConvertRaw(data []byte) []byte {
+	eventType, forward, err := b.classifyEvent(data)
+	if err != nil {
+		return b.handleUnknownEvent(data, "")
+	}
+	if !forward {
+		return nil
+	}
+	return b.convertEventToWebhookFormat(data, eventType)
+}
+
+// Flush delivers any progress still held back by coalescing or batching. Call it during shutdown after
+// the gateway connection is closed and before the webhook connection is.
+func (b *Bridge) Flush() {
+	if b.coalescer != nil {
+		b.coalescer.flushAll()
+	}
+	if b.batcher != nil {
+		b.batcher.flush()
+	}
+	if b.fanout != nil && !b.fanout.drain(fanoutDrainTimeout) {
+		log.Printf("[Bridge] Gave up waiting for slow webhooks after %v, some frames were not sent", fanoutDrainTimeout)
+	}
+}
+
+// forwardToWebhook sends a converted event, feeding the response watchdog and
+// passing it through the coalescer when those are enabled
+func (b *Bridge) forwardToWebhook(data []byte) {
+	var outbound struct {
+		Type    string `json:"type"`
+		Session string `json:"session"`
+	}
+	json.Unmarshal(data, &outbound)
+	b.publish(BridgeEvent{Type: EventOutbound, SessionKey: outbound.Session, Kind: outbound.Type})
+
+	if b.coalescer == nil && b.watchdog == nil {
+		b.sendToWebhook(data)
+		return
+	}
+
+	if b.watchdog != nil && outbound.Session != "" {
+		b.watchdog.activity(outbound.Session, outbound.Type == "complete" || outbound.Type == "error")
+	}
+	b.deliverToWebhook(outbound.Type, outbound.Session, data)
+}
+
+// deliverToWebhook sends an outbound message through the coalescer if enabled
+func (b *Bridge) deliverToWebhook(msgType, sessionKey string, data []byte) {
+	if b.coalescer == nil {
+		b.sendToWebhook(data)
+		return
+	}
+	b.coalescer.submit(msgType, sessionKey, data)
 }
 
 // sendToWebhook sends data to the webhook client
 func (b *Bridge) sendToWebhook(data []byte) {
+	if b.batcher != nil {
+		b.batcher.add(data)
+		return
+	}
+	b.writeToWebhook(data)
+}
+
+// writeToWebhook writes one frame to the webhook connection(s)
+func (b *Bridge) writeToWebhook(data []byte) {
+	if b.fanout != nil {
+		b.fanout.send(data)
+		return
+	}
 	if err := b.webhookClient.Send(data); err != nil {
+		metrics.Inc(metrics.WebhookSendErrorsTotal)
 		log.Printf("[Bridge] Failed to send to webhook: %v", err)
 	}
 }
@@ -307,31 +1186,38 @@ func (b *Bridge) convertEventToWebhookFormat(data []byte, eventType string) []by
 			} `json:"data,omitempty"`
 		}
 		if err := json.Unmarshal(data, &agentEvent); err == nil {
+			sessionKey, ok := b.eventSessionKey(eventType, agentEvent.SessionKey)
+			if !ok {
+				return nil
+			}
+			agentEvent.SessionKey = sessionKey
 			// Check for lifecycle events
 			if agentEvent.Stream == "lifecycle" {
 				// "end" phase means the request is complete
 				if agentEvent.Data.Phase == "end" || agentEvent.Data.Phase == "complete" {
 					// Send empty complete event to signal end
-					response := map[string]interface{}{
-						"type":    "complete",
-						"content": "",
-						"session": agentEvent.SessionKey,
-					}
-					converted, _ := json.Marshal(response)
-					return converted
+					return b.buildWebhookResponse("complete", "", agentEvent.SessionKey)
 				}
 				// Skip other lifecycle phases
 				return nil
 			}
 			// "assistant" stream with text content
-			if agentEvent.Stream == "assistant" && agentEvent.Data.Text != "" {
-				response := map[string]interface{}{
-					"type":    "progress",
-					"content": agentEvent.Data.Text,
-					"session": agentEvent.SessionKey,
+			if agentEvent.Stream == "assistant" {
+				if !b.shouldForwardDelta(agentEvent.Data.Text) {
+					return nil
+				}
+				return b.buildWebhookResponse("progress", agentEvent.Data.Text, agentEvent.SessionKey)
+			}
+			// "reasoning" stream - the model's thinking, hidden unless configured
+			if agentEvent.Stream == "reasoning" {
+				switch b.reasoning {
+				case ReasoningThinking, ReasoningProgress:
+					if !b.shouldForwardDelta(agentEvent.Data.Text) {
+						return nil
+					}
+					return b.buildWebhookResponse(string(b.reasoning), agentEvent.Data.Text, agentEvent.SessionKey)
 				}
-				converted, _ := json.Marshal(response)
-				return converted
+				return nil
 			}
 			// "tool" stream - skip for cleaner output
 			if agentEvent.Stream == "tool" {
@@ -353,6 +1239,11 @@ func (b *Bridge) convertEventToWebhookFormat(data []byte, eventType string) []by
 			} `json:"message,omitempty"`
 		}
 		if err := json.Unmarshal(data, &chatEvent); err == nil {
+			sessionKey, ok := b.eventSessionKey(eventType, chatEvent.SessionKey)
+			if !ok {
+				return nil
+			}
+			chatEvent.SessionKey = sessionKey
 			// Extract text from content array
 			var text string
 			if chatEvent.Message != nil && len(chatEvent.Message.Content) > 0 {
@@ -364,65 +1255,108 @@ func (b *Bridge) convertEventToWebhookFormat(data []byte, eventType string) []by
 			}
 			// "final" state means complete
 			if chatEvent.State == "final" {
-				response := map[string]interface{}{
-					"type":    "complete",
-					"content": text,
-					"session": chatEvent.SessionKey,
-				}
-				converted, _ := json.Marshal(response)
-				return converted
+				return b.buildWebhookResponse("complete", text, chatEvent.SessionKey)
 			}
 			// "delta" state means progress (streaming)
-			if chatEvent.State == "delta" && text != "" {
-				response := map[string]interface{}{
-					"type":    "progress",
-					"content": text,
-					"session": chatEvent.SessionKey,
+			if chatEvent.State == "delta" {
+				if !b.shouldForwardDelta(text) {
+					return nil
 				}
-				converted, _ := json.Marshal(response)
-				return converted
+				return b.buildWebhookResponse("progress", text, chatEvent.SessionKey)
 			}
 			// "error" state
 			if chatEvent.State == "error" {
-				response := map[string]interface{}{
-					"type":    "error",
-					"content": "An error occurred",
-					"session": chatEvent.SessionKey,
-				}
-				converted, _ := json.Marshal(response)
-				return converted
+				return b.buildWebhookResponse("error", "An error occurred", chatEvent.SessionKey)
 			}
 		}
 	}
 
-	// For unknown event types, return original data
-	return data
+	// Gateway event frames and partially handled agent/chat events pass through as-is
+	switch eventType {
+	case "agent", "chat", "event":
+		return data
+	}
+
+	return b.handleUnknownEvent(data, eventType)
 }
 
-// handleSessionControlMessage handles session control messages
-func (b *Bridge) handleSessionControlMessage(data []byte) error {
-	if b.sessionStore == nil {
-		log.Printf("[Bridge] Session store not configured, ignoring control message")
-		return nil
+// handleUnknownEvent applies the configured policy to an unrecognized top-level event type
+func (b *Bridge) handleUnknownEvent(data []byte, eventType string) []byte {
+	switch b.unknownEvents {
+	case UnknownEventsForward:
+		return data
+	case UnknownEventsLogOnly:
+		log.Printf("[Bridge] Unrecognized gateway event (not forwarded): type=%q, %d bytes", eventType, len(data))
 	}
+	return nil
+}
+
+// buildWebhookResponse encodes an outbound message, stamping the session's next
+// sequence number when sequencing is enabled
+func (b *Bridge) buildWebhookResponse(msgType, content, sessionKey string) []byte {
+	response := map[string]interface{}{
+		"type":    msgType,
+		"content": content,
+		"session": sessionKey,
+	}
+	if b.seq != nil {
+		response["seq"] = b.seq.Next(sessionKey)
+	}
+	b.converters.apply(sessionKey, response)
+	converted, _ := json.Marshal(response)
+	return converted
+}
 
+// shouldForwardDelta reports whether a streaming delta carries content worth sending.
+// Empty deltas are always skipped; whitespace-only deltas depend on configuration.
+// Content is forwarded untrimmed so assistant and chat deltas render identically.
+func (b *Bridge) shouldForwardDelta(text string) bool {
+	if text == "" {
+		return false
+	}
+	if b.dropWhitespaceDeltas && strings.TrimSpace(text) == "" {
+		return false
+	}
+	return true
+}
+
+// handleSessionControlMessage handles session control messages
+func (b *Bridge) handleSessionControlMessage(data []byte) error {
 	ctrlMsg, err := sessions.ParseSessionControlMessage(data)
 	if err != nil {
 		log.Printf("[Bridge] Failed to parse session control message: %v", err)
-		return err
+		return serializationError("parse control message", err)
 	}
 
 	log.Printf("[Bridge] Handling session control: type=%s, key=%s", ctrlMsg.Type, ctrlMsg.Key)
 
+	if b.sessionStore == nil {
+		log.Printf("[Bridge] Session store not configured, rejecting control message")
+		return b.sendControlResponse(ctrlMsg.Type, map[string]interface{}{
+			"error": "Session store not configured",
+		})
+	}
+
+	// Everything but session.list acts on one session
+	if ctrlMsg.Type != sessions.ControlMessageSessionList && controlSessionKey(ctrlMsg) == "" {
+		return b.sendControlResponse(ctrlMsg.Type, map[string]interface{}{
+			"error": "Missing session key",
+		})
+	}
+
 	switch ctrlMsg.Type {
 	case sessions.ControlMessageSessionGet:
 		return b.handleSessionGet(ctrlMsg)
 	case sessions.ControlMessageSessionList:
-		return b.handleSessionList()
+		return b.handleSessionList(ctrlMsg)
 	case sessions.ControlMessageSessionReset:
 		return b.handleSessionReset(ctrlMsg)
 	case sessions.ControlMessageSessionDelete:
 		return b.handleSessionDelete(ctrlMsg)
+	case sessions.ControlMessageSessionLabel:
+		return b.handleSessionLabel(ctrlMsg)
+	case sessions.ControlMessageSessionReplay:
+		return b.handleSessionReplay(ctrlMsg)
 	default:
 		log.Printf("[Bridge] Unknown control message type: %s", ctrlMsg.Type)
 	}
@@ -430,15 +1364,25 @@ func (b *Bridge) handleSessionControlMessage(data []byte) error {
 	return nil
 }
 
+// controlSessionKey returns the session a control message targets (key, falling back to id)
+func controlSessionKey(msg *sessions.SessionControlMessage) string {
+	if msg.Key != "" {
+		return msg.Key
+	}
+	return msg.ID
+}
+
 // handleSessionGet returns information about a specific session
 func (b *Bridge) handleSessionGet(msg *sessions.SessionControlMessage) error {
-	sessionKey := msg.Key
-	if sessionKey == "" {
-		sessionKey = msg.ID
-	}
+	sessionKey := controlSessionKey(msg)
 
 	entry, err := b.sessionStore.GetEntry(sessionKey)
 	if err != nil {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error": "Failed to load session",
+		})
+	}
+	if entry == nil {
 		return b.sendControlResponse(msg.Type, map[string]interface{}{
 			"error": "Session not found",
 		})
@@ -451,35 +1395,54 @@ func (b *Bridge) handleSessionGet(msg *sessions.SessionControlMessage) error {
 		DeliveryContext: entry.DeliveryContext,
 		LastChannel:     entry.LastChannel,
 		LastTo:          entry.LastTo,
+		Label:           entry.Label,
 	}
 
 	return b.sendControlResponse(msg.Type, response)
 }
 
-// handleSessionList returns all sessions
-func (b *Bridge) handleSessionList() error {
-	store, err := b.sessionStore.Load()
+// handleSessionList returns a page of sessions ordered by last update, newest first
+func (b *Bridge) handleSessionList(msg *sessions.SessionControlMessage) error {
+	// Stable order so consecutive pages neither skip nor repeat sessions
+	entries, err := b.sessionStore.ListEntries(sessions.ListOptions{NewestFirst: true})
 	if err != nil {
-		return err
+		return storeError("list sessions", err)
 	}
 
-	sessionList := make([]sessions.SessionInfoResponse, 0, len(store))
-	for key, entry := range store {
-		if entry != nil {
-			sessionList = append(sessionList, sessions.SessionInfoResponse{
-				Key:             key,
-				SessionID:       entry.SessionID,
-				UpdatedAt:       entry.UpdatedAt,
-				DeliveryContext: entry.DeliveryContext,
-				LastChannel:     entry.LastChannel,
-				LastTo:          entry.LastTo,
-			})
-		}
+	sessionList := make([]sessions.SessionInfoResponse, 0, len(entries))
+	for _, item := range entries {
+		entry := item.Entry
+		sessionList = append(sessionList, sessions.SessionInfoResponse{
+			Key:             item.Key,
+			SessionID:       entry.SessionID,
+			UpdatedAt:       entry.UpdatedAt,
+			DeliveryContext: entry.DeliveryContext,
+			LastChannel:     entry.LastChannel,
+			LastTo:          entry.LastTo,
+			Label:           entry.Label,
+		})
 	}
 
+	total := len(sessionList)
+	offset := msg.Offset
+	if offset < 0 {
+		offset = 0
+	}
+	if offset > total {
+		offset = total
+	}
+	end := total
+	if msg.Limit > 0 && offset+msg.Limit < total {
+		end = offset + msg.Limit
+	}
+	page := sessionList[offset:end]
+
 	response := sessions.SessionListResponse{
-		Sessions: sessionList,
-		Count:    len(sessionList),
+		Sessions: page,
+		Count:    len(page),
+		Total:    total,
+		Offset:   offset,
+		Limit:    msg.Limit,
 	}
 
 	return b.sendControlResponse(sessions.ControlMessageSessionList, response)
@@ -487,47 +1450,163 @@ func (b *Bridge) handleSessionList() error {
 
 // handleSessionReset resets a session
 func (b *Bridge) handleSessionReset(msg *sessions.SessionControlMessage) error {
-	sessionKey := msg.Key
-	if sessionKey == "" {
-		sessionKey = msg.ID
+	sessionKey := controlSessionKey(msg)
+
+	if err := b.resetSession(sessionKey); err != nil {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error": "Failed to reset session",
+		})
 	}
 
-	_, err := b.sessionStore.UpdateEntry(sessionKey, func(existing *sessions.SessionEntry) (*sessions.SessionEntry, error) {
-		return &sessions.SessionEntry{
-			SessionID: sessions.GenerateSessionID(),
-			UpdatedAt: getCurrentTimestamp(),
-		}, nil
-	})
+	response := map[string]interface{}{
+		"success": true,
+		"key":     sessionKey,
+	}
+	if entry, err := b.sessionStore.GetEntry(sessionKey); err == nil && entry != nil {
+		response["sessionId"] = entry.SessionID
+	}
+	return b.sendControlResponse(msg.Type, response)
+}
+
+// handleSessionLabel sets a session's label, or clears it when the label is empty
+func (b *Bridge) handleSessionLabel(msg *sessions.SessionControlMessage) error {
+	sessionKey := controlSessionKey(msg)
 
+	found := false
+	err := b.sessionStore.Update(func(store map[string]*sessions.SessionEntry) error {
+		entry := store[sessionKey]
+		if entry == nil {
+			return nil
+		}
+		found = true
+		labeled := *entry
+		labeled.Label = msg.Label
+		store[sessionKey] = &labeled
+		return nil
+	})
 	if err != nil {
 		return b.sendControlResponse(msg.Type, map[string]interface{}{
-			"error": "Failed to reset session",
+			"error": "Failed to label session",
+		})
+	}
+	if !found {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error": "Session not found",
 		})
 	}
 
 	return b.sendControlResponse(msg.Type, map[string]interface{}{
 		"success": true,
 		"key":     sessionKey,
+		"label":   msg.Label,
 	})
 }
 
-// handleSessionDelete deletes a session
-func (b *Bridge) handleSessionDelete(msg *sessions.SessionControlMessage) error {
-	sessionKey := msg.Key
-	if sessionKey == "" {
-		sessionKey = msg.ID
+// handleSessionReplay re-sends a session's recent prompts to the gateway
+func (b *Bridge) handleSessionReplay(msg *sessions.SessionControlMessage) error {
+	sessionKey := controlSessionKey(msg)
+
+	if b.replayHistory <= 0 {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error": "Replay is not enabled",
+		})
+	}
+
+	replayed, err := b.ReplaySession(sessionKey, msg.Count)
+	if err != nil {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error":    "Failed to replay session",
+			"replayed": replayed,
+		})
+	}
+
+	return b.sendControlResponse(msg.Type, map[string]interface{}{
+		"success":  true,
+		"key":      sessionKey,
+		"replayed": replayed,
+	})
+}
+
+// Sessions returns a copy of all entries in the session store
+func (b *Bridge) Sessions() (map[string]*sessions.SessionEntry, error) {
+	if b.sessionStore == nil {
+		return nil, storeError("load sessions", errNoStore)
+	}
+	store, err := b.sessionStore.Load()
+	if err != nil {
+		return nil, storeError("load sessions", err)
+	}
+	return store, nil
+}
+
+// ResetSession starts a fresh session for the key, as if the user had sent a reset trigger
+func (b *Bridge) ResetSession(sessionKey string) error {
+	if b.sessionStore == nil {
+		return storeError("reset session", errNoStore)
+	}
+	if err := b.resetSession(sessionKey); err != nil {
+		return storeError("reset session", err)
+	}
+	b.publish(BridgeEvent{Type: EventSessionReset, SessionKey: sessionKey})
+	return nil
+}
+
+// resetSession starts a fresh session ID for the key and restarts its outbound sequence.
+// Delivery and route fields survive the reset unless resetWipesRouting is set, in which
+// case the entry is replaced outright. The label is kept only when resetKeepsLabel is set.
+func (b *Bridge) resetSession(sessionKey string) error {
+	fresh := &sessions.SessionEntry{
+		SessionID: sessions.GenerateSessionID(),
+		UpdatedAt: getCurrentTimestamp(),
 	}
 
 	err := b.sessionStore.Update(func(store map[string]*sessions.SessionEntry) error {
-		delete(store, sessionKey)
+		existing := store[sessionKey]
+		entry := fresh
+		if existing != nil && !b.resetWipesRouting {
+			// Merge so only the session ID and timestamp change
+			entry = sessions.MergeSessionEntry(existing, fresh)
+		}
+		// A reset starts a new conversation, which gets the preamble again and must not
+		// resume the old server-side session file
+		entry.SystemSent = false
+		entry.SessionFile = ""
+		if existing != nil && b.resetKeepsLabel {
+			entry.Label = existing.Label
+		} else {
+			entry.Label = ""
+		}
+		store[sessionKey] = entry
 		return nil
 	})
+	if err != nil {
+		return err
+	}
+
+	if b.seq != nil {
+		b.seq.Reset(sessionKey)
+	}
+	return nil
+}
+
+// handleSessionDelete deletes a session
+func (b *Bridge) handleSessionDelete(msg *sessions.SessionControlMessage) error {
+	sessionKey := controlSessionKey(msg)
 
+	found, err := b.sessionStore.DeleteEntry(sessionKey)
 	if err != nil {
 		return b.sendControlResponse(msg.Type, map[string]interface{}{
 			"error": "Failed to delete session",
 		})
 	}
+	if !found {
+		return b.sendControlResponse(msg.Type, map[string]interface{}{
+			"error": "Session not found",
+		})
+	}
+	if b.seq != nil {
+		b.seq.Reset(sessionKey)
+	}
 
 	return b.sendControlResponse(msg.Type, map[string]interface{}{
 		"success": true,
@@ -539,28 +1618,24 @@ func (b *Bridge) handleSessionDelete(msg *sessions.SessionControlMessage) error
 func (b *Bridge) sendControlResponse(msgType sessions.ControlMessageType, data interface{}) error {
 	response, err := sessions.BuildSessionControlResponse(msgType, data)
 	if err != nil {
-		return err
-	}
-
-	if err := b.webhookClient.Send(response); err != nil {
-		log.Printf("[Bridge] Failed to send control response: %v", err)
-		return err
+		return serializationError("build control response", err)
 	}
 
+	// Same path as other outbound frames so responses stay in order with batched output
+	b.sendToWebhook(response)
 	return nil
 }
 
-// isResetTrigger checks if the message content is a session reset trigger
+// isResetTrigger checks if the message content is a session reset trigger,
+// either on its own or followed by a message ("/new let's start over")
 // Optimized to avoid unnecessary string operations
 func (b *Bridge) isResetTrigger(content string) bool {
-	// Only process first 100 chars for trigger checking
-	if len(content) > 100 {
-		content = content[:100]
-	}
+	// Only process first 100 bytes for trigger checking
+	content = truncateUTF8(content, 100)
 	normalized := strings.TrimSpace(content)
 
 	for _, trigger := range sessions.DefaultResetTriggers {
-		if normalized == trigger {
+		if normalized == trigger || strings.HasPrefix(normalized, trigger+" ") {
 			return true
 		}
 	}
@@ -600,21 +1675,21 @@ func getCurrentTimestamp() int64 {
 
 // handleCommand processes a command message and sends the response back
 func (b *Bridge) handleCommand(content, session, messageID string) error {
-	log.Printf("[Bridge] Processing command: %s", content)
+	log.Printf("[Bridge] Processing command: %s", truncateContent(content, b.logTruncate))
 
 	// Handle the command
-	response, err := b.commandHandler.HandleCommand(content)
+	response, err := b.commandHandler.HandleCommand(content, session)
 	if err != nil {
 		// Check if this is a forward request
 		if strings.HasPrefix(err.Error(), "FORWARD_TO_GATEWAY:") {
 			// Extract the actual command to forward
 			forwardContent := strings.TrimPrefix(err.Error(), "FORWARD_TO_GATEWAY:")
-			log.Printf("[Bridge] Forwarding to Gateway: %s", forwardContent)
+			log.Printf("[Bridge] Forwarding to Gateway: %s", truncateContent(forwardContent, b.logTruncate))
 
 			// Send to OpenClaw Gateway as an agent request
-			if err := b.clawdbotClient.SendAgentRequest(forwardContent, session); err != nil {
+			if err := b.clawdbotClient.SendAgentRequestTo(b.sessionAgent(session), forwardContent, session); err != nil {
 				log.Printf("[Bridge] Failed to forward to Gateway: %v", err)
-				return err
+				return gatewayError("forward command", err)
 			}
 
 			// Don't send a response back to webhook - let Gateway handle it
@@ -630,14 +1705,10 @@ func (b *Bridge) handleCommand(content, session, messageID string) error {
 	responseData, err := commands.FormatCommandResponse(response, session)
 	if err != nil {
 		log.Printf("[Bridge] Failed to format command response: %v", err)
-		return err
-	}
-
-	// Send response back to webhook
-	if err := b.webhookClient.Send(responseData); err != nil {
-		log.Printf("[Bridge] Failed to send command response: %v", err)
-		return err
+		return serializationError("format command response", err)
 	}
 
+	// Send response back to webhook (batched like every other outbound frame)
+	b.sendToWebhook(responseData)
 	return nil
 }
@@ -0,0 +1,424 @@
+package bridge
+
+import (
+	"encoding/json"
+	"fmt"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/openclaw"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+// agentRequest is a prompt the bridge sent to the gateway
+type agentRequest struct {
+	agentID, message, sessionKey string
+}
+
+// stubGateway records agent requests instead of sending them, failing with err if set
+type stubGateway struct {
+	mu       sync.Mutex
+	requests []agentRequest
+	err      error
+}
+
+func (g *stubGateway) AgentID() string { return "main" }
+
+func (g *stubGateway) SendApproval(requestID string, approved bool) error { return g.err }
+
+func (g *stubGateway) SendAgentRequestTo(agentID, message, sessionKey string) error {
+	return g.SendAgentRequestWithOptions(agentID, message, sessionKey, openclaw.AgentRequestOptions{})
+}
+
+func (g *stubGateway) SendAgentRequestWithOptions(agentID, message, sessionKey string, opts openclaw.AgentRequestOptions) error {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if g.err != nil {
+		return g.err
+	}
+	g.requests = append(g.requests, agentRequest{agentID, message, sessionKey})
+	return nil
+}
+
+func (g *stubGateway) sent() []agentRequest {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	return append([]agentRequest{}, g.requests...)
+}
+
+// webhookRecorder is a WebhookSender that records the frames written to it
+type webhookRecorder struct {
+	sentFrames
+}
+
+func (r *webhookRecorder) Send(data []byte) error {
+	r.send(data)
+	return nil
+}
+
+// newTestBridge returns a bridge wired to stubs and a session store in a temp dir
+func newTestBridge(t *testing.T, gateway *stubGateway) (*Bridge, *webhookRecorder) {
+	t.Helper()
+	hook := &webhookRecorder{}
+	b := NewBridge(hook, gateway)
+	b.SetSessionStore(sessions.NewStore(&sessions.StoreConfig{
+		StorePath:   filepath.Join(t.TempDir(), "sessions.json"),
+		LockTimeout: time.Second,
+		Format:      sessions.StoreFormatJSON,
+	}))
+	return b, hook
+}
+
+func TestProcessMessage(t *testing.T) {
+	tests := []struct {
+		name        string
+		data        string
+		gatewayErr  error
+		wantKind    ProcessOutcomeKind
+		wantPrompt  string // Prompt the gateway should get ("" = none)
+		wantWebhook string // Substring of the single frame sent back to the webhook ("" = none)
+		wantErr     bool
+	}{
+		{
+			name:        "control message",
+			data:        `{"type":"session.list"}`,
+			wantKind:    OutcomeControl,
+			wantWebhook: `"type":"session.list"`,
+		},
+		{
+			name:        "command",
+			data:        `{"id":"m1","content":"/help"}`,
+			wantKind:    OutcomeCommand,
+			wantWebhook: "Available Commands",
+		},
+		{
+			name:     "empty content",
+			data:     `{"id":"m1","content":""}`,
+			wantKind: OutcomeSkipped,
+		},
+		{
+			name:        "bare reset trigger",
+			data:        `{"id":"m1","content":"/reset"}`,
+			wantKind:    OutcomeReset,
+			wantWebhook: DefaultResetConfirmation,
+		},
+		{
+			name:       "reset trigger with a message",
+			data:       `{"id":"m1","content":"/new hello"}`,
+			wantKind:   OutcomeReset,
+			wantPrompt: "hello",
+		},
+		{
+			name:       "normal message",
+			data:       `{"id":"m1","content":"hello"}`,
+			wantKind:   OutcomeForwarded,
+			wantPrompt: "hello",
+		},
+		{
+			name:       "gateway failure",
+			data:       `{"id":"m1","content":"hello"}`,
+			gatewayErr: openclaw.ErrNotConnected,
+			wantKind:   OutcomeForwarded,
+			wantErr:    true,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			gateway := &stubGateway{err: tt.gatewayErr}
+			b, hook := newTestBridge(t, gateway)
+
+			outcome, err := b.ProcessMessage([]byte(tt.data))
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("ProcessMessage error = %v, want error %v", err, tt.wantErr)
+			}
+			if outcome.Kind != tt.wantKind {
+				t.Errorf("outcome = %q (%s), want %q", outcome.Kind, outcome.Reason, tt.wantKind)
+			}
+			if (outcome.SessionKey == "") != (tt.wantKind == OutcomeSkipped || tt.wantKind == OutcomeControl) {
+				t.Errorf("outcome session key = %q", outcome.SessionKey)
+			}
+
+			requests := gateway.sent()
+			switch {
+			case tt.wantPrompt == "" && len(requests) != 0:
+				t.Errorf("gateway got %+v, want nothing", requests)
+			case tt.wantPrompt != "" && (len(requests) != 1 || requests[0].message != tt.wantPrompt || requests[0].sessionKey != outcome.SessionKey):
+				t.Errorf("gateway got %+v, want %q for session %q", requests, tt.wantPrompt, outcome.SessionKey)
+			}
+
+			frames := hook.get()
+			switch {
+			case tt.wantWebhook == "" && len(frames) != 0:
+				t.Errorf("webhook got %q, want nothing", frames)
+			case tt.wantWebhook != "" && (len(frames) != 1 || !strings.Contains(frames[0], tt.wantWebhook)):
+				t.Errorf("webhook got %q, want one frame containing %q", frames, tt.wantWebhook)
+			}
+		})
+	}
+}
+
+func TestClassifyEvent(t *testing.T) {
+	tests := []struct {
+		name      string
+		allowlist []string // nil = the default (agent, chat)
+		data      string
+		wantType  string
+		forward   bool
+		wantErr   bool
+	}{
+		{name: "agent event", data: `{"type":"agent","stream":"assistant"}`, wantType: "agent", forward: true},
+		{name: "chat event", data: `{"type":"chat","state":"delta"}`, wantType: "chat", forward: true},
+		{name: "type outside the allowlist", data: `{"type":"presence"}`, wantType: "presence"},
+		{name: "event frame matched by its event name", allowlist: []string{"custom"}, data: `{"type":"event","event":"custom"}`, wantType: "event", forward: true},
+		{name: "event frame outside the allowlist", data: `{"type":"event","event":"custom"}`, wantType: "event"},
+		{name: "lifecycle events are internal", allowlist: []string{"*"}, data: `{"type":"event","event":"tick"}`, wantType: "event"},
+		{name: "wildcard allows unknown types", allowlist: []string{"*"}, data: `{"type":"custom"}`, wantType: "custom", forward: true},
+		{name: "invalid JSON", data: `{"type":`, wantErr: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			b := NewBridge(nil, nil)
+			if tt.allowlist != nil {
+				b.SetEventAllowlist(tt.allowlist)
+			}
+
+			gotType, forward, err := b.classifyEvent([]byte(tt.data))
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("classifyEvent error = %v, want error %v", err, tt.wantErr)
+			}
+			if gotType != tt.wantType || forward != tt.forward {
+				t.Errorf("classifyEvent = %q, %v, want %q, %v", gotType, forward, tt.wantType, tt.forward)
+			}
+		})
+	}
+}
+
+func TestConvertRaw(t *testing.T) {
+	// want is the expected webhook payload: "" for dropped, "raw" for the frame unchanged
+	tests := []struct {
+		name  string
+		setup func(b *Bridge)
+		data  string
+		want  string
+	}{
+		{
+			name: "assistant text becomes progress",
+			data: `{"type":"agent","stream":"assistant","sessionKey":"s1","data":{"text":"Hi"}}`,
+			want: `{"type":"progress","content":"Hi","session":"s1"}`,
+		},
+		{
+			name: "lifecycle end becomes complete",
+			data: `{"type":"agent","stream":"lifecycle","sessionKey":"s1","data":{"phase":"end"}}`,
+			want: `{"type":"complete","content":"","session":"s1"}`,
+		},
+		{
+			name: "other lifecycle phases are dropped",
+			data: `{"type":"agent","stream":"lifecycle","sessionKey":"s1","data":{"phase":"start"}}`,
+		},
+		{
+			name: "tool stream is dropped",
+			data: `{"type":"agent","stream":"tool","sessionKey":"s1","data":{"text":"ls"}}`,
+		},
+		{
+			name: "reasoning is suppressed by default",
+			data: `{"type":"agent","stream":"reasoning","sessionKey":"s1","data":{"text":"hmm"}}`,
+		},
+		{
+			name:  "reasoning as thinking",
+			setup: func(b *Bridge) { b.SetReasoningMode(ReasoningThinking) },
+			data:  `{"type":"agent","stream":"reasoning","sessionKey":"s1","data":{"text":"hmm"}}`,
+			want:  `{"type":"thinking","content":"hmm","session":"s1"}`,
+		},
+		{
+			name: "chat final joins the text parts",
+			data: `{"type":"chat","state":"final","sessionKey":"s1","message":{"content":[{"type":"text","text":"Hello "},{"type":"image"},{"type":"text","text":"world"}]}}`,
+			want: `{"type":"complete","content":"Hello world","session":"s1"}`,
+		},
+		{
+			name: "empty chat delta is dropped",
+			data: `{"type":"chat","state":"delta","sessionKey":"s1","message":{"content":[]}}`,
+		},
+		{
+			name: "chat error",
+			data: `{"type":"chat","state":"error","sessionKey":"s1"}`,
+			want: `{"type":"error","content":"An error occurred","session":"s1"}`,
+		},
+		{
+			name: "whitespace delta is kept by default",
+			data: `{"type":"agent","stream":"assistant","sessionKey":"s1","data":{"text":"  "}}`,
+			want: `{"type":"progress","content":"  ","session":"s1"}`,
+		},
+		{
+			name:  "whitespace delta dropped when configured",
+			setup: func(b *Bridge) { b.SetDropWhitespaceDeltas(true) },
+			data:  `{"type":"agent","stream":"assistant","sessionKey":"s1","data":{"text":"  "}}`,
+		},
+		{
+			name: "missing sessionKey is forwarded with an empty session",
+			data: `{"type":"agent","stream":"assistant","data":{"text":"Hi"}}`,
+			want: `{"type":"progress","content":"Hi","session":""}`,
+		},
+		{
+			name:  "missing sessionKey dropped",
+			setup: func(b *Bridge) { b.SetMissingSessionKeyMode(MissingSessionKeyDrop, "") },
+			data:  `{"type":"agent","stream":"assistant","data":{"text":"Hi"}}`,
+		},
+		{
+			name:  "missing sessionKey sent to the fallback session",
+			setup: func(b *Bridge) { b.SetMissingSessionKeyMode(MissingSessionKeyFallback, "global") },
+			data:  `{"type":"chat","state":"final","message":{"content":[{"type":"text","text":"done"}]}}`,
+			want:  `{"type":"complete","content":"done","session":"global"}`,
+		},
+		{
+			name:  "allowlisted event frame passes through",
+			setup: func(b *Bridge) { b.SetEventAllowlist([]string{"custom"}) },
+			data:  `{"type":"event","event":"custom","payload":{"x":1}}`,
+			want:  "raw",
+		},
+		{
+			name:  "unknown type dropped by default",
+			setup: func(b *Bridge) { b.SetEventAllowlist([]string{"*"}) },
+			data:  `{"type":"custom"}`,
+		},
+		{
+			name: "unknown type forwarded when configured",
+			setup: func(b *Bridge) {
+				b.SetEventAllowlist([]string{"*"})
+				b.SetUnknownEventMode(UnknownEventsForward)
+			},
+			data: `{"type":"custom"}`,
+			want: "raw",
+		},
+		{
+			name:  "unparseable frame follows the unknown event policy",
+			setup: func(b *Bridge) { b.SetUnknownEventMode(UnknownEventsForward) },
+			data:  `not json`,
+			want:  "raw",
+		},
+		{
+			name: "unparseable frame dropped by default",
+			data: `not json`,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			b := NewBridge(nil, nil)
+			if tt.setup != nil {
+				tt.setup(b)
+			}
+
+			got := b.ConvertRaw([]byte(tt.data))
+			switch tt.want {
+			case "":
+				if got != nil {
+					t.Errorf("ConvertRaw = %s, want it dropped", got)
+				}
+			case "raw":
+				if string(got) != tt.data {
+					t.Errorf("ConvertRaw = %s, want the frame unchanged", got)
+				}
+			default:
+				var gotMsg, wantMsg map[string]interface{}
+				if err := json.Unmarshal(got, &gotMsg); err != nil {
+					t.Fatalf("ConvertRaw = %s, not JSON: %v", got, err)
+				}
+				if err := json.Unmarshal([]byte(tt.want), &wantMsg); err != nil {
+					t.Fatal(err)
+				}
+				if !reflect.DeepEqual(gotMsg, wantMsg) {
+					t.Errorf("ConvertRaw = %s, want %s", got, tt.want)
+				}
+			}
+		})
+	}
+}
+
+func TestResetConfirmation(t *testing.T) {
+	type step struct {
+		content string
+		advance time.Duration // Clock moves this far before the message
+	}
+	tests := []struct {
+		name        string
+		steps       []step
+		wantKind    ProcessOutcomeKind // Outcome of the last step
+		wantReset   bool
+		wantWebhook string // Substring of the last frame sent to the webhook
+	}{
+		{
+			name:        "bare reset asks for confirmation",
+			steps:       []step{{content: "/reset"}},
+			wantKind:    OutcomeResetPending,
+			wantWebhook: "Send /reset again within 1m0s",
+		},
+		{
+			name:        "repeating it within the window resets",
+			steps:       []step{{content: "/reset"}, {content: "/reset", advance: 30 * time.Second}},
+			wantKind:    OutcomeReset,
+			wantReset:   true,
+			wantWebhook: DefaultResetConfirmation,
+		},
+		{
+			name:        "timed-out confirmation does not reset",
+			steps:       []step{{content: "/reset"}, {content: "/reset", advance: time.Minute}},
+			wantKind:    OutcomeResetPending,
+			wantWebhook: "Send /reset again",
+		},
+		{
+			name:      "reset with a message needs no confirmation",
+			steps:     []step{{content: "/new hello"}},
+			wantKind:  OutcomeReset,
+			wantReset: true,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			b, hook := newTestBridge(t, &stubGateway{})
+			b.SetResetConfirmWindow(time.Minute)
+			clock := time.Unix(1700000000, 0)
+			b.resetConfirm.now = func() time.Time { return clock }
+
+			outcome, err := b.ProcessMessage([]byte(`{"id":"m0","content":"hello","session":"s1"}`))
+			if err != nil {
+				t.Fatalf("ProcessMessage: %v", err)
+			}
+			sessionKey := outcome.SessionKey
+			before, err := b.sessionStore.GetEntry(sessionKey)
+			if err != nil || before == nil {
+				t.Fatalf("GetEntry = %v, %v", before, err)
+			}
+
+			for i, s := range tt.steps {
+				clock = clock.Add(s.advance)
+				data, _ := json.Marshal(map[string]string{"id": fmt.Sprintf("m%d", i+1), "content": s.content, "session": "s1"})
+				if outcome, err = b.ProcessMessage(data); err != nil {
+					t.Fatalf("ProcessMessage(%q): %v", s.content, err)
+				}
+			}
+			if outcome.Kind != tt.wantKind {
+				t.Errorf("outcome = %q, want %q", outcome.Kind, tt.wantKind)
+			}
+
+			after, err := b.sessionStore.GetEntry(sessionKey)
+			if err != nil || after == nil {
+				t.Fatalf("GetEntry = %v, %v", after, err)
+			}
+			if reset := after.SessionID != before.SessionID; reset != tt.wantReset {
+				t.Errorf("session reset = %v, want %v", reset, tt.wantReset)
+			}
+
+			frames := hook.get()
+			if tt.wantWebhook != "" && (len(frames) == 0 || !strings.Contains(frames[len(frames)-1], tt.wantWebhook)) {
+				t.Errorf("webhook got %q, want a last frame containing %q", frames, tt.wantWebhook)
+			}
+		})
+	}
+}
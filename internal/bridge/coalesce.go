@@ -0,0 +1,122 @@
+package bridge
+
+import (
+	"sync"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
+)
+
+// CoalesceWindow configures how streaming progress messages are batched for one kind of session
+type CoalesceWindow struct {
+	Interval  time.Duration // How long to hold progress before sending (0 = send immediately)
+	MaxBuffer int           // Send early once this many progress messages are held (0 = no limit)
+}
+
+// Session kinds a coalesce window can be configured for.
+// CoalesceDefault applies to sessions without a more specific window.
+const (
+	CoalesceDefault = sessions.SessionKindDefault
+	CoalesceDM      = sessions.SessionKindDM
+	CoalesceGroup   = sessions.SessionKindGroup
+	CoalesceChannel = sessions.SessionKindChannel
+	CoalesceGlobal  = sessions.SessionKindGlobal
+)
+
+// coalescer holds back progress messages per session and only sends the latest one when the
+// session's window elapses. Progress content is cumulative, so the newest message replaces
+// the ones before it. Any other message for the session flushes held progress first so
+// ordering is preserved.
+type coalescer struct {
+	windows map[string]CoalesceWindow
+	send    func([]byte)
+
+	// mu is held while sending so a timer flush can't overtake a later message
+	mu      sync.Mutex
+	pending map[string]*pendingProgress
+}
+
+// pendingProgress is the latest held progress message for a session
+type pendingProgress struct {
+	data  []byte
+	count int
+	timer *time.Timer
+}
+
+func newCoalescer(windows map[string]CoalesceWindow, send func([]byte)) *coalescer {
+	return &coalescer{
+		windows: windows,
+		send:    send,
+		pending: make(map[string]*pendingProgress),
+	}
+}
+
+// coalesceEnabled reports whether any window actually holds messages back
+func coalesceEnabled(windows map[string]CoalesceWindow) bool {
+	for _, w := range windows {
+		if w.Interval > 0 {
+			return true
+		}
+	}
+	return false
+}
+
+// windowFor returns the window for a session, falling back to the default window
+func (c *coalescer) windowFor(sessionKey string) CoalesceWindow {
+	if w, ok := c.windows[sessions.SessionKind(sessionKey)]; ok {
+		return w
+	}
+	return c.windows[CoalesceDefault]
+}
+
+// submit sends or holds an outbound message
+func (c *coalescer) submit(msgType, sessionKey string, data []byte) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	w := c.windowFor(sessionKey)
+	if msgType != "progress" || w.Interval <= 0 {
+		c.flushLocked(sessionKey)
+		c.send(data)
+		return
+	}
+
+	p := c.pending[sessionKey]
+	if p == nil {
+		p = &pendingProgress{}
+		c.pending[sessionKey] = p
+		p.timer = time.AfterFunc(w.Interval, func() {
+			c.mu.Lock()
+			defer c.mu.Unlock()
+			if c.pending[sessionKey] == p {
+				c.flushLocked(sessionKey)
+			}
+		})
+	}
+	p.data = data
+	p.count++
+
+	if w.MaxBuffer > 0 && p.count >= w.MaxBuffer {
+		c.flushLocked(sessionKey)
+	}
+}
+
+// flushLocked sends the held progress for a session, if any (must be called with mu held)
+func (c *coalescer) flushLocked(sessionKey string) {
+	p := c.pending[sessionKey]
+	if p == nil {
+		return
+	}
+	delete(c.pending, sessionKey)
+	p.timer.Stop()
+	c.send(p.data)
+}
+
+// flushAll sends all held progress messages
+func (c *coalescer) flushAll() {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	for sessionKey := range c.pending {
+		c.flushLocked(sessionKey)
+	}
+}
@@ -0,0 +1,141 @@
+package bridge
+
+import (
+	"reflect"
+	"sync"
+	"testing"
+	"time"
+)
+
+// sentFrames records what a coalescer sends
+type sentFrames struct {
+	mu     sync.Mutex
+	frames []string
+}
+
+func (s *sentFrames) send(data []byte) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.frames = append(s.frames, string(data))
+}
+
+func (s *sentFrames) get() []string {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return append([]string{}, s.frames...)
+}
+
+func TestCoalescerSubmit(t *testing.T) {
+	const (
+		group = "agent:main:webhook:group:g1"
+		dm    = "webhook:m1"
+	)
+	type submit struct {
+		msgType, session, data string
+	}
+
+	// Intervals are long enough that no timer fires during a test
+	tests := []struct {
+		name     string
+		windows  map[string]CoalesceWindow
+		submits  []submit
+		flushAll bool
+		want     []string
+	}{
+		{
+			name:    "progress is held",
+			windows: map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour}},
+			submits: []submit{{"progress", group, "p1"}, {"progress", group, "p2"}},
+			want:    []string{},
+		},
+		{
+			name:    "other messages flush the latest progress first",
+			windows: map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour}},
+			submits: []submit{{"progress", group, "p1"}, {"progress", group, "p2"}, {"complete", group, "done"}},
+			want:    []string{"p2", "done"},
+		},
+		{
+			name:    "max buffer sends early",
+			windows: map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour, MaxBuffer: 2}},
+			submits: []submit{{"progress", group, "p1"}, {"progress", group, "p2"}, {"progress", group, "p3"}},
+			want:    []string{"p2"},
+		},
+		{
+			name:    "sessions are held separately",
+			windows: map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour}},
+			submits: []submit{{"progress", group, "g"}, {"progress", dm, "d"}, {"complete", dm, "done"}},
+			want:    []string{"d", "done"},
+		},
+		{
+			name:    "kinds without a window are not held",
+			windows: map[string]CoalesceWindow{CoalesceGroup: {Interval: time.Hour}},
+			submits: []submit{{"progress", group, "g"}, {"progress", dm, "d"}},
+			want:    []string{"d"},
+		},
+		{
+			name:    "specific kind beats the default",
+			windows: map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour}, CoalesceDM: {}},
+			submits: []submit{{"progress", group, "g"}, {"progress", dm, "d"}},
+			want:    []string{"d"},
+		},
+		{
+			name:     "flushAll sends everything held",
+			windows:  map[string]CoalesceWindow{CoalesceDefault: {Interval: time.Hour}},
+			submits:  []submit{{"progress", group, "g1"}, {"progress", group, "g2"}},
+			flushAll: true,
+			want:     []string{"g2"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var sent sentFrames
+			c := newCoalescer(tt.windows, sent.send)
+			for _, s := range tt.submits {
+				c.submit(s.msgType, s.session, []byte(s.data))
+			}
+			if tt.flushAll {
+				c.flushAll()
+			}
+			if got := sent.get(); !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("sent %q, want %q", got, tt.want)
+			}
+			c.flushAll() // Stop pending timers
+		})
+	}
+}
+
+func TestCoalescerIntervalFlush(t *testing.T) {
+	var sent sentFrames
+	c := newCoalescer(map[string]CoalesceWindow{CoalesceDefault: {Interval: 20 * time.Millisecond}}, sent.send)
+	c.submit("progress", "webhook:m1", []byte("p1"))
+	c.submit("progress", "webhook:m1", []byte("p2"))
+
+	deadline := time.Now().Add(time.Second)
+	for len(sent.get()) == 0 && time.Now().Before(deadline) {
+		time.Sleep(5 * time.Millisecond)
+	}
+	if got := sent.get(); !reflect.DeepEqual(got, []string{"p2"}) {
+		t.Errorf("sent %q after the interval, want [\"p2\"]", got)
+	}
+}
+
+func TestCoalesceEnabled(t *testing.T) {
+	tests := []struct {
+		name    string
+		windows map[string]CoalesceWindow
+		want    bool
+	}{
+		{name: "no windows"},
+		{name: "only zero intervals", windows: map[string]CoalesceWindow{CoalesceDM: {MaxBuffer: 5}}},
+		{name: "one positive interval", windows: map[string]CoalesceWindow{CoalesceDM: {}, CoalesceGroup: {Interval: time.Second}}, want: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := coalesceEnabled(tt.windows); got != tt.want {
+				t.Errorf("coalesceEnabled = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,53 @@
+package bridge
+
+import "sync"
+
+// ReplyConverter adapts an outbound message ("type", "content", "session" and optional
+// "seq") to the modality of the request it answers, e.g. adding a "voice" field or
+// reshaping the content. It edits the message in place.
+type ReplyConverter func(reply map[string]interface{})
+
+// replyConverters selects a ReplyConverter by the `type` of the last inbound message of
+// each session, so replies follow the format of the request
+type replyConverters struct {
+	mu           sync.Mutex
+	byType       map[string]ReplyConverter
+	sessionTypes map[string]string // Session key -> inbound type of its last message
+}
+
+// RegisterReplyConverter makes replies to sessions whose last inbound message had the
+// given `type` pass through conv before they are sent. Replies to untyped messages, or
+// to types without a converter, keep the default format.
+func (b *Bridge) RegisterReplyConverter(inboundType string, conv ReplyConverter) {
+	b.converters.mu.Lock()
+	defer b.converters.mu.Unlock()
+	if b.converters.byType == nil {
+		b.converters.byType = make(map[string]ReplyConverter)
+		b.converters.sessionTypes = make(map[string]string)
+	}
+	b.converters.byType[inboundType] = conv
+}
+
+// noteInboundType remembers which converter a session's replies should use
+func (r *replyConverters) noteInboundType(sessionKey, inboundType string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if r.byType == nil {
+		return
+	}
+	if _, ok := r.byType[inboundType]; ok {
+		r.sessionTypes[sessionKey] = inboundType
+	} else {
+		delete(r.sessionTypes, sessionKey)
+	}
+}
+
+// apply runs the converter selected for a session, if any, over an outbound message
+func (r *replyConverters) apply(sessionKey string, reply map[string]interface{}) {
+	r.mu.Lock()
+	conv := r.byType[r.sessionTypes[sessionKey]]
+	r.mu.Unlock()
+	if conv != nil {
+		conv(reply)
+	}
+}
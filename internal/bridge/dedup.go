@@ -0,0 +1,90 @@
+package bridge
+
+import (
+	"container/list"
+	"sync"
+	"time"
+)
+
+// Defaults for inbound message deduplication
+const (
+	DefaultDedupSize = 1000
+	DefaultDedupTTL  = 10 * time.Minute
+)
+
+// dedupCache remembers recently processed webhook message IDs so redeliveries (e.g. a
+// webhook server replaying after a reconnect) aren't forwarded twice. It holds at most
+// size IDs, evicting the least recently seen, and forgets IDs older than ttl.
+type dedupCache struct {
+	mu    sync.Mutex
+	size  int
+	ttl   time.Duration
+	order *list.List // Front = most recently seen; values are *dedupEntry
+	byID  map[string]*list.Element
+	now   func() time.Time // Clock, replaceable in tests
+}
+
+type dedupEntry struct {
+	id     string
+	seenAt time.Time
+}
+
+func newDedupCache(size int, ttl time.Duration) *dedupCache {
+	return &dedupCache{
+		size:  size,
+		ttl:   ttl,
+		order: list.New(),
+		byID:  make(map[string]*list.Element),
+		now:   time.Now,
+	}
+}
+
+// Seen reports whether id was already processed within the TTL, and records it if not
+func (d *dedupCache) Seen(id string) bool {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	now := d.now()
+	d.expire(now)
+	if el, ok := d.byID[id]; ok {
+		if !d.expired(el, now) {
+			d.order.MoveToFront(el)
+			return true
+		}
+		d.remove(el)
+	}
+
+	d.byID[id] = d.order.PushFront(&dedupEntry{id: id, seenAt: now})
+	for d.order.Len() > d.size {
+		d.remove(d.order.Back())
+	}
+	return false
+}
+
+// expire drops expired IDs from the back of the list so they don't take up room that a
+// burst of new IDs needs. Duplicates move to the front without a new seenAt, so an expired
+// entry can sit further up; Seen checks the one it finds.
+func (d *dedupCache) expire(now time.Time) {
+	for el := d.order.Back(); el != nil && d.expired(el, now); el = d.order.Back() {
+		d.remove(el)
+	}
+}
+
+// expired reports whether the entry was seen more than ttl ago
+func (d *dedupCache) expired(el *list.Element, now time.Time) bool {
+	return d.ttl > 0 && now.Sub(el.Value.(*dedupEntry).seenAt) >= d.ttl
+}
+
+func (d *dedupCache) remove(el *list.Element) {
+	d.order.Remove(el)
+	delete(d.byID, el.Value.(*dedupEntry).id)
+}
+
+// Forget drops id so a redelivery is processed again (used when processing failed)
+func (d *dedupCache) Forget(id string) {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	if el, ok := d.byID[id]; ok {
+		d.remove(el)
+	}
+}
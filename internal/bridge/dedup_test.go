@@ -0,0 +1,106 @@
+package bridge
+
+import (
+	"testing"
+	"time"
+)
+
+func TestDedupCacheSeen(t *testing.T) {
+	type step struct {
+		after time.Duration // Clock advance before the call
+		id    string
+		seen  bool
+	}
+	tests := []struct {
+		name  string
+		size  int
+		ttl   time.Duration
+		steps []step
+	}{
+		{
+			name: "duplicate within the TTL is skipped",
+			size: 10,
+			ttl:  time.Minute,
+			steps: []step{
+				{id: "a", seen: false},
+				{after: 30 * time.Second, id: "a", seen: true},
+			},
+		},
+		{
+			name: "duplicate after the TTL is processed again",
+			size: 10,
+			ttl:  time.Minute,
+			steps: []step{
+				{id: "a", seen: false},
+				{after: time.Minute, id: "a", seen: false},
+				{after: time.Second, id: "a", seen: true},
+			},
+		},
+		{
+			name: "a refreshed duplicate still expires from its first sighting",
+			size: 10,
+			ttl:  time.Minute,
+			steps: []step{
+				{id: "a", seen: false},
+				{id: "b", seen: false},
+				{after: 40 * time.Second, id: "a", seen: true},
+				{after: 20 * time.Second, id: "a", seen: false},
+			},
+		},
+		{
+			name: "size bound evicts the least recently seen",
+			size: 2,
+			ttl:  time.Minute,
+			steps: []step{
+				{id: "a", seen: false},
+				{id: "b", seen: false},
+				{id: "a", seen: true},
+				{id: "c", seen: false},
+				{id: "b", seen: false},
+				{id: "a", seen: false},
+			},
+		},
+		{
+			name: "expired IDs make room before live ones are evicted",
+			size: 2,
+			ttl:  time.Minute,
+			steps: []step{
+				{id: "a", seen: false},
+				{after: 50 * time.Second, id: "b", seen: false},
+				{after: 10 * time.Second, id: "c", seen: false},
+				{id: "b", seen: true},
+			},
+		},
+		{
+			name: "no TTL keeps IDs until evicted",
+			size: 10,
+			steps: []step{
+				{id: "a", seen: false},
+				{after: 24 * time.Hour, id: "a", seen: true},
+			},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			now := time.Unix(1700000000, 0)
+			d := newDedupCache(tt.size, tt.ttl)
+			d.now = func() time.Time { return now }
+			for i, s := range tt.steps {
+				now = now.Add(s.after)
+				if got := d.Seen(s.id); got != s.seen {
+					t.Fatalf("step %d: Seen(%q) = %v, want %v", i, s.id, got, s.seen)
+				}
+			}
+		})
+	}
+}
+
+func TestDedupCacheForget(t *testing.T) {
+	d := newDedupCache(10, time.Minute)
+	d.Seen("a")
+	d.Forget("a")
+	if d.Seen("a") {
+		t.Error("Seen reported a forgotten ID as a duplicate")
+	}
+}
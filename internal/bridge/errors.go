@@ -0,0 +1,64 @@
+package bridge
+
+import (
+	"errors"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/openclaw"
+)
+
+// Failure kinds returned by the Bridge's exported methods. Match them with errors.Is;
+// the underlying cause stays reachable through errors.Is/As as well.
+var (
+	ErrNotConnected  = errors.New("not connected")
+	ErrSerialization = errors.New("serialization failed")
+	ErrStore         = errors.New("session store error")
+	ErrGatewaySend   = errors.New("gateway send failed")
+	ErrWebhookSend   = errors.New("webhook send failed")
+)
+
+// errNoStore is the cause for store operations on a bridge without a session store
+var errNoStore = errors.New("no session store configured")
+
+// Error describes a failed Bridge operation
+type Error struct {
+	Op   string // What the bridge was doing, e.g. "forward message"
+	Kind error  // One of the Err* kinds above
+	Err  error  // Underlying cause
+}
+
+func (e *Error) Error() string {
+	return e.Op + ": " + e.Kind.Error() + ": " + e.Err.Error()
+}
+
+// Unwrap exposes both the kind and the cause to errors.Is and errors.As
+func (e *Error) Unwrap() []error {
+	return []error{e.Kind, e.Err}
+}
+
+// storeError wraps a session store failure
+func storeError(op string, err error) error {
+	if err == nil {
+		return nil
+	}
+	return &Error{Op: op, Kind: ErrStore, Err: err}
+}
+
+// serializationError wraps a JSON encoding or decoding failure
+func serializationError(op string, err error) error {
+	if err == nil {
+		return nil
+	}
+	return &Error{Op: op, Kind: ErrSerialization, Err: err}
+}
+
+// gatewayError wraps a failed send to the gateway
+func gatewayError(op string, err error) error {
+	if err == nil {
+		return nil
+	}
+	kind := ErrGatewaySend
+	if errors.Is(err, openclaw.ErrNotConnected) {
+		kind = ErrNotConnected
+	}
+	return &Error{Op: op, Kind: kind, Err: err}
+}
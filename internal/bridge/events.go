@@ -0,0 +1,71 @@
+package bridge
+
+import (
+	"sync"
+	"time"
+)
+
+// Bridge event types
+const (
+	EventMessageProcessed = "message.processed" // A webhook message was handled
+	EventOutbound         = "outbound"          // A message was forwarded to the webhook
+	EventSessionReset     = "session.reset"     // A session was reset through ResetSession
+)
+
+// BridgeEvent describes something the bridge did, for live introspection (dashboards, admin tools)
+type BridgeEvent struct {
+	Type       string `json:"type"`
+	Time       int64  `json:"ts"`                   // Unix milliseconds
+	SessionKey string `json:"sessionKey,omitempty"` // Session the event belongs to, if any
+	Outcome    string `json:"outcome,omitempty"`    // ProcessOutcomeKind for processed messages
+	Kind       string `json:"kind,omitempty"`       // Outbound message type (progress, complete, ...)
+	Reason     string `json:"reason,omitempty"`     // Why a message was skipped
+	Error      string `json:"error,omitempty"`
+}
+
+// eventBus fans bridge events out to subscribers. Slow subscribers miss events rather
+// than stalling the bridge. The zero value is ready to use.
+type eventBus struct {
+	mu   sync.Mutex
+	subs map[chan BridgeEvent]struct{}
+}
+
+// Subscribe returns a channel receiving bridge events and a function that ends the
+// subscription. Events are dropped while the channel's buffer is full.
+func (b *Bridge) Subscribe(buffer int) (<-chan BridgeEvent, func()) {
+	ch := make(chan BridgeEvent, buffer)
+
+	b.events.mu.Lock()
+	if b.events.subs == nil {
+		b.events.subs = make(map[chan BridgeEvent]struct{})
+	}
+	b.events.subs[ch] = struct{}{}
+	b.events.mu.Unlock()
+
+	var once sync.Once
+	return ch, func() {
+		once.Do(func() {
+			b.events.mu.Lock()
+			delete(b.events.subs, ch)
+			b.events.mu.Unlock()
+			close(ch)
+		})
+	}
+}
+
+// publish delivers an event to all subscribers without blocking
+func (b *Bridge) publish(ev BridgeEvent) {
+	b.events.mu.Lock()
+	defer b.events.mu.Unlock()
+
+	if len(b.events.subs) == 0 {
+		return
+	}
+	ev.Time = time.Now().UnixMilli()
+	for ch := range b.events.subs {
+		select {
+		case ch <- ev:
+		default:
+		}
+	}
+}
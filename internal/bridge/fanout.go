@@ -0,0 +1,136 @@
+package bridge
+
+import (
+	"log"
+	"sync"
+	"sync/atomic"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
+)
+
+// fanoutQueueSize is how many frames may wait for each webhook of a fan-out. Each webhook
+// is written by its own goroutine, so a slow one drops its own frames instead of holding
+// up the others.
+const fanoutQueueSize = 256
+
+// fanoutDrainTimeout bounds how long Flush waits for fan-out queues to empty
+const fanoutDrainTimeout = 5 * time.Second
+
+// WebhookDeliveryMode decides which webhooks get an outbound message when there are several
+type WebhookDeliveryMode string
+
+const (
+	DeliveryBroadcast WebhookDeliveryMode = "broadcast" // Every webhook gets every message (default)
+	DeliveryBalance   WebhookDeliveryMode = "balance"   // Each message goes to one webhook, by weighted round-robin
+)
+
+// WebhookSender is a connection outbound frames are written to, e.g. a *webhook.Client
+type WebhookSender interface {
+	Send(data []byte) error
+}
+
+// WebhookTarget is one webhook connection of a fan-out
+type WebhookTarget struct {
+	Name   string // Shown in logs, e.g. the URL
+	Sender WebhookSender
+	Weight int // Share of the messages in balance mode (less than 1 counts as 1)
+}
+
+// webhookFanout delivers outbound frames to several webhooks, either to all of them or
+// to one per frame picked by smooth weighted round-robin (the spread nginx uses, which
+// interleaves targets instead of sending runs to the heaviest)
+type webhookFanout struct {
+	mode    WebhookDeliveryMode
+	targets []WebhookTarget
+	queues  []chan []byte // Frames waiting for each target's writer
+	pending atomic.Int64  // Frames queued or being written, across all targets
+
+	mu      sync.Mutex
+	current []int // Running score per target
+	total   int   // Sum of the weights
+}
+
+func newWebhookFanout(targets []WebhookTarget, mode WebhookDeliveryMode) *webhookFanout {
+	f := &webhookFanout{
+		mode:    mode,
+		targets: targets,
+		queues:  make([]chan []byte, len(targets)),
+		current: make([]int, len(targets)),
+	}
+	for i := range f.targets {
+		if f.targets[i].Weight < 1 {
+			f.targets[i].Weight = 1
+		}
+		f.total += f.targets[i].Weight
+		f.queues[i] = make(chan []byte, fanoutQueueSize)
+		go f.write(i)
+	}
+	return f
+}
+
+// write sends the frames queued for target i, in order, for the life of the process
+func (f *webhookFanout) write(i int) {
+	for data := range f.queues[i] {
+		f.sendTo(f.targets[i], data)
+		f.pending.Add(-1)
+	}
+}
+
+// next returns the index of the target for the next frame in balance mode
+func (f *webhookFanout) next() int {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+
+	best := 0
+	for i, t := range f.targets {
+		f.current[i] += t.Weight
+		if f.current[i] > f.current[best] {
+			best = i
+		}
+	}
+	f.current[best] -= f.total
+	return best
+}
+
+// send queues one frame for delivery according to the delivery mode. It never waits for
+// a webhook: a frame for one whose queue is full is dropped.
+func (f *webhookFanout) send(data []byte) {
+	if f.mode == DeliveryBalance {
+		f.enqueue(f.next(), data)
+		return
+	}
+	for i := range f.targets {
+		f.enqueue(i, data)
+	}
+}
+
+func (f *webhookFanout) enqueue(i int, data []byte) {
+	f.pending.Add(1)
+	select {
+	case f.queues[i] <- data:
+	default:
+		f.pending.Add(-1)
+		metrics.Inc(metrics.WebhookFanoutDroppedTotal)
+		log.Printf("[Bridge] Webhook %s is not keeping up (%d frames queued), dropped a frame", f.targets[i].Name, fanoutQueueSize)
+	}
+}
+
+// drain waits up to timeout for the queued frames to be written, reporting whether they were
+func (f *webhookFanout) drain(timeout time.Duration) bool {
+	deadline := time.Now().Add(timeout)
+	for f.pending.Load() > 0 {
+		if time.Now().After(deadline) {
+			return false
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+	return true
+}
+
+func (f *webhookFanout) sendTo(t WebhookTarget, data []byte) {
+	if err := t.Sender.Send(data); err != nil {
+		metrics.Inc(metrics.WebhookSendErrorsTotal)
+		log.Printf("[Bridge] Failed to send to webhook %s: %v", t.Name, err)
+	}
+}
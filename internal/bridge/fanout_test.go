@@ -0,0 +1,150 @@
+package bridge
+
+import (
+	"errors"
+	"sync/atomic"
+	"testing"
+	"time"
+)
+
+// countingSender records how many frames it was sent
+type countingSender struct {
+	count atomic.Int64
+	err   error
+}
+
+func (s *countingSender) Send([]byte) error {
+	s.count.Add(1)
+	return s.err
+}
+
+func (s *countingSender) frames() int {
+	return int(s.count.Load())
+}
+
+// stalledSender blocks every Send until release is closed
+type stalledSender struct {
+	release chan struct{}
+}
+
+func (s *stalledSender) Send([]byte) error {
+	<-s.release
+	return nil
+}
+
+func TestWebhookFanoutBalanceFollowsWeights(t *testing.T) {
+	tests := []struct {
+		name     string
+		weights  []int
+		messages int
+		want     []int
+	}{
+		{name: "equal weights", weights: []int{1, 1, 1}, messages: 300, want: []int{100, 100, 100}},
+		{name: "uneven weights", weights: []int{5, 1, 1}, messages: 700, want: []int{500, 100, 100}},
+		{name: "zero weight counts as one", weights: []int{3, 0}, messages: 400, want: []int{300, 100}},
+		{name: "partial round", weights: []int{2, 1}, messages: 100, want: []int{67, 33}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			senders := make([]*countingSender, len(tt.weights))
+			targets := make([]WebhookTarget, len(tt.weights))
+			for i, w := range tt.weights {
+				senders[i] = &countingSender{}
+				targets[i] = WebhookTarget{Name: "webhook", Sender: senders[i], Weight: w}
+			}
+			f := newWebhookFanout(targets, DeliveryBalance)
+			for i := 0; i < tt.messages; i++ {
+				f.send([]byte(`{}`))
+			}
+			if !f.drain(time.Second) {
+				t.Fatal("fan-out queues did not drain")
+			}
+			for i, s := range senders {
+				// Smooth weighted round-robin is exact per full round, so allow one frame of slack
+				if diff := s.frames() - tt.want[i]; diff < -1 || diff > 1 {
+					t.Errorf("target %d got %d frames, want about %d", i, s.frames(), tt.want[i])
+				}
+			}
+		})
+	}
+}
+
+func TestWebhookFanoutBalanceInterleaves(t *testing.T) {
+	senders := []*countingSender{{}, {}}
+	f := newWebhookFanout([]WebhookTarget{
+		{Name: "a", Sender: senders[0], Weight: 2},
+		{Name: "b", Sender: senders[1], Weight: 1},
+	}, DeliveryBalance)
+
+	var order []int
+	for i := 0; i < 6; i++ {
+		order = append(order, f.next())
+	}
+	want := []int{0, 1, 0, 0, 1, 0}
+	for i := range want {
+		if order[i] != want[i] {
+			t.Fatalf("pick order = %v, want %v", order, want)
+		}
+	}
+}
+
+func TestWebhookFanoutBroadcast(t *testing.T) {
+	failing := &countingSender{err: errors.New("down")}
+	healthy := &countingSender{}
+	f := newWebhookFanout([]WebhookTarget{
+		{Name: "failing", Sender: failing, Weight: 5},
+		{Name: "healthy", Sender: healthy, Weight: 1},
+	}, DeliveryBroadcast)
+
+	for i := 0; i < 10; i++ {
+		f.send([]byte(`{}`))
+	}
+	if !f.drain(time.Second) {
+		t.Fatal("fan-out queues did not drain")
+	}
+	if failing.frames() != 10 || healthy.frames() != 10 {
+		t.Errorf("broadcast sent %d and %d frames, want 10 to each", failing.frames(), healthy.frames())
+	}
+}
+
+func TestWebhookFanoutStalledWebhookDoesNotBlockOthers(t *testing.T) {
+	stalled := &stalledSender{release: make(chan struct{})}
+	defer close(stalled.release)
+	healthy := &countingSender{}
+	f := newWebhookFanout([]WebhookTarget{
+		{Name: "stalled", Sender: stalled},
+		{Name: "healthy", Sender: healthy},
+	}, DeliveryBroadcast)
+
+	// Far more frames than the stalled webhook's queue holds, each of which must reach
+	// the healthy webhook promptly
+	frames := 3 * fanoutQueueSize
+	done := make(chan int)
+	go func() {
+		for i := 1; i <= frames; i++ {
+			f.send([]byte(`{}`))
+			deadline := time.Now().Add(time.Second)
+			for healthy.frames() < i {
+				if time.Now().After(deadline) {
+					done <- i
+					return
+				}
+				time.Sleep(100 * time.Microsecond)
+			}
+		}
+		done <- 0
+	}()
+	select {
+	case stuck := <-done:
+		if stuck != 0 {
+			t.Fatalf("healthy webhook did not get frame %d", stuck)
+		}
+	case <-time.After(10 * time.Second):
+		t.Fatal("send blocked behind the stalled webhook")
+	}
+	// The stalled webhook holds one frame in Send plus a full queue; the rest were dropped
+	if pending := f.pending.Load(); pending > fanoutQueueSize+1 {
+		t.Errorf("%d frames pending for the stalled webhook, want at most %d", pending, fanoutQueueSize+1)
+	}
+}
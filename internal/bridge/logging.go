@@ -0,0 +1,119 @@
+package bridge
+
+import (
+	"log"
+	"sync"
+	"sync/atomic"
+	"time"
+)
+
+// MessageLogLevel controls how much of each relayed message is logged
+type MessageLogLevel string
+
+const (
+	MessageLogOff     MessageLogLevel = "off"     // Don't log relayed messages
+	MessageLogBytes   MessageLogLevel = "bytes"   // Log the message size only
+	MessageLogContent MessageLogLevel = "content" // Log the full message body
+)
+
+// directionLogger logs the messages relayed in one direction.
+// Only every Nth message is logged when sampling is enabled; errors are logged elsewhere
+// and are never sampled.
+type directionLogger struct {
+	label    string
+	level    MessageLogLevel
+	every    uint64
+	truncate int // Max content bytes logged (0 = unlimited)
+	count    atomic.Uint64
+
+	// Byte-count logs are limited to one per minInterval (0 = no limit); messages in
+	// between are summed up and reported in a summary line once the interval ends
+	minInterval     time.Duration
+	mu              sync.Mutex
+	lastLogged      time.Time
+	suppressed      int
+	suppressedBytes int
+	summaryTimer    *time.Timer
+}
+
+func newDirectionLogger(label string) *directionLogger {
+	return &directionLogger{
+		label: label,
+		level: MessageLogBytes,
+		every: 1,
+	}
+}
+
+// configure sets the level and sampling interval (values below 1 log every message)
+func (l *directionLogger) configure(level MessageLogLevel, every int) {
+	if every < 1 {
+		every = 1
+	}
+	l.level = level
+	l.every = uint64(every)
+}
+
+// log records a relayed message according to the configured level and sampling
+func (l *directionLogger) log(data []byte) {
+	if l.level == MessageLogOff {
+		return
+	}
+	n := l.count.Add(1)
+	if (n-1)%l.every != 0 {
+		return
+	}
+
+	switch l.level {
+	case MessageLogContent:
+		log.Printf("[Bridge] %s: %s", l.label, truncateContent(string(data), l.truncate))
+	default:
+		l.logBytes(len(data))
+	}
+}
+
+// logBytes logs a message size, holding back logs that come within minInterval of the last one
+func (l *directionLogger) logBytes(size int) {
+	if l.minInterval <= 0 {
+		log.Printf("[Bridge] %s: %d bytes", l.label, size)
+		return
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+
+	now := time.Now()
+	if now.Sub(l.lastLogged) >= l.minInterval {
+		l.summaryLocked()
+		l.lastLogged = now
+		log.Printf("[Bridge] %s: %d bytes", l.label, size)
+		return
+	}
+
+	l.suppressed++
+	l.suppressedBytes += size
+	if l.summaryTimer == nil {
+		// Report a burst once its interval is over, even if no further message arrives
+		l.summaryTimer = time.AfterFunc(l.lastLogged.Add(l.minInterval).Sub(now), func() {
+			l.mu.Lock()
+			defer l.mu.Unlock()
+			l.summaryTimer = nil
+			l.summaryLocked()
+		})
+	}
+}
+
+// summaryLocked logs and resets the held-back message count (must be called with mu held)
+func (l *directionLogger) summaryLocked() {
+	if l.summaryTimer != nil {
+		l.summaryTimer.Stop()
+		l.summaryTimer = nil
+	}
+	if l.suppressed == 0 {
+		return
+	}
+	log.Printf("[Bridge] %s: forwarded %d more messages (%d bytes) in the last %v",
+		l.label, l.suppressed, l.suppressedBytes, l.minInterval)
+	l.suppressed = 0
+	l.suppressedBytes = 0
+	l.lastLogged = time.Now()
+}
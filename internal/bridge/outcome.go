@@ -0,0 +1,25 @@
+package bridge
+
+// ProcessOutcomeKind describes what ProcessMessage did with a webhook message
+type ProcessOutcomeKind string
+
+const (
+	OutcomeForwarded    ProcessOutcomeKind = "forwarded"     // Sent to the gateway as an agent request
+	OutcomeReset        ProcessOutcomeKind = "reset"         // Session reset; any remaining content was forwarded
+	OutcomeResetPending ProcessOutcomeKind = "reset-pending" // Bare reset trigger, waiting to be sent again to confirm
+	OutcomeCommand      ProcessOutcomeKind = "command"       // Handled as a slash command
+	OutcomeControl      ProcessOutcomeKind = "control"       // Handled as a session control message
+	OutcomeEcho         ProcessOutcomeKind = "echo"          // Echoed back to the webhook (echo mode)
+	OutcomeSkipped      ProcessOutcomeKind = "skipped"       // Ignored; see Reason
+)
+
+// ProcessOutcome is the result of processing one webhook message
+type ProcessOutcome struct {
+	Kind       ProcessOutcomeKind
+	SessionKey string // Resolved session key, if the message got that far
+	Reason     string // Why the message was skipped
+}
+
+func skipped(reason string) ProcessOutcome {
+	return ProcessOutcome{Kind: OutcomeSkipped, Reason: reason}
+}
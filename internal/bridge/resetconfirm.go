@@ -0,0 +1,43 @@
+package bridge
+
+import (
+	"sync"
+	"time"
+)
+
+// resetConfirmer makes a bare reset trigger take effect only when it is sent again
+// within window, so a stray "/reset" doesn't throw away a conversation
+type resetConfirmer struct {
+	mu      sync.Mutex
+	window  time.Duration
+	pending map[string]time.Time // Session key -> when its confirmation expires
+	now     func() time.Time     // Clock, replaceable in tests
+}
+
+func newResetConfirmer(window time.Duration) *resetConfirmer {
+	return &resetConfirmer{
+		window:  window,
+		pending: make(map[string]time.Time),
+		now:     time.Now,
+	}
+}
+
+// confirm reports whether a bare reset trigger for sessionKey confirms an earlier one.
+// If not, it starts waiting for the confirmation.
+func (r *resetConfirmer) confirm(sessionKey string) bool {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	now := r.now()
+	for key, expires := range r.pending {
+		if !now.Before(expires) {
+			delete(r.pending, key)
+		}
+	}
+	if _, ok := r.pending[sessionKey]; ok {
+		delete(r.pending, sessionKey)
+		return true
+	}
+	r.pending[sessionKey] = now.Add(r.window)
+	return false
+}
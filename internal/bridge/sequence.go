@@ -0,0 +1,31 @@
+package bridge
+
+import "sync"
+
+// sequencer hands out per-session increasing sequence numbers for outbound messages,
+// letting receivers on unordered transports restore the original order
+type sequencer struct {
+	mu   sync.Mutex
+	next map[string]uint64
+}
+
+func newSequencer() *sequencer {
+	return &sequencer{
+		next: make(map[string]uint64),
+	}
+}
+
+// Next returns the next sequence number for a session, starting at 1
+func (s *sequencer) Next(sessionKey string) uint64 {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.next[sessionKey]++
+	return s.next[sessionKey]
+}
+
+// Reset restarts the sequence for a session
+func (s *sequencer) Reset(sessionKey string) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	delete(s.next, sessionKey)
+}
@@ -0,0 +1,28 @@
+package bridge
+
+import (
+	"fmt"
+	"unicode/utf8"
+)
+
+// truncateUTF8 returns at most maxBytes bytes of s without splitting a multi-byte character
+func truncateUTF8(s string, maxBytes int) string {
+	if len(s) <= maxBytes {
+		return s
+	}
+	cut := maxBytes
+	for cut > 0 && !utf8.RuneStart(s[cut]) {
+		cut--
+	}
+	return s[:cut]
+}
+
+// truncateContent shortens content for logging to at most maxBytes bytes plus a
+// "...(N more bytes)" marker. A maxBytes of 0 or less leaves content untouched.
+func truncateContent(content string, maxBytes int) string {
+	if maxBytes <= 0 || len(content) <= maxBytes {
+		return content
+	}
+	kept := truncateUTF8(content, maxBytes)
+	return fmt.Sprintf("%s...(%d more bytes)", kept, len(content)-len(kept))
+}
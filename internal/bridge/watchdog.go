@@ -0,0 +1,132 @@
+package bridge
+
+import (
+	"strings"
+	"sync"
+	"time"
+)
+
+// Messages sent when the gateway goes quiet on a session
+const (
+	watchdogNoticeText  = "Still working…"
+	watchdogTimeoutText = "Timed out waiting for a response"
+)
+
+// responseWatchdog tracks sessions with a prompt in flight and tells the user when the
+// gateway stops sending events for them: a progress notice after noticeAfter, then a single
+// error after timeout. Every event for the session restarts the clock; a final
+// (complete/error) event stops it.
+type responseWatchdog struct {
+	noticeAfter time.Duration
+	timeout     time.Duration
+	notify      func(msgType, content, sessionKey string)
+	afterFunc   func(d time.Duration, f func()) watchdogTimer // Timer factory, replaceable in tests
+
+	mu      sync.Mutex
+	pending map[string]*responseWatch
+}
+
+// watchdogTimer is the part of *time.Timer the watchdog uses
+type watchdogTimer interface {
+	Stop() bool
+}
+
+// responseWatch holds the timers for one session
+type responseWatch struct {
+	notice watchdogTimer
+	expire watchdogTimer
+}
+
+func newResponseWatchdog(noticeAfter, timeout time.Duration, notify func(msgType, content, sessionKey string)) *responseWatchdog {
+	return &responseWatchdog{
+		noticeAfter: noticeAfter,
+		timeout:     timeout,
+		notify:      notify,
+		pending:     make(map[string]*responseWatch),
+		afterFunc:   timeAfterFunc,
+	}
+}
+
+// timeAfterFunc is the real timer factory
+func timeAfterFunc(d time.Duration, f func()) watchdogTimer {
+	return time.AfterFunc(d, f)
+}
+
+// start arms (or re-arms) the timers for a session after a prompt was forwarded
+func (w *responseWatchdog) start(sessionKey string) {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	w.armLocked(sessionKey)
+}
+
+// activity records an outbound event for a session. Final events stop the watch;
+// anything else restarts its timers.
+func (w *responseWatchdog) activity(eventSessionKey string, final bool) {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+
+	sessionKey, ok := w.findLocked(eventSessionKey)
+	if !ok {
+		return
+	}
+	if final {
+		w.stopLocked(sessionKey)
+		return
+	}
+	w.armLocked(sessionKey)
+}
+
+// findLocked maps an event's session key to a watched key. The gateway may report
+// the canonical "agent:<id>:..." form of a key the bridge sent without that prefix.
+func (w *responseWatchdog) findLocked(eventSessionKey string) (string, bool) {
+	key := strings.ToLower(eventSessionKey)
+	if _, ok := w.pending[key]; ok {
+		return key, true
+	}
+	for sessionKey := range w.pending {
+		if strings.HasSuffix(key, ":"+sessionKey) {
+			return sessionKey, true
+		}
+	}
+	return "", false
+}
+
+func (w *responseWatchdog) armLocked(sessionKey string) {
+	w.stopLocked(sessionKey)
+
+	watch := &responseWatch{}
+	if w.noticeAfter > 0 && w.noticeAfter < w.timeout {
+		watch.notice = w.afterFunc(w.noticeAfter, func() {
+			w.mu.Lock()
+			current := w.pending[sessionKey] == watch
+			w.mu.Unlock()
+			if current {
+				w.notify("progress", watchdogNoticeText, sessionKey)
+			}
+		})
+	}
+	watch.expire = w.afterFunc(w.timeout, func() {
+		w.mu.Lock()
+		current := w.pending[sessionKey] == watch
+		if current {
+			delete(w.pending, sessionKey)
+		}
+		w.mu.Unlock()
+		if current {
+			w.notify("error", watchdogTimeoutText, sessionKey)
+		}
+	})
+	w.pending[sessionKey] = watch
+}
+
+func (w *responseWatchdog) stopLocked(sessionKey string) {
+	watch := w.pending[sessionKey]
+	if watch == nil {
+		return
+	}
+	if watch.notice != nil {
+		watch.notice.Stop()
+	}
+	watch.expire.Stop()
+	delete(w.pending, sessionKey)
+}
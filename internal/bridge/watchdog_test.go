@@ -0,0 +1,119 @@
+package bridge
+
+import (
+	"reflect"
+	"testing"
+	"time"
+)
+
+// fakeTimers is a manual clock for the watchdog: timers fire only from advance
+type fakeTimers struct {
+	now    time.Duration
+	timers []*fakeTimer
+}
+
+type fakeTimer struct {
+	at      time.Duration
+	f       func()
+	stopped bool
+}
+
+func (t *fakeTimer) Stop() bool {
+	wasActive := !t.stopped
+	t.stopped = true
+	return wasActive
+}
+
+func (c *fakeTimers) afterFunc(d time.Duration, f func()) watchdogTimer {
+	t := &fakeTimer{at: c.now + d, f: f}
+	c.timers = append(c.timers, t)
+	return t
+}
+
+// advance moves the clock forward by d, firing due timers in order
+func (c *fakeTimers) advance(d time.Duration) {
+	end := c.now + d
+	for {
+		var next *fakeTimer
+		for _, t := range c.timers {
+			if !t.stopped && t.at <= end && (next == nil || t.at < next.at) {
+				next = t
+			}
+		}
+		if next == nil {
+			break
+		}
+		next.stopped = true
+		c.now = next.at
+		next.f()
+	}
+	c.now = end
+}
+
+func TestResponseWatchdog(t *testing.T) {
+	const session = "webhook:m1"
+
+	// Each step reports an outbound event for event (if set), then advances the clock
+	type step struct {
+		event   string // Session key of the event ("" = none)
+		final   bool
+		advance time.Duration
+	}
+	tests := []struct {
+		name  string
+		steps []step
+		want  []string // Notifications sent, in order
+	}{
+		{
+			name:  "stalled request gets one notice and one timeout",
+			steps: []step{{advance: 30 * time.Second}, {advance: 30 * time.Second}, {advance: time.Hour}},
+			want:  []string{"progress:" + watchdogNoticeText, "error:" + watchdogTimeoutText},
+		},
+		{
+			name:  "completed response cancels the watch",
+			steps: []step{{advance: 10 * time.Second}, {event: session, final: true, advance: time.Hour}},
+			want:  []string{},
+		},
+		{
+			name:  "completion under the canonical key cancels the watch",
+			steps: []step{{event: "agent:main:" + session, final: true, advance: time.Hour}},
+			want:  []string{},
+		},
+		{
+			name:  "other sessions don't cancel the watch",
+			steps: []step{{event: "webhook:m2", final: true, advance: time.Minute}},
+			want:  []string{"progress:" + watchdogNoticeText, "error:" + watchdogTimeoutText},
+		},
+		{
+			name:  "progress restarts the clock",
+			steps: []step{{advance: 50 * time.Second}, {event: session, advance: 50 * time.Second}, {event: session, final: true, advance: time.Hour}},
+			want:  []string{"progress:" + watchdogNoticeText, "progress:" + watchdogNoticeText},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var clock fakeTimers
+			got := []string{}
+			w := newResponseWatchdog(30*time.Second, time.Minute, func(msgType, content, sessionKey string) {
+				if sessionKey != session {
+					t.Errorf("notified session %q, want %q", sessionKey, session)
+				}
+				got = append(got, msgType+":"+content)
+			})
+			w.afterFunc = clock.afterFunc
+
+			w.start(session)
+			for _, s := range tt.steps {
+				if s.event != "" {
+					w.activity(s.event, s.final)
+				}
+				clock.advance(s.advance)
+			}
+
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("notifications = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,59 @@
+// Package capture records raw gateway frames to an NDJSON file for debugging and replay.
+package capture
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"sync"
+	"time"
+)
+
+// Record is one line of a capture file
+type Record struct {
+	ReceivedAt int64           `json:"ts"`              // Receive time in Unix milliseconds
+	Frame      json.RawMessage `json:"frame,omitempty"` // The frame as received, if it was valid JSON
+	Raw        string          `json:"raw,omitempty"`   // The frame text otherwise
+}
+
+// Recorder appends frames to a capture file
+type Recorder struct {
+	mu   sync.Mutex
+	file *os.File
+}
+
+// Open opens (or creates) a capture file for appending
+func Open(path string) (*Recorder, error) {
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0600)
+	if err != nil {
+		return nil, fmt.Errorf("failed to open capture file: %w", err)
+	}
+	return &Recorder{file: f}, nil
+}
+
+// Write appends one frame with the current time
+func (r *Recorder) Write(frame []byte) error {
+	rec := Record{ReceivedAt: time.Now().UnixMilli()}
+	if json.Valid(frame) {
+		rec.Frame = frame
+	} else {
+		rec.Raw = string(frame)
+	}
+
+	line, err := json.Marshal(rec)
+	if err != nil {
+		return fmt.Errorf("failed to encode frame: %w", err)
+	}
+
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	_, err = r.file.Write(append(line, '\n'))
+	return err
+}
+
+// Close closes the capture file
+func (r *Recorder) Close() error {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	return r.file.Close()
+}
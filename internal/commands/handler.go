@@ -4,12 +4,24 @@ import (
 	"encoding/json"
 	"fmt"
 	"log"
+	"strconv"
 	"strings"
+	"time"
 )
 
 // CommandHandler handles slash commands from webhook messages
 type CommandHandler struct {
 	gatewayClient GatewayClient
+
+	// Agent switching via /agent (sessionAgents nil = unavailable)
+	sessionAgents SessionAgents
+	knownAgents   []string
+
+	// Prompt replay via /replay (nil = unavailable)
+	replayer SessionReplayer
+
+	// Session listing via /sessions (nil = unavailable)
+	lister SessionLister
 }
 
 // GatewayClient interface for OpenClaw Gateway communication
@@ -17,6 +29,35 @@ type GatewayClient interface {
 	SendApproval(requestID string, approved bool) error
 }
 
+// SessionAgents reads and updates the agent a session is routed to
+type SessionAgents interface {
+	SessionAgent(sessionKey string) string
+	SetSessionAgent(sessionKey, agentID string) error
+}
+
+// SessionReplayer re-sends a session's recently recorded prompts to the gateway
+type SessionReplayer interface {
+	ReplaySession(sessionKey string, count int) (int, error)
+}
+
+// SessionSummary is one line of the /sessions list
+type SessionSummary struct {
+	Key       string
+	SessionID string
+	UpdatedAt time.Time
+}
+
+// SessionLister lists stored sessions, most recently active first (limit 0 = all)
+type SessionLister interface {
+	ListSessions(limit int) ([]SessionSummary, error)
+}
+
+// maxListedSessions caps /sessions output so it stays readable in chat clients
+const maxListedSessions = 20
+
+// maxSessionKeyDisplay truncates long session keys in /sessions output
+const maxSessionKeyDisplay = 60
+
 // NewCommandHandler creates a new command handler
 func NewCommandHandler(gateway GatewayClient) *CommandHandler {
 	return &CommandHandler{
@@ -24,6 +65,22 @@ func NewCommandHandler(gateway GatewayClient) *CommandHandler {
 	}
 }
 
+// SetAgents enables the /agent command, allowing sessions to switch between the known agents
+func (h *CommandHandler) SetAgents(sessionAgents SessionAgents, known []string) {
+	h.sessionAgents = sessionAgents
+	h.knownAgents = known
+}
+
+// SetReplayer enables the /replay command
+func (h *CommandHandler) SetReplayer(replayer SessionReplayer) {
+	h.replayer = replayer
+}
+
+// SetSessionLister enables the /sessions command
+func (h *CommandHandler) SetSessionLister(lister SessionLister) {
+	h.lister = lister
+}
+
 // IsCommand checks if a message starts with a slash command
 func IsCommand(message string) bool {
 	trimmed := strings.TrimSpace(message)
@@ -51,8 +108,8 @@ func ParseCommand(message string) (command string, args string) {
 	return command, args
 }
 
-// HandleCommand processes a slash command and returns a response
-func (h *CommandHandler) HandleCommand(message string) (string, error) {
+// HandleCommand processes a slash command sent in the given session and returns a response
+func (h *CommandHandler) HandleCommand(message, sessionKey string) (string, error) {
 	command, args := ParseCommand(message)
 
 	log.Printf("[Commands] Processing command: /%s args: %s", command, args)
@@ -66,6 +123,12 @@ func (h *CommandHandler) HandleCommand(message string) (string, error) {
 		return h.handleSkill(args)
 	case "approve":
 		return h.handleApprove(args)
+	case "agent":
+		return h.handleAgent(args, sessionKey)
+	case "replay":
+		return h.handleReplay(args, sessionKey)
+	case "sessions":
+		return h.handleSessions()
 	default:
 		return "", fmt.Errorf("unknown command: /%s", command)
 	}
@@ -79,6 +142,9 @@ func (h *CommandHandler) handleHelp() (string, error) {
 🔹 **/commands** - List all available commands
 🔹 **/skill [name]** - List skills or run a specific skill
 🔹 **/approve [id]** - Approve or deny pending requests
+🔹 **/agent [id]** - Show or switch the agent for this conversation
+🔹 **/replay [n]** - Re-send the last n messages of this conversation
+🔹 **/sessions** - List the most recently active sessions
 
 💡 Use /commands to see the full command list
 💡 Use /skill to see all available skills`
@@ -136,6 +202,113 @@ func (h *CommandHandler) handleApprove(args string) (string, error) {
 	return fmt.Sprintf("Request %s has been %s", requestID, status), nil
 }
 
+// handleAgent shows the session's agent, or switches it to one of the known agents
+func (h *CommandHandler) handleAgent(args, sessionKey string) (string, error) {
+	if h.sessionAgents == nil || sessionKey == "" {
+		return "Agent switching is not available", nil
+	}
+
+	valid := strings.Join(h.knownAgents, ", ")
+	if args == "" {
+		return fmt.Sprintf("Current agent: %s\nAvailable agents: %s", h.sessionAgents.SessionAgent(sessionKey), valid), nil
+	}
+
+	agentID := strings.Fields(args)[0]
+	known := false
+	for _, id := range h.knownAgents {
+		if id == agentID {
+			known = true
+			break
+		}
+	}
+	if !known {
+		return fmt.Sprintf("Unknown agent: %s\nAvailable agents: %s", agentID, valid), nil
+	}
+
+	if err := h.sessionAgents.SetSessionAgent(sessionKey, agentID); err != nil {
+		log.Printf("[Commands] Failed to switch agent: %v", err)
+		return "", fmt.Errorf("failed to switch agent: %w", err)
+	}
+	return fmt.Sprintf("Switched this conversation to agent %s", agentID), nil
+}
+
+// handleReplay re-sends the session's last n recorded prompts (all of them without n)
+func (h *CommandHandler) handleReplay(args, sessionKey string) (string, error) {
+	if h.replayer == nil || sessionKey == "" {
+		return "Replay is not available", nil
+	}
+
+	count := 0
+	if args != "" {
+		n, err := strconv.Atoi(strings.Fields(args)[0])
+		if err != nil || n <= 0 {
+			return "Usage: /replay [number of messages]", nil
+		}
+		count = n
+	}
+
+	replayed, err := h.replayer.ReplaySession(sessionKey, count)
+	if err != nil {
+		log.Printf("[Commands] Failed to replay session: %v", err)
+		return "", fmt.Errorf("failed to replay: %w", err)
+	}
+	if replayed == 0 {
+		return "No recorded messages to replay", nil
+	}
+	return fmt.Sprintf("Replaying %d message(s)", replayed), nil
+}
+
+// handleSessions lists the most recently active sessions
+func (h *CommandHandler) handleSessions() (string, error) {
+	if h.lister == nil {
+		return "Session listing is not available", nil
+	}
+
+	// Ask for one more than we show to know whether the list was cut short
+	list, err := h.lister.ListSessions(maxListedSessions + 1)
+	if err != nil {
+		log.Printf("[Commands] Failed to list sessions: %v", err)
+		return "", fmt.Errorf("failed to list sessions: %w", err)
+	}
+	if len(list) == 0 {
+		return "No sessions", nil
+	}
+
+	var b strings.Builder
+	b.WriteString("**Sessions** (most recent first):\n")
+	now := time.Now()
+	for i, s := range list {
+		if i == maxListedSessions {
+			b.WriteString("…and more\n")
+			break
+		}
+		key := s.Key
+		if runes := []rune(key); len(runes) > maxSessionKeyDisplay {
+			key = string(runes[:maxSessionKeyDisplay-1]) + "…"
+		}
+		id := s.SessionID
+		if len(id) > 8 {
+			id = id[:8]
+		}
+		fmt.Fprintf(&b, "• %s (%s) - %s\n", key, id, relativeTime(now.Sub(s.UpdatedAt)))
+	}
+	return strings.TrimSuffix(b.String(), "\n"), nil
+}
+
+// relativeTime renders an age like "5m ago"
+func relativeTime(age time.Duration) string {
+	switch {
+	case age < time.Minute:
+		return "just now"
+	case age < time.Hour:
+		return fmt.Sprintf("%dm ago", int(age/time.Minute))
+	case age < 24*time.Hour:
+		return fmt.Sprintf("%dh ago", int(age/time.Hour))
+	default:
+		return fmt.Sprintf("%dd ago", int(age/(24*time.Hour)))
+	}
+}
+
 // FormatCommandResponse wraps a command response in the webhook message format
 func FormatCommandResponse(content string, session string) ([]byte, error) {
 	response := map[string]interface{}{
@@ -1,12 +1,16 @@
 package config
 
 import (
+	"bytes"
 	"encoding/json"
 	"fmt"
+	"net"
 	"os"
 	"path/filepath"
+	"strings"
 
 	"github.com/google/uuid"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/sessions"
 )
 
 // Config holds all configuration for the bridge
@@ -16,15 +20,172 @@ type Config struct {
 	UID        string // Unique ID for this bridge instance
 
 	// Session configuration
-	SessionStorePath string // Path to session store JSON file
-	SessionScope     string // Session scope: "per-sender" or "global"
+	SessionStorePath string                // Path to session store JSON file
+	SessionScope     sessions.SessionScope // Session scope: per-sender (default), global or per-chat
+	PreferPeer       bool                  // Derive keys from peer info even when the message has an explicit session
+	CompactStore     bool                  // Write sessions.json as single-line JSON
+	StoreFormat      string                // On-disk store format: "json" (default) or "gob"
+	SessionBatchMs   int                   // Coalesce session updates made within this many ms (json backend, 0 = off)
+
+	// Event conversion
+	WhitespaceDeltas string   // Whitespace-only streaming deltas: "keep" (default) or "drop"
+	SequenceNumbers  bool     // Add a per-session "seq" field to outbound messages
+	UnknownEvents    string   // Unrecognized gateway event types: "drop" (default), "forward" or "log-only"
+	ReasoningStream  string   // Agent reasoning stream: "suppress" (default), "thinking" or "progress"
+	EventAllowlist   []string // Gateway event types forwarded to the webhook ("*" = all)
+	RequestContext   []string // Message fields sent with agent requests as a "context" object
+	UnknownSessions  string   // Responses for sessions not in the store: "forward" (default), "drop" or "quarantine"
+	MissingKeyMode   string   // Agent/chat events without a sessionKey: "forward" (default), "drop" or "fallback"
+	FallbackKey      string   // Session key used for them in fallback mode
+	QuarantinePath   string   // NDJSON file that quarantined responses are appended to
+
+	// Inbound webhook schema validation
+	SchemaValidation string // "off" (default), "warn" or "drop"
+	SchemaPath       string // Custom schema file, empty = bundled schema
+
+	// MaxSessionKeyLength caps session key length, hashing the tail of longer keys (0 = unlimited)
+	MaxSessionKeyLength int
+
+	// Relayed message logging per direction: "off", "bytes" (default) or "content"
+	WebhookLogLevel  string
+	OpenClawLogLevel string
+	LogSampleEvery   int // Log only every Nth relayed message (1 = all)
+	LogMinInterval   int // Seconds between byte-count logs per direction, with a summary (0 = no limit)
+
+	// LogTruncateLength caps logged message content, in bytes (0 = no truncation)
+	LogTruncateLength int
+
+	// ResetPreservesRouting keeps delivery/route fields when a session is reset
+	ResetPreservesRouting bool
+	// ResetPreservesLabel keeps a session's operator label when it is reset
+	ResetPreservesLabel bool
+
+	// ResetConfirmation is the reply to a message that is only a reset trigger
+	// (nil = bridge default, empty = none)
+	ResetConfirmation *string
+	// ResetConfirmWindow makes a bare reset trigger need repeating within this many
+	// seconds before the session is reset (0 = reset right away)
+	ResetConfirmWindow int
+
+	// Gateway response watchdog, in seconds (0 = disabled)
+	ResponseNoticeAfter int
+	ResponseTimeout     int
+
+	// Coalesce holds progress coalescing windows keyed by session kind
+	// ("default", "dm", "group", "channel", "global")
+	Coalesce map[string]CoalesceConfig
+
+	// SessionTTL is the idle eviction time in seconds keyed by session kind (empty = never evict);
+	// session_ttl_secs fills in the "default" kind
+	SessionTTL map[string]int
+
+	// SessionPruneInterval is how often idle sessions are swept, in seconds
+	SessionPruneInterval int
+
+	// OutboundBatch groups outbound messages into JSON-array frames (nil = disabled)
+	OutboundBatch *OutboundBatchConfig
+
+	// AdminAddr enables the admin WebSocket endpoint on this address (empty = disabled)
+	AdminAddr string
+
+	// ReplayHistory is how many prompts per session are kept for session.replay (0 = disabled)
+	ReplayHistory int
+
+	// SessionPreamble is prepended to the first message of every new session (empty = none)
+	SessionPreamble string
+
+	// DedupSize is how many recent webhook message IDs are remembered to skip
+	// redeliveries (0 = no deduplication); DedupTTL is how long each is kept, in seconds
+	// (0 = until evicted)
+	DedupSize int
+	DedupTTL  int
+
+	// EchoMode echoes inbound messages back to the webhook instead of forwarding them
+	EchoMode bool
+
+	// ClientIdentification is how the bridge version/platform is reported on the webhook
+	// upgrade: "query" (default), "header" or "off"
+	ClientIdentification string
+
+	// RegisterOnConnect is sent to the webhook server after every connect (nil = disabled)
+	RegisterOnConnect json.RawMessage
+
+	// Webhook TLS: extra trusted root CAs (PEM) and refusing plaintext ws:// URLs
+	WebhookCAFile     string
+	WebhookRequireTLS bool
+
+	// WebhookToken is sent as "Authorization: Bearer <token>" on the webhook upgrade (empty = none)
+	WebhookToken string
+
+	// Webhooks lists every webhook server to connect to, webhook_url first.
+	// WebhookDeliveryMode is "broadcast" (default, each outbound message to all of them)
+	// or "balance" (each message to one, by weighted round-robin).
+	Webhooks            []WebhookTarget
+	WebhookDeliveryMode string
+
+	// Webhook keepalive in seconds: ping interval (0 = no pings) and read-idle timeout (0 = none)
+	WebhookPingInterval int
+	WebhookIdleTimeout  int
+
+	// WebhookSendQueue is how many outbound messages are held while the webhook is down (0 = none)
+	WebhookSendQueue int
+
+	// WebhookSendIdleCheck is how many seconds without outbound traffic make the next send
+	// validate the connection first (0 = never)
+	WebhookSendIdleCheck int
+
+	// WebhookSpoolPath keeps the send queue on disk across restarts (empty = memory only)
+	WebhookSpoolPath string
+
+	// MaxMessageAge is how many seconds a queued outbound message may wait before it is
+	// dropped instead of sent (0 = no limit)
+	MaxMessageAge int
+
+	// Adaptive webhook send timeout bounds in milliseconds, derived from the ping RTT
+	// (ceiling 0 = no send timeout)
+	WebhookSendTimeoutFloorMs   int
+	WebhookSendTimeoutCeilingMs int
+
+	// Metrics persistence across restarts
+	PersistMetrics bool
+	MetricsPath    string // Path to metrics snapshot JSON file
+
+	// MetricsPort serves Prometheus metrics at /metrics on this port (0 = disabled)
+	MetricsPort int
 }
 
 // OpenClawConfig contains OpenClaw Gateway configuration
 type OpenClawConfig struct {
-	GatewayPort  int
-	GatewayToken string
-	AgentID      string
+	GatewayHost       string // Host the gateway runs on (default 127.0.0.1)
+	GatewayTLS        bool   // Connect to the gateway with wss://
+	GatewayPort       int
+	GatewayToken      string
+	AgentID           string
+	Agents            []string // Other agents sessions may switch to with /agent
+	AuthMode          string   // Handshake auth: "token" (default) or "challenge"
+	AuthSecret        string   // HMAC key for challenge auth, defaults to the gateway token
+	RedirectTTL       int      // Seconds to follow a gateway redirect before reverting (0 = default)
+	HandshakeTimeout  int      // Seconds to wait for the connect response (0 = don't wait)
+	KeepaliveInterval int      // Seconds between keepalive pings (0 = disabled)
+	KeepaliveTimeout  int      // Seconds past the interval to wait for traffic before reconnecting
+}
+
+// WebhookTarget is a webhook server and its share of outbound messages in balance mode
+type WebhookTarget struct {
+	URL    string `json:"url"`
+	Weight int    `json:"weight,omitempty"` // Defaults to 1
+}
+
+// CoalesceConfig is the progress coalescing window for one kind of session
+type CoalesceConfig struct {
+	IntervalMs int `json:"interval_ms"`
+	MaxBuffer  int `json:"max_buffer,omitempty"`
+}
+
+// OutboundBatchConfig controls batching of outbound webhook frames
+type OutboundBatchConfig struct {
+	MaxCount   int `json:"max_count,omitempty"`
+	MaxDelayMs int `json:"max_delay_ms"`
 }
 
 // openclawJSON matches ~/.openclaw/openclaw.json (managed by OpenClaw)
@@ -39,11 +200,114 @@ type openclawJSON struct {
 
 // bridgeJSON matches ~/.openclaw/bridge.json
 type bridgeJSON struct {
-	WebhookURL string `json:"webhook_url"`
-	AgentID    string `json:"agent_id,omitempty"`
-	UID        string `json:"uid,omitempty"` // Optional pre-configured UID
+	WebhookURL          string `json:"webhook_url"`
+	AgentID             string `json:"agent_id,omitempty"`
+	UID                 string `json:"uid,omitempty"` // Optional pre-configured UID
+	WhitespaceDeltas    string `json:"whitespace_deltas,omitempty"`
+	MaxSessionKeyLength int    `json:"max_session_key_length,omitempty"`
+	GatewayAuthMode     string `json:"gateway_auth_mode,omitempty"`
+	GatewayAuthSecret   string `json:"gateway_auth_secret,omitempty"`
+	LogWebhookMessages  string `json:"log_webhook_messages,omitempty"`
+	LogOpenClawMessages string `json:"log_openclaw_messages,omitempty"`
+	LogSampleEvery      int    `json:"log_sample_every,omitempty"`
+	LogMinInterval      int    `json:"log_min_interval,omitempty"`
+	PersistMetrics      bool   `json:"persist_metrics,omitempty"`
+	SequenceNumbers     bool   `json:"sequence_numbers,omitempty"`
+	GatewayRedirectTTL  int    `json:"gateway_redirect_ttl,omitempty"`
+	SchemaValidation    string `json:"schema_validation,omitempty"`
+	UnknownEvents       string `json:"unknown_events,omitempty"`
+	UnknownSessions     string `json:"unknown_session_responses,omitempty"`
+	MissingSessionKey   string `json:"missing_session_key,omitempty"`
+	FallbackSessionKey  string `json:"fallback_session_key,omitempty"`
+	ReasoningStream     string `json:"reasoning_stream,omitempty"`
+	ResponseNoticeAfter int    `json:"response_notice_after,omitempty"`
+	ResponseTimeout     int    `json:"response_timeout,omitempty"`
+	ResetConfirmWindow  int    `json:"reset_confirm_window,omitempty"`
+	SchemaPath          string `json:"schema_path,omitempty"`
+	CompactSessionStore bool   `json:"compact_session_store,omitempty"`
+	SessionStoreFormat  string `json:"session_store_format,omitempty"`
+	SessionWriteBatch   int    `json:"session_write_batch_ms,omitempty"`
+	AdminAddr           string `json:"admin_addr,omitempty"`
+	EchoMode            bool   `json:"echo_mode,omitempty"`
+	ClientIdentifier    string `json:"client_identification,omitempty"`
+	ReplayHistory       int    `json:"replay_history,omitempty"`
+	SessionPreamble     string `json:"session_preamble,omitempty"`
+	MetricsPort         int    `json:"metrics_port,omitempty"`
+	GatewayHost         string `json:"gateway_host,omitempty"`
+	GatewayTLS          bool   `json:"gateway_tls,omitempty"`
+	WebhookSendQueue    int    `json:"webhook_send_queue,omitempty"`
+	WebhookSendSpool    bool   `json:"webhook_send_spool,omitempty"`
+	WebhookSendIdle     int    `json:"webhook_send_idle_check,omitempty"`
+	SessionTTLSecs      int    `json:"session_ttl_secs,omitempty"`
+	SessionPruneSecs    int    `json:"session_prune_interval_secs,omitempty"`
+	PreferPeerSession   bool   `json:"prefer_peer_over_explicit_session,omitempty"`
+	SessionScope        string `json:"session_scope,omitempty"`
+	WebhookCAFile       string `json:"webhook_ca_file,omitempty"`
+	WebhookRequireTLS   bool   `json:"webhook_require_tls,omitempty"`
+	WebhookToken        string `json:"webhook_token,omitempty"`
+	KeepaliveTimeout    int    `json:"gateway_keepalive_timeout,omitempty"`
+	SendTimeoutFloorMs  int    `json:"webhook_send_timeout_floor_ms,omitempty"`
+	SendTimeoutCeilMs   int    `json:"webhook_send_timeout_ceiling_ms,omitempty"`
+	WebhookWeight       int    `json:"webhook_weight,omitempty"`
+	WebhookDelivery     string `json:"webhook_delivery_mode,omitempty"`
+	MaxMessageAge       int    `json:"max_message_age,omitempty"`
+
+	Coalesce          map[string]CoalesceConfig `json:"coalesce,omitempty"`
+	SessionTTL        map[string]int            `json:"session_ttl,omitempty"`
+	EventAllowlist    []string                  `json:"event_allowlist,omitempty"`
+	Agents            []string                  `json:"agents,omitempty"`
+	RequestContext    []string                  `json:"request_context,omitempty"`
+	OutboundBatch     *OutboundBatchConfig      `json:"outbound_batch,omitempty"`
+	RegisterOnConnect json.RawMessage           `json:"register_on_connect,omitempty"`
+	Webhooks          []WebhookTarget           `json:"webhooks,omitempty"`
+
+	// Pointers so an explicit false/0/"" can be told apart from unset
+	ResetPreservesRouting *bool   `json:"reset_preserves_routing,omitempty"`
+	LogTruncateLength     *int    `json:"log_truncate_length,omitempty"`
+	ResetConfirmation     *string `json:"reset_confirmation,omitempty"`
+	KeepaliveInterval     *int    `json:"gateway_keepalive_interval,omitempty"`
+	HandshakeTimeout      *int    `json:"gateway_handshake_timeout,omitempty"`
+	WebhookPingInterval   *int    `json:"webhook_ping_interval,omitempty"`
+	WebhookIdleTimeout    *int    `json:"webhook_idle_timeout,omitempty"`
+	ResetPreservesLabel   *bool   `json:"reset_preserves_label,omitempty"`
+	DedupSize             *int    `json:"dedup_size,omitempty"`
+	DedupTTL              *int    `json:"dedup_ttl,omitempty"`
+}
+
+// defaultSessionPruneInterval is how often idle sessions are swept when unset (hourly)
+const defaultSessionPruneInterval = 3600
+
+// requestContextFields are the message fields request_context may list
+var requestContextFields = map[string]bool{
+	"messageId": true,
+	"senderId":  true,
+	"chatId":    true,
+	"chatType":  true,
+	"peerKind":  true,
+	"peerId":    true,
+	"topicId":   true,
+	"threadId":  true,
+	"bridgeUid": true,
 }
 
+// Handshake and keepalive defaults for the gateway and webhook connections, in seconds
+const (
+	defaultHandshakeTimeout    = 10
+	defaultKeepaliveInterval   = 30
+	defaultKeepaliveTimeout    = 10
+	defaultWebhookPingInterval = 30
+	defaultWebhookIdleTimeout  = 90
+)
+
+// Inbound message deduplication defaults: IDs remembered, and for how many seconds
+const (
+	defaultDedupSize = 1000
+	defaultDedupTTL  = 600
+)
+
+// defaultLogTruncateLength is the logged content cap used when log_truncate_length is unset
+const defaultLogTruncateLength = 2000
+
 // Dir returns the config directory path
 // Tries ~/.openclaw first, falls back to ~/.openclaw
 func Dir() (string, error) {
@@ -65,10 +329,50 @@ func Dir() (string, error) {
 		}
 	}
 
+	// A file in the way would make every later read/write fail with a confusing error
+	if info, err := os.Stat(candidates[0]); err == nil && !info.IsDir() {
+		return "", fmt.Errorf("%s is a file, not a directory; move it aside so the config directory can be created", candidates[0])
+	}
+
 	// Default to .openclaw if none exist
 	return candidates[0], nil
 }
 
+// mergedConfigName is the optional single config file holding both a "gateway" section
+// (same shape as in openclaw.json) and a "bridge" section (same fields as bridge.json)
+const mergedConfigName = "config.json"
+
+// fileExists reports whether path exists and is a regular file
+func fileExists(path string) bool {
+	info, err := os.Stat(path)
+	return err == nil && !info.IsDir()
+}
+
+// loadMergedConfig reads the gateway and bridge sections of a merged config file
+func loadMergedConfig(path string, gwCfg *openclawJSON, brCfg *bridgeJSON) error {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return fmt.Errorf("failed to read %s: %w", path, err)
+	}
+	if err := json.Unmarshal(data, gwCfg); err != nil {
+		return fmt.Errorf("failed to parse %s: %w", path, err)
+	}
+
+	var merged struct {
+		Bridge json.RawMessage `json:"bridge"`
+	}
+	if err := json.Unmarshal(data, &merged); err != nil {
+		return fmt.Errorf("failed to parse %s: %w", path, err)
+	}
+	if len(merged.Bridge) == 0 {
+		return fmt.Errorf("%s has no \"bridge\" section", path)
+	}
+	if err := json.Unmarshal(merged.Bridge, brCfg); err != nil {
+		return fmt.Errorf("failed to parse bridge section of %s: %w", path, err)
+	}
+	return nil
+}
+
 // findConfigFile searches for a config file with multiple possible names
 // Returns the first file found, or error if none exist
 func findConfigFile(dir string, candidates ...string) (string, error) {
@@ -86,44 +390,81 @@ func findConfigFile(dir string, candidates ...string) (string, error) {
 // Supports both ~/.openclaw/ and ~/.openclaw/ directories
 // Gateway config: openclaw.json or openclaw.json
 // Bridge config: bridge.json
+// A merged config.json with "gateway" and "bridge" sections is preferred when present
+// EnvWebhookURL, EnvUID and EnvWebhookToken override the values from bridge.json
 func Load() (*Config, error) {
+	return LoadWithOverrides(Overrides{})
+}
+
+// Environment variables that override bridge.json (command-line overrides win over both)
+const (
+	EnvWebhookURL   = "OPENCLAW_BRIDGE_WEBHOOK_URL"
+	EnvUID          = "OPENCLAW_BRIDGE_UID"
+	EnvWebhookToken = "OPENCLAW_BRIDGE_WEBHOOK_TOKEN"
+)
+
+// Overrides are command-line values that take precedence over the environment and bridge.json
+type Overrides struct {
+	WebhookURL    string
+	UID           string
+	SessionScope  string // "per-sender", "global" or "per-chat"
+	MaxMessageAge *int   // Seconds a queued outbound message may wait (nil = from config)
+}
+
+// LoadWithOverrides reads configuration like Load, with command-line values taking
+// precedence over the environment and the config files (CLI > env > file)
+func LoadWithOverrides(overrides Overrides) (*Config, error) {
 	dir, err := Dir()
 	if err != nil {
 		return nil, err
 	}
 
-	// Find gateway config file: openclaw.json or openclaw.json
-	gwPath, err := findConfigFile(dir, "openclaw.json", "openclaw.json")
-	if err != nil {
-		return nil, fmt.Errorf("failed to find gateway config (openclaw.json or openclaw.json) in %s: %w", dir, err)
-	}
-	gwData, err := os.ReadFile(gwPath)
-	if err != nil {
-		return nil, fmt.Errorf("failed to read %s: %w", gwPath, err)
-	}
 	var gwCfg openclawJSON
-	if err := json.Unmarshal(gwData, &gwCfg); err != nil {
-		return nil, fmt.Errorf("failed to parse %s: %w", gwPath, err)
-	}
-
-	// Find bridge config file: bridge.json
-	brPath, err := findConfigFile(dir, "bridge.json")
-	if err != nil {
-		return nil, fmt.Errorf(
-			"failed to find bridge.json in %s: %w\n\nCreate it with:\n  {\n    \"webhook_url\": \"ws://localhost:8080/ws\"\n  }", dir, err)
-	}
-	brData, err := os.ReadFile(brPath)
-	if err != nil {
-		return nil, fmt.Errorf("failed to read %s: %w", brPath, err)
-	}
 	var brCfg bridgeJSON
-	if err := json.Unmarshal(brData, &brCfg); err != nil {
-		return nil, fmt.Errorf("failed to parse %s: %w", brPath, err)
+	var gwPath, brPath string
+
+	// A single merged config.json takes precedence over the split files
+	if mergedPath := filepath.Join(dir, mergedConfigName); fileExists(mergedPath) {
+		gwPath, brPath = mergedPath, mergedPath
+		if err := loadMergedConfig(mergedPath, &gwCfg, &brCfg); err != nil {
+			return nil, err
+		}
+	} else {
+		// Find gateway config file: openclaw.json or openclaw.json
+		gwPath, err = findConfigFile(dir, "openclaw.json", "openclaw.json")
+		if err != nil {
+			return nil, fmt.Errorf("failed to find gateway config (openclaw.json or openclaw.json) in %s: %w", dir, err)
+		}
+		gwData, err := os.ReadFile(gwPath)
+		if err != nil {
+			return nil, fmt.Errorf("failed to read %s: %w", gwPath, err)
+		}
+		if err := json.Unmarshal(gwData, &gwCfg); err != nil {
+			return nil, fmt.Errorf("failed to parse %s: %w", gwPath, err)
+		}
+
+		// Find bridge config file: bridge.json
+		brPath, err = findConfigFile(dir, "bridge.json")
+		if err != nil {
+			return nil, fmt.Errorf(
+				"failed to find bridge.json in %s: %w\n\nCreate it with:\n  {\n    \"webhook_url\": \"ws://localhost:8080/ws\"\n  }", dir, err)
+		}
+		brData, err := os.ReadFile(brPath)
+		if err != nil {
+			return nil, fmt.Errorf("failed to read %s: %w", brPath, err)
+		}
+		if err := json.Unmarshal(brData, &brCfg); err != nil {
+			return nil, fmt.Errorf("failed to parse %s: %w", brPath, err)
+		}
 	}
 
+	brCfg.WebhookURL = firstNonEmpty(overrides.WebhookURL, os.Getenv(EnvWebhookURL), brCfg.WebhookURL)
+	brCfg.UID = firstNonEmpty(overrides.UID, os.Getenv(EnvUID), brCfg.UID)
+	brCfg.WebhookToken = firstNonEmpty(os.Getenv(EnvWebhookToken), brCfg.WebhookToken)
+
 	// Validate required fields
 	if brCfg.WebhookURL == "" {
-		return nil, fmt.Errorf("webhook_url is required in ~/.openclaw/bridge.json")
+		return nil, fmt.Errorf("webhook_url is required in %s (or set %s / --webhook-url)", brPath, EnvWebhookURL)
 	}
 
 	// Build config with defaults
@@ -139,6 +480,7 @@ func Load() (*Config, error) {
 	if brCfg.AgentID != "" {
 		cfg.OpenClaw.AgentID = brCfg.AgentID
 	}
+	cfg.OpenClaw.Agents = brCfg.Agents
 	if cfg.OpenClaw.GatewayPort == 0 {
 		cfg.OpenClaw.GatewayPort = 18789
 	}
@@ -153,12 +495,540 @@ func Load() (*Config, error) {
 	// Set session store path
 	cfg.SessionStorePath = filepath.Join(dir, "sessions.json")
 
-	// Session scope defaults to per-sender
-	cfg.SessionScope = "per-sender"
+	// Metrics snapshot lives next to the session store
+	cfg.PersistMetrics = brCfg.PersistMetrics
+	cfg.MetricsPath = filepath.Join(dir, "metrics.json")
+	if brCfg.MetricsPort < 0 || brCfg.MetricsPort > 65535 {
+		return nil, fmt.Errorf("invalid metrics_port %d in %s", brCfg.MetricsPort, brPath)
+	}
+	cfg.MetricsPort = brCfg.MetricsPort
+
+	cfg.CompactStore = brCfg.CompactSessionStore
+	switch brCfg.SessionStoreFormat {
+	case "", "json":
+		cfg.StoreFormat = "json"
+	case "gob":
+		cfg.StoreFormat = "gob"
+	default:
+		return nil, fmt.Errorf("invalid session_store_format %q in %s (expected \"json\" or \"gob\")", brCfg.SessionStoreFormat, brPath)
+	}
+	if brCfg.SessionWriteBatch < 0 {
+		return nil, fmt.Errorf("session_write_batch_ms must not be negative in %s", brPath)
+	}
+	cfg.SessionBatchMs = brCfg.SessionWriteBatch
+
+	cfg.PreferPeer = brCfg.PreferPeerSession
+
+	// Session scope defaults to per-sender; --session-scope beats session_scope
+	cfg.SessionScope = sessions.SessionScopePerSender
+	if brCfg.SessionScope != "" {
+		if cfg.SessionScope, err = sessions.ParseSessionScope(brCfg.SessionScope); err != nil {
+			return nil, fmt.Errorf("invalid session_scope in %s: %w", brPath, err)
+		}
+	}
+	if overrides.SessionScope != "" {
+		if cfg.SessionScope, err = sessions.ParseSessionScope(overrides.SessionScope); err != nil {
+			return nil, fmt.Errorf("invalid --session-scope: %w", err)
+		}
+	}
+
+	switch brCfg.WhitespaceDeltas {
+	case "", "keep":
+		cfg.WhitespaceDeltas = "keep"
+	case "drop":
+		cfg.WhitespaceDeltas = "drop"
+	default:
+		return nil, fmt.Errorf("invalid whitespace_deltas %q in %s (expected \"keep\" or \"drop\")", brCfg.WhitespaceDeltas, brPath)
+	}
+
+	cfg.SequenceNumbers = brCfg.SequenceNumbers
+
+	switch brCfg.UnknownEvents {
+	case "", "drop":
+		cfg.UnknownEvents = "drop"
+	case "forward", "log-only":
+		cfg.UnknownEvents = brCfg.UnknownEvents
+	default:
+		return nil, fmt.Errorf("invalid unknown_events %q in %s (expected \"drop\", \"forward\" or \"log-only\")", brCfg.UnknownEvents, brPath)
+	}
+
+	switch brCfg.UnknownSessions {
+	case "", "forward":
+		cfg.UnknownSessions = "forward"
+	case "drop", "quarantine":
+		cfg.UnknownSessions = brCfg.UnknownSessions
+	default:
+		return nil, fmt.Errorf("invalid unknown_session_responses %q in %s (expected \"forward\", \"drop\" or \"quarantine\")", brCfg.UnknownSessions, brPath)
+	}
+	cfg.QuarantinePath = filepath.Join(dir, "quarantine.ndjson")
+
+	switch brCfg.MissingSessionKey {
+	case "", "forward":
+		cfg.MissingKeyMode = "forward"
+	case "drop", "fallback":
+		cfg.MissingKeyMode = brCfg.MissingSessionKey
+	default:
+		return nil, fmt.Errorf("invalid missing_session_key %q in %s (expected \"forward\", \"drop\" or \"fallback\")", brCfg.MissingSessionKey, brPath)
+	}
+	cfg.FallbackKey = strings.TrimSpace(brCfg.FallbackSessionKey)
+	if cfg.FallbackKey == "" {
+		cfg.FallbackKey = "global"
+	}
+
+	switch brCfg.ReasoningStream {
+	case "", "suppress":
+		cfg.ReasoningStream = "suppress"
+	case "thinking", "progress":
+		cfg.ReasoningStream = brCfg.ReasoningStream
+	default:
+		return nil, fmt.Errorf("invalid reasoning_stream %q in %s (expected \"suppress\", \"thinking\" or \"progress\")", brCfg.ReasoningStream, brPath)
+	}
+
+	cfg.ResetPreservesRouting = true
+	if brCfg.ResetPreservesRouting != nil {
+		cfg.ResetPreservesRouting = *brCfg.ResetPreservesRouting
+	}
+	cfg.ResetPreservesLabel = true
+	if brCfg.ResetPreservesLabel != nil {
+		cfg.ResetPreservesLabel = *brCfg.ResetPreservesLabel
+	}
+	cfg.ResetConfirmation = brCfg.ResetConfirmation
+	if brCfg.ResetConfirmWindow < 0 {
+		return nil, fmt.Errorf("reset_confirm_window must not be negative in %s", brPath)
+	}
+	cfg.ResetConfirmWindow = brCfg.ResetConfirmWindow
+
+	if brCfg.ResponseNoticeAfter < 0 || brCfg.ResponseTimeout < 0 {
+		return nil, fmt.Errorf("response_notice_after and response_timeout must not be negative in %s", brPath)
+	}
+	cfg.ResponseNoticeAfter = brCfg.ResponseNoticeAfter
+	cfg.ResponseTimeout = brCfg.ResponseTimeout
+
+	for _, field := range brCfg.RequestContext {
+		if !requestContextFields[field] {
+			return nil, fmt.Errorf("invalid request_context field %q in %s (expected messageId, senderId, chatId, chatType, peerKind, peerId, topicId, threadId or bridgeUid)", field, brPath)
+		}
+	}
+	cfg.RequestContext = brCfg.RequestContext
+
+	cfg.EventAllowlist = brCfg.EventAllowlist
+	if len(cfg.EventAllowlist) == 0 {
+		cfg.EventAllowlist = []string{"agent", "chat"}
+	}
+
+	switch brCfg.SchemaValidation {
+	case "", "off":
+		cfg.SchemaValidation = "off"
+	case "warn", "drop":
+		cfg.SchemaValidation = brCfg.SchemaValidation
+	default:
+		return nil, fmt.Errorf("invalid schema_validation %q in %s (expected \"off\", \"warn\" or \"drop\")", brCfg.SchemaValidation, brPath)
+	}
+	cfg.SchemaPath = brCfg.SchemaPath
+
+	for kind, window := range brCfg.Coalesce {
+		switch kind {
+		case "default", "dm", "group", "channel", "global":
+		default:
+			return nil, fmt.Errorf("invalid coalesce kind %q in %s (expected default, dm, group, channel or global)", kind, brPath)
+		}
+		if window.IntervalMs < 0 || window.MaxBuffer < 0 {
+			return nil, fmt.Errorf("coalesce.%s values must not be negative in %s", kind, brPath)
+		}
+	}
+	cfg.Coalesce = brCfg.Coalesce
+
+	for kind, seconds := range brCfg.SessionTTL {
+		switch kind {
+		case "default", "dm", "group", "channel", "global":
+		default:
+			return nil, fmt.Errorf("invalid session_ttl kind %q in %s (expected default, dm, group, channel or global)", kind, brPath)
+		}
+		if seconds < 0 {
+			return nil, fmt.Errorf("session_ttl.%s must not be negative in %s", kind, brPath)
+		}
+	}
+	cfg.SessionTTL = brCfg.SessionTTL
+	if brCfg.SessionTTLSecs < 0 {
+		return nil, fmt.Errorf("session_ttl_secs must not be negative in %s", brPath)
+	}
+	if _, ok := brCfg.SessionTTL["default"]; !ok && brCfg.SessionTTLSecs > 0 {
+		cfg.SessionTTL = make(map[string]int, len(brCfg.SessionTTL)+1)
+		for kind, seconds := range brCfg.SessionTTL {
+			cfg.SessionTTL[kind] = seconds
+		}
+		cfg.SessionTTL["default"] = brCfg.SessionTTLSecs
+	}
+	if brCfg.SessionPruneSecs < 0 {
+		return nil, fmt.Errorf("session_prune_interval_secs must not be negative in %s", brPath)
+	}
+	cfg.SessionPruneInterval = brCfg.SessionPruneSecs
+	if cfg.SessionPruneInterval == 0 {
+		cfg.SessionPruneInterval = defaultSessionPruneInterval
+	}
+
+	if batch := brCfg.OutboundBatch; batch != nil {
+		if batch.MaxCount < 0 || batch.MaxDelayMs <= 0 {
+			return nil, fmt.Errorf("outbound_batch in %s needs a positive max_delay_ms and a non-negative max_count", brPath)
+		}
+		cfg.OutboundBatch = batch
+	}
+
+	if brCfg.MaxSessionKeyLength < 0 {
+		return nil, fmt.Errorf("max_session_key_length must not be negative in %s", brPath)
+	}
+	if brCfg.MaxSessionKeyLength > 0 && brCfg.MaxSessionKeyLength < sessions.MinSessionKeyLength {
+		return nil, fmt.Errorf("max_session_key_length must be 0 or at least %d in %s", sessions.MinSessionKeyLength, brPath)
+	}
+	cfg.MaxSessionKeyLength = brCfg.MaxSessionKeyLength
+
+	if brCfg.GatewayRedirectTTL < 0 {
+		return nil, fmt.Errorf("gateway_redirect_ttl must not be negative in %s", brPath)
+	}
+	cfg.OpenClaw.RedirectTTL = brCfg.GatewayRedirectTTL
+
+	cfg.OpenClaw.GatewayHost = brCfg.GatewayHost
+	if cfg.OpenClaw.GatewayHost == "" {
+		cfg.OpenClaw.GatewayHost = "127.0.0.1"
+	}
+	if strings.Contains(cfg.OpenClaw.GatewayHost, "/") {
+		return nil, fmt.Errorf("invalid gateway_host %q in %s (expected a host name or IP without scheme or path; use gateway_tls for wss://)", brCfg.GatewayHost, brPath)
+	}
+	cfg.OpenClaw.GatewayTLS = brCfg.GatewayTLS
+
+	cfg.DedupSize = defaultDedupSize
+	if brCfg.DedupSize != nil {
+		if *brCfg.DedupSize < 0 {
+			return nil, fmt.Errorf("dedup_size must not be negative in %s", brPath)
+		}
+		cfg.DedupSize = *brCfg.DedupSize
+	}
+	cfg.DedupTTL = defaultDedupTTL
+	if brCfg.DedupTTL != nil {
+		if *brCfg.DedupTTL < 0 {
+			return nil, fmt.Errorf("dedup_ttl must not be negative in %s", brPath)
+		}
+		cfg.DedupTTL = *brCfg.DedupTTL
+	}
+
+	cfg.OpenClaw.HandshakeTimeout = defaultHandshakeTimeout
+	if brCfg.HandshakeTimeout != nil {
+		if *brCfg.HandshakeTimeout < 0 {
+			return nil, fmt.Errorf("gateway_handshake_timeout must not be negative in %s", brPath)
+		}
+		cfg.OpenClaw.HandshakeTimeout = *brCfg.HandshakeTimeout
+	}
+
+	cfg.OpenClaw.KeepaliveInterval = defaultKeepaliveInterval
+	if brCfg.KeepaliveInterval != nil {
+		if *brCfg.KeepaliveInterval < 0 {
+			return nil, fmt.Errorf("gateway_keepalive_interval must not be negative in %s", brPath)
+		}
+		cfg.OpenClaw.KeepaliveInterval = *brCfg.KeepaliveInterval
+	}
+	if brCfg.KeepaliveTimeout < 0 {
+		return nil, fmt.Errorf("gateway_keepalive_timeout must not be negative in %s", brPath)
+	}
+	cfg.OpenClaw.KeepaliveTimeout = brCfg.KeepaliveTimeout
+	if cfg.OpenClaw.KeepaliveTimeout == 0 {
+		cfg.OpenClaw.KeepaliveTimeout = defaultKeepaliveTimeout
+	}
+
+	switch brCfg.GatewayAuthMode {
+	case "", "token":
+		cfg.OpenClaw.AuthMode = "token"
+	case "challenge":
+		cfg.OpenClaw.AuthMode = "challenge"
+		cfg.OpenClaw.AuthSecret = brCfg.GatewayAuthSecret
+		if cfg.OpenClaw.AuthSecret == "" {
+			cfg.OpenClaw.AuthSecret = cfg.OpenClaw.GatewayToken
+		}
+		if cfg.OpenClaw.AuthSecret == "" {
+			return nil, fmt.Errorf("gateway_auth_mode \"challenge\" needs gateway_auth_secret in %s or gateway.auth.token in %s", brPath, gwPath)
+		}
+	default:
+		return nil, fmt.Errorf("invalid gateway_auth_mode %q in %s (expected \"token\" or \"challenge\")", brCfg.GatewayAuthMode, brPath)
+	}
+
+	if cfg.WebhookLogLevel, err = parseMessageLogLevel(brCfg.LogWebhookMessages); err != nil {
+		return nil, fmt.Errorf("invalid log_webhook_messages in %s: %w", brPath, err)
+	}
+	if cfg.OpenClawLogLevel, err = parseMessageLogLevel(brCfg.LogOpenClawMessages); err != nil {
+		return nil, fmt.Errorf("invalid log_openclaw_messages in %s: %w", brPath, err)
+	}
+	if brCfg.LogSampleEvery < 0 {
+		return nil, fmt.Errorf("log_sample_every must not be negative in %s", brPath)
+	}
+	cfg.LogSampleEvery = brCfg.LogSampleEvery
+	if cfg.LogSampleEvery == 0 {
+		cfg.LogSampleEvery = 1
+	}
+	cfg.EchoMode = brCfg.EchoMode
+	if brCfg.ReplayHistory < 0 {
+		return nil, fmt.Errorf("replay_history must not be negative in %s", brPath)
+	}
+	cfg.ReplayHistory = brCfg.ReplayHistory
+	cfg.SessionPreamble = brCfg.SessionPreamble
+	switch brCfg.ClientIdentifier {
+	case "", "query":
+		cfg.ClientIdentification = "query"
+	case "header", "off":
+		cfg.ClientIdentification = brCfg.ClientIdentifier
+	default:
+		return nil, fmt.Errorf("invalid client_identification %q in %s (expected \"query\", \"header\" or \"off\")", brCfg.ClientIdentifier, brPath)
+	}
+	if len(brCfg.RegisterOnConnect) > 0 && string(brCfg.RegisterOnConnect) != "null" {
+		var obj map[string]json.RawMessage
+		if err := json.Unmarshal(brCfg.RegisterOnConnect, &obj); err != nil {
+			return nil, fmt.Errorf("register_on_connect in %s must be a JSON object: %w", brPath, err)
+		}
+		var compact bytes.Buffer
+		json.Compact(&compact, brCfg.RegisterOnConnect)
+		cfg.RegisterOnConnect = compact.Bytes()
+	}
+	if brCfg.WebhookCAFile != "" {
+		if _, err := os.Stat(brCfg.WebhookCAFile); err != nil {
+			return nil, fmt.Errorf("webhook_ca_file in %s: %w", brPath, err)
+		}
+	}
+	cfg.WebhookCAFile = brCfg.WebhookCAFile
+	cfg.WebhookRequireTLS = brCfg.WebhookRequireTLS
+	cfg.WebhookToken = brCfg.WebhookToken
+	if brCfg.WebhookSendQueue < 0 {
+		return nil, fmt.Errorf("webhook_send_queue must not be negative in %s", brPath)
+	}
+	cfg.WebhookSendQueue = brCfg.WebhookSendQueue
+	if brCfg.WebhookSendSpool {
+		if cfg.WebhookSendQueue == 0 {
+			return nil, fmt.Errorf("webhook_send_spool needs webhook_send_queue to be set in %s", brPath)
+		}
+		cfg.WebhookSpoolPath = filepath.Join(dir, "outbound-spool.ndjson")
+	}
+	cfg.MaxMessageAge = brCfg.MaxMessageAge
+	if overrides.MaxMessageAge != nil {
+		cfg.MaxMessageAge = *overrides.MaxMessageAge
+	}
+	if cfg.MaxMessageAge < 0 {
+		return nil, fmt.Errorf("max_message_age must not be negative in %s", brPath)
+	}
+	if cfg.MaxMessageAge > 0 && cfg.WebhookSendQueue == 0 {
+		return nil, fmt.Errorf("max_message_age needs webhook_send_queue to be set in %s", brPath)
+	}
+	if brCfg.WebhookSendIdle < 0 {
+		return nil, fmt.Errorf("webhook_send_idle_check must not be negative in %s", brPath)
+	}
+	if brCfg.WebhookSendIdle > 0 && cfg.WebhookSendQueue == 0 {
+		return nil, fmt.Errorf("webhook_send_idle_check needs webhook_send_queue to be set in %s", brPath)
+	}
+	cfg.WebhookSendIdleCheck = brCfg.WebhookSendIdle
+
+	// webhook_url plus any extra webhooks, each weighted for balance mode
+	if brCfg.WebhookWeight < 0 {
+		return nil, fmt.Errorf("webhook_weight must not be negative in %s", brPath)
+	}
+	cfg.Webhooks = []WebhookTarget{{URL: cfg.WebhookURL, Weight: brCfg.WebhookWeight}}
+	for i, target := range brCfg.Webhooks {
+		if target.URL == "" {
+			return nil, fmt.Errorf("webhooks[%d] has no url in %s", i, brPath)
+		}
+		if target.Weight < 0 {
+			return nil, fmt.Errorf("webhooks[%d] weight must not be negative in %s", i, brPath)
+		}
+		cfg.Webhooks = append(cfg.Webhooks, target)
+	}
+	for i := range cfg.Webhooks {
+		if cfg.Webhooks[i].Weight == 0 {
+			cfg.Webhooks[i].Weight = 1
+		}
+	}
+	switch brCfg.WebhookDelivery {
+	case "", "broadcast":
+		cfg.WebhookDeliveryMode = "broadcast"
+	case "balance":
+		cfg.WebhookDeliveryMode = brCfg.WebhookDelivery
+	default:
+		return nil, fmt.Errorf("invalid webhook_delivery_mode %q in %s (expected \"broadcast\" or \"balance\")", brCfg.WebhookDelivery, brPath)
+	}
+
+	cfg.WebhookPingInterval = defaultWebhookPingInterval
+	if brCfg.WebhookPingInterval != nil {
+		if *brCfg.WebhookPingInterval < 0 {
+			return nil, fmt.Errorf("webhook_ping_interval must not be negative in %s", brPath)
+		}
+		cfg.WebhookPingInterval = *brCfg.WebhookPingInterval
+	}
+	cfg.WebhookIdleTimeout = defaultWebhookIdleTimeout
+	if brCfg.WebhookIdleTimeout != nil {
+		if *brCfg.WebhookIdleTimeout < 0 {
+			return nil, fmt.Errorf("webhook_idle_timeout must not be negative in %s", brPath)
+		}
+		cfg.WebhookIdleTimeout = *brCfg.WebhookIdleTimeout
+	}
+	if cfg.WebhookIdleTimeout > 0 && cfg.WebhookPingInterval > 0 && cfg.WebhookIdleTimeout <= cfg.WebhookPingInterval {
+		return nil, fmt.Errorf("webhook_idle_timeout must be longer than webhook_ping_interval in %s", brPath)
+	}
+	if brCfg.SendTimeoutFloorMs < 0 || brCfg.SendTimeoutCeilMs < 0 {
+		return nil, fmt.Errorf("webhook_send_timeout_floor_ms and webhook_send_timeout_ceiling_ms must not be negative in %s", brPath)
+	}
+	if brCfg.SendTimeoutCeilMs > 0 {
+		if brCfg.SendTimeoutFloorMs > brCfg.SendTimeoutCeilMs {
+			return nil, fmt.Errorf("webhook_send_timeout_floor_ms must not exceed webhook_send_timeout_ceiling_ms in %s", brPath)
+		}
+		if cfg.WebhookPingInterval == 0 {
+			return nil, fmt.Errorf("webhook_send_timeout_ceiling_ms needs webhook_ping_interval to measure the RTT in %s", brPath)
+		}
+	} else if brCfg.SendTimeoutFloorMs > 0 {
+		return nil, fmt.Errorf("webhook_send_timeout_floor_ms needs webhook_send_timeout_ceiling_ms to be set in %s", brPath)
+	}
+	cfg.WebhookSendTimeoutFloorMs = brCfg.SendTimeoutFloorMs
+	cfg.WebhookSendTimeoutCeilingMs = brCfg.SendTimeoutCeilMs
+	if brCfg.AdminAddr != "" {
+		host, _, err := net.SplitHostPort(brCfg.AdminAddr)
+		if err != nil {
+			return nil, fmt.Errorf("invalid admin_addr %q in %s (expected host:port or :port)", brCfg.AdminAddr, brPath)
+		}
+		// The endpoint has no authentication and can reset sessions
+		if ip := net.ParseIP(host); host != "" && host != "localhost" && (ip == nil || !ip.IsLoopback()) {
+			return nil, fmt.Errorf("admin_addr %q must be a loopback address in %s (the endpoint is unauthenticated)", brCfg.AdminAddr, brPath)
+		}
+		cfg.AdminAddr = brCfg.AdminAddr
+	}
+	if brCfg.LogMinInterval < 0 {
+		return nil, fmt.Errorf("log_min_interval must not be negative in %s", brPath)
+	}
+	cfg.LogMinInterval = brCfg.LogMinInterval
+	cfg.LogTruncateLength = defaultLogTruncateLength
+	if brCfg.LogTruncateLength != nil {
+		if *brCfg.LogTruncateLength < 0 {
+			return nil, fmt.Errorf("log_truncate_length must not be negative in %s", brPath)
+		}
+		cfg.LogTruncateLength = *brCfg.LogTruncateLength
+	}
 
 	return cfg, nil
 }
 
+// parseMessageLogLevel validates a message logging level, defaulting to "bytes"
+func parseMessageLogLevel(s string) (string, error) {
+	switch s {
+	case "":
+		return "bytes", nil
+	case "off", "bytes", "content":
+		return s, nil
+	}
+	return "", fmt.Errorf("%q (expected \"off\", \"bytes\" or \"content\")", s)
+}
+
+// UpdateBridgeConfig writes the given fields into bridge.json (or the bridge section of a
+// merged config.json), leaving all other fields untouched. Returns the path of the file written.
+func UpdateBridgeConfig(fields map[string]string) (string, error) {
+	dir, err := Dir()
+	if err != nil {
+		return "", err
+	}
+
+	path, merged := bridgeConfigPath(dir)
+
+	file := make(map[string]json.RawMessage)
+	if data, err := os.ReadFile(path); err == nil {
+		if err := json.Unmarshal(data, &file); err != nil {
+			return "", fmt.Errorf("failed to parse %s: %w", path, err)
+		}
+	}
+
+	raw := file
+	if merged {
+		raw = make(map[string]json.RawMessage)
+		if section, ok := file["bridge"]; ok {
+			if err := json.Unmarshal(section, &raw); err != nil {
+				return "", fmt.Errorf("failed to parse bridge section of %s: %w", path, err)
+			}
+		}
+	}
+
+	for key, value := range fields {
+		encoded, err := json.Marshal(value)
+		if err != nil {
+			return "", fmt.Errorf("failed to encode %s: %w", key, err)
+		}
+		raw[key] = encoded
+	}
+
+	if merged {
+		section, err := json.Marshal(raw)
+		if err != nil {
+			return "", fmt.Errorf("failed to marshal bridge config: %w", err)
+		}
+		file["bridge"] = section
+	}
+
+	data, err := json.MarshalIndent(file, "", "  ")
+	if err != nil {
+		return "", fmt.Errorf("failed to marshal bridge config: %w", err)
+	}
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		return "", fmt.Errorf("failed to create config directory: %w", err)
+	}
+	if err := os.WriteFile(path, data, 0600); err != nil {
+		return "", fmt.Errorf("failed to write %s: %w", path, err)
+	}
+	return path, nil
+}
+
+// bridgeConfigPath returns the file holding the bridge fields: a merged config.json (in its
+// "bridge" section) when present, bridge.json otherwise
+func bridgeConfigPath(dir string) (path string, merged bool) {
+	path = filepath.Join(dir, mergedConfigName)
+	if fileExists(path) {
+		return path, true
+	}
+	return filepath.Join(dir, "bridge.json"), false
+}
+
+// SavedBridgeIdentity returns the webhook URL and UID saved in bridge.json (or the bridge
+// section of a merged config.json), without environment or command-line overrides.
+// A missing file gives empty values.
+func SavedBridgeIdentity() (webhookURL, uid string, err error) {
+	dir, err := Dir()
+	if err != nil {
+		return "", "", err
+	}
+	path, merged := bridgeConfigPath(dir)
+	data, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		return "", "", nil
+	}
+	if err != nil {
+		return "", "", fmt.Errorf("failed to read %s: %w", path, err)
+	}
+
+	var brCfg bridgeJSON
+	if merged {
+		var file struct {
+			Bridge bridgeJSON `json:"bridge"`
+		}
+		err = json.Unmarshal(data, &file)
+		brCfg = file.Bridge
+	} else {
+		err = json.Unmarshal(data, &brCfg)
+	}
+	if err != nil {
+		return "", "", fmt.Errorf("failed to parse %s: %w", path, err)
+	}
+	// Configs written before uid existed kept it in agent_id
+	return brCfg.WebhookURL, firstNonEmpty(brCfg.UID, brCfg.AgentID), nil
+}
+
+// firstNonEmpty returns the first non-empty value
+func firstNonEmpty(values ...string) string {
+	for _, v := range values {
+		if v != "" {
+			return v
+		}
+	}
+	return ""
+}
+
 // generateUID generates a unique ID for this bridge instance
 // Uses UUID v4 for uniqueness
 func generateUID() string {
@@ -0,0 +1,378 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+const testGatewayConfig = `{"gateway": {"port": 19000, "auth": {"token": "gw-token"}}}`
+
+// setupConfigDir points the home directory at a temporary one holding the given config
+// files (name -> contents) in .openclaw, and clears the environment overrides
+func setupConfigDir(t *testing.T, files map[string]string) string {
+	t.Helper()
+	home := t.TempDir()
+	t.Setenv("HOME", home)
+	t.Setenv("USERPROFILE", home)
+	for _, env := range []string{EnvWebhookURL, EnvUID, EnvWebhookToken} {
+		t.Setenv(env, "")
+	}
+
+	dir := filepath.Join(home, ".openclaw")
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		t.Fatal(err)
+	}
+	for name, contents := range files {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(contents), 0600); err != nil {
+			t.Fatal(err)
+		}
+	}
+	return dir
+}
+
+func TestLoadWithOverridesPrecedence(t *testing.T) {
+	const fileConfig = `{"webhook_url": "ws://file/ws", "uid": "file-uid"}`
+
+	tests := []struct {
+		name      string
+		env       map[string]string
+		overrides Overrides
+		wantURL   string
+		wantUID   string
+	}{
+		{
+			name:    "file values without overrides",
+			wantURL: "ws://file/ws",
+			wantUID: "file-uid",
+		},
+		{
+			name:    "environment beats the file",
+			env:     map[string]string{EnvWebhookURL: "ws://env/ws", EnvUID: "env-uid"},
+			wantURL: "ws://env/ws",
+			wantUID: "env-uid",
+		},
+		{
+			name:      "command line beats the environment",
+			env:       map[string]string{EnvWebhookURL: "ws://env/ws", EnvUID: "env-uid"},
+			overrides: Overrides{WebhookURL: "ws://cli/ws", UID: "cli-uid"},
+			wantURL:   "ws://cli/ws",
+			wantUID:   "cli-uid",
+		},
+		{
+			name:      "each value falls back on its own",
+			env:       map[string]string{EnvUID: "env-uid"},
+			overrides: Overrides{WebhookURL: "ws://cli/ws"},
+			wantURL:   "ws://cli/ws",
+			wantUID:   "env-uid",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setupConfigDir(t, map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": fileConfig})
+			for key, value := range tt.env {
+				t.Setenv(key, value)
+			}
+
+			cfg, err := LoadWithOverrides(tt.overrides)
+			if err != nil {
+				t.Fatalf("LoadWithOverrides: %v", err)
+			}
+			if cfg.WebhookURL != tt.wantURL {
+				t.Errorf("WebhookURL = %q, want %q", cfg.WebhookURL, tt.wantURL)
+			}
+			if cfg.UID != tt.wantUID {
+				t.Errorf("UID = %q, want %q", cfg.UID, tt.wantUID)
+			}
+		})
+	}
+}
+
+func TestLoadWithOverridesWebhookURLRequired(t *testing.T) {
+	setupConfigDir(t, map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": `{}`})
+
+	if _, err := LoadWithOverrides(Overrides{}); err == nil {
+		t.Fatal("LoadWithOverrides succeeded without a webhook URL")
+	}
+	cfg, err := LoadWithOverrides(Overrides{WebhookURL: "ws://cli/ws"})
+	if err != nil {
+		t.Fatalf("LoadWithOverrides with --webhook-url: %v", err)
+	}
+	if cfg.UID == "" {
+		t.Error("no UID generated when none is configured")
+	}
+}
+
+func TestLoadWithOverridesValidation(t *testing.T) {
+	tests := []struct {
+		name      string
+		bridge    string
+		overrides Overrides
+		wantErr   string // Substring of the expected error, "" for a valid config
+	}{
+		{name: "minimal config", bridge: `{"webhook_url": "ws://h/ws"}`},
+		{name: "unknown session_scope", bridge: `{"webhook_url": "ws://h/ws", "session_scope": "per-user"}`, wantErr: "invalid session_scope"},
+		{name: "unknown --session-scope", bridge: `{"webhook_url": "ws://h/ws"}`, overrides: Overrides{SessionScope: "per-user"}, wantErr: "invalid --session-scope"},
+		{name: "unknown whitespace_deltas", bridge: `{"webhook_url": "ws://h/ws", "whitespace_deltas": "trim"}`, wantErr: "invalid whitespace_deltas"},
+		{name: "metrics_port out of range", bridge: `{"webhook_url": "ws://h/ws", "metrics_port": 70000}`, wantErr: "invalid metrics_port"},
+		{name: "gateway_host with a scheme", bridge: `{"webhook_url": "ws://h/ws", "gateway_host": "ws://gw"}`, wantErr: "invalid gateway_host"},
+		{name: "challenge auth falls back to the gateway token", bridge: `{"webhook_url": "ws://h/ws", "gateway_auth_mode": "challenge"}`},
+		{name: "unknown request_context field", bridge: `{"webhook_url": "ws://h/ws", "request_context": ["email"]}`, wantErr: "invalid request_context field"},
+		{name: "unknown coalesce kind", bridge: `{"webhook_url": "ws://h/ws", "coalesce": {"thread": {"interval_ms": 100}}}`, wantErr: "invalid coalesce kind"},
+		{name: "negative session_ttl", bridge: `{"webhook_url": "ws://h/ws", "session_ttl": {"dm": -1}}`, wantErr: "session_ttl.dm must not be negative"},
+		{name: "outbound_batch without a delay", bridge: `{"webhook_url": "ws://h/ws", "outbound_batch": {"max_count": 10}}`, wantErr: "outbound_batch"},
+		{name: "spool without a queue", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_spool": true}`, wantErr: "webhook_send_spool needs webhook_send_queue"},
+		{name: "max_message_age without a queue", bridge: `{"webhook_url": "ws://h/ws", "max_message_age": 60}`, wantErr: "max_message_age needs webhook_send_queue"},
+		{name: "max_message_age with a queue", bridge: `{"webhook_url": "ws://h/ws", "max_message_age": 60, "webhook_send_queue": 100}`},
+		{name: "negative --max-message-age", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_queue": 100}`, overrides: Overrides{MaxMessageAge: intPtr(-1)}, wantErr: "max_message_age must not be negative"},
+		{name: "idle send check without a queue", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_idle_check": 60}`, wantErr: "webhook_send_idle_check needs webhook_send_queue"},
+		{name: "idle send check with a queue", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_idle_check": 60, "webhook_send_queue": 100}`},
+		{name: "idle timeout not above the ping interval", bridge: `{"webhook_url": "ws://h/ws", "webhook_ping_interval": 30, "webhook_idle_timeout": 30}`, wantErr: "webhook_idle_timeout must be longer"},
+		{name: "send timeout floor above the ceiling", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_timeout_floor_ms": 500, "webhook_send_timeout_ceiling_ms": 100}`, wantErr: "must not exceed"},
+		{name: "send timeout floor without a ceiling", bridge: `{"webhook_url": "ws://h/ws", "webhook_send_timeout_floor_ms": 500}`, wantErr: "needs webhook_send_timeout_ceiling_ms"},
+		{name: "send timeout without pings", bridge: `{"webhook_url": "ws://h/ws", "webhook_ping_interval": 0, "webhook_idle_timeout": 0, "webhook_send_timeout_ceiling_ms": 1000}`, wantErr: "needs webhook_ping_interval"},
+		{name: "extra webhook without a url", bridge: `{"webhook_url": "ws://h/ws", "webhooks": [{"weight": 2}]}`, wantErr: "webhooks[0] has no url"},
+		{name: "negative webhook weight", bridge: `{"webhook_url": "ws://h/ws", "webhooks": [{"url": "ws://b/ws", "weight": -1}]}`, wantErr: "webhooks[0] weight must not be negative"},
+		{name: "unknown delivery mode", bridge: `{"webhook_url": "ws://h/ws", "webhook_delivery_mode": "random"}`, wantErr: "invalid webhook_delivery_mode"},
+		{name: "invalid admin_addr", bridge: `{"webhook_url": "ws://h/ws", "admin_addr": "localhost"}`, wantErr: "invalid admin_addr"},
+		{name: "admin_addr on all interfaces", bridge: `{"webhook_url": "ws://h/ws", "admin_addr": "0.0.0.0:9090"}`, wantErr: "must be a loopback address"},
+		{name: "admin_addr on a LAN address", bridge: `{"webhook_url": "ws://h/ws", "admin_addr": "192.168.1.5:9090"}`, wantErr: "must be a loopback address"},
+		{name: "admin_addr on loopback", bridge: `{"webhook_url": "ws://h/ws", "admin_addr": "[::1]:9090"}`},
+		{name: "max_session_key_length below the minimum", bridge: `{"webhook_url": "ws://h/ws", "max_session_key_length": 17}`, wantErr: "max_session_key_length must be 0 or at least"},
+		{name: "max_session_key_length at the minimum", bridge: `{"webhook_url": "ws://h/ws", "max_session_key_length": 64}`},
+		{name: "negative reset_confirm_window", bridge: `{"webhook_url": "ws://h/ws", "reset_confirm_window": -1}`, wantErr: "reset_confirm_window must not be negative"},
+		{name: "negative dedup_size", bridge: `{"webhook_url": "ws://h/ws", "dedup_size": -1}`, wantErr: "dedup_size must not be negative"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setupConfigDir(t, map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": tt.bridge})
+
+			_, err := LoadWithOverrides(tt.overrides)
+			if tt.wantErr == "" {
+				if err != nil {
+					t.Fatalf("LoadWithOverrides: %v", err)
+				}
+				return
+			}
+			if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+				t.Fatalf("LoadWithOverrides error = %v, want one containing %q", err, tt.wantErr)
+			}
+		})
+	}
+}
+
+func TestLoadWithOverridesDefaults(t *testing.T) {
+	setupConfigDir(t, map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": `{"webhook_url": "ws://h/ws"}`})
+
+	cfg, err := LoadWithOverrides(Overrides{})
+	if err != nil {
+		t.Fatalf("LoadWithOverrides: %v", err)
+	}
+
+	checks := []struct {
+		name      string
+		got, want interface{}
+	}{
+		{"gateway port", cfg.OpenClaw.GatewayPort, 19000},
+		{"gateway token", cfg.OpenClaw.GatewayToken, "gw-token"},
+		{"gateway host", cfg.OpenClaw.GatewayHost, "127.0.0.1"},
+		{"agent", cfg.OpenClaw.AgentID, "main"},
+		{"session scope", string(cfg.SessionScope), "per-sender"},
+		{"auth mode", cfg.OpenClaw.AuthMode, "token"},
+		{"unknown events", cfg.UnknownEvents, "drop"},
+		{"delivery mode", cfg.WebhookDeliveryMode, "broadcast"},
+		{"dedup size", cfg.DedupSize, defaultDedupSize},
+		{"ping interval", cfg.WebhookPingInterval, defaultWebhookPingInterval},
+		{"log truncate length", cfg.LogTruncateLength, defaultLogTruncateLength},
+	}
+	for _, c := range checks {
+		if c.got != c.want {
+			t.Errorf("%s = %v, want %v", c.name, c.got, c.want)
+		}
+	}
+}
+
+func intPtr(v int) *int {
+	return &v
+}
+
+func TestLoadMergedConfig(t *testing.T) {
+	const merged = `{
+		"gateway": {"port": 19500, "auth": {"token": "merged-token"}},
+		"bridge": {"webhook_url": "ws://merged/ws", "uid": "merged-uid"}
+	}`
+
+	tests := []struct {
+		name     string
+		files    map[string]string
+		wantURL  string
+		wantPort int
+		wantErr  string
+	}{
+		{
+			name:     "split files",
+			files:    map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": `{"webhook_url": "ws://split/ws"}`},
+			wantURL:  "ws://split/ws",
+			wantPort: 19000,
+		},
+		{
+			name:     "merged config.json",
+			files:    map[string]string{"config.json": merged},
+			wantURL:  "ws://merged/ws",
+			wantPort: 19500,
+		},
+		{
+			name:     "merged config.json wins over split files",
+			files:    map[string]string{"config.json": merged, "openclaw.json": testGatewayConfig, "bridge.json": `{"webhook_url": "ws://split/ws"}`},
+			wantURL:  "ws://merged/ws",
+			wantPort: 19500,
+		},
+		{
+			name:    "merged config.json without a bridge section",
+			files:   map[string]string{"config.json": testGatewayConfig},
+			wantErr: `no "bridge" section`,
+		},
+		{
+			name:    "no bridge.json",
+			files:   map[string]string{"openclaw.json": testGatewayConfig},
+			wantErr: "failed to find bridge.json",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setupConfigDir(t, tt.files)
+
+			cfg, err := LoadWithOverrides(Overrides{})
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("LoadWithOverrides error = %v, want one containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("LoadWithOverrides: %v", err)
+			}
+			if cfg.WebhookURL != tt.wantURL {
+				t.Errorf("WebhookURL = %q, want %q", cfg.WebhookURL, tt.wantURL)
+			}
+			if cfg.OpenClaw.GatewayPort != tt.wantPort {
+				t.Errorf("GatewayPort = %d, want %d", cfg.OpenClaw.GatewayPort, tt.wantPort)
+			}
+		})
+	}
+}
+
+func TestSavedBridgeIdentity(t *testing.T) {
+	tests := []struct {
+		name    string
+		files   map[string]string
+		wantURL string
+		wantUID string
+	}{
+		{
+			name:    "bridge.json",
+			files:   map[string]string{"bridge.json": `{"webhook_url": "ws://h/ws", "uid": "u1"}`},
+			wantURL: "ws://h/ws",
+			wantUID: "u1",
+		},
+		{
+			name:    "bridge section of config.json",
+			files:   map[string]string{"config.json": `{"bridge": {"webhook_url": "ws://m/ws", "uid": "u2"}}`},
+			wantURL: "ws://m/ws",
+			wantUID: "u2",
+		},
+		{
+			name:    "agent_id from older configs",
+			files:   map[string]string{"bridge.json": `{"webhook_url": "ws://h/ws", "agent_id": "legacy"}`},
+			wantURL: "ws://h/ws",
+			wantUID: "legacy",
+		},
+		{
+			name: "no file yet",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setupConfigDir(t, tt.files)
+			// The environment must not leak into the saved values
+			t.Setenv(EnvWebhookURL, "ws://env/ws")
+			t.Setenv(EnvUID, "env-uid")
+
+			url, uid, err := SavedBridgeIdentity()
+			if err != nil {
+				t.Fatalf("SavedBridgeIdentity: %v", err)
+			}
+			if url != tt.wantURL || uid != tt.wantUID {
+				t.Errorf("SavedBridgeIdentity = %q, %q, want %q, %q", url, uid, tt.wantURL, tt.wantUID)
+			}
+		})
+	}
+}
+
+func TestUpdateBridgeConfigMerged(t *testing.T) {
+	dir := setupConfigDir(t, map[string]string{
+		"config.json": `{"gateway": {"port": 19500}, "bridge": {"webhook_url": "ws://m/ws", "uid": "old"}}`,
+	})
+
+	path, err := UpdateBridgeConfig(map[string]string{"uid": "new"})
+	if err != nil {
+		t.Fatalf("UpdateBridgeConfig: %v", err)
+	}
+	if want := filepath.Join(dir, "config.json"); path != want {
+		t.Errorf("wrote %s, want %s", path, want)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "bridge.json")); !os.IsNotExist(err) {
+		t.Error("UpdateBridgeConfig created bridge.json next to a merged config")
+	}
+
+	cfg, err := LoadWithOverrides(Overrides{})
+	if err != nil {
+		t.Fatalf("LoadWithOverrides: %v", err)
+	}
+	if cfg.UID != "new" || cfg.WebhookURL != "ws://m/ws" || cfg.OpenClaw.GatewayPort != 19500 {
+		t.Errorf("after update: uid %q, webhook_url %q, port %d", cfg.UID, cfg.WebhookURL, cfg.OpenClaw.GatewayPort)
+	}
+}
+
+func TestLoadWebhookTargets(t *testing.T) {
+	tests := []struct {
+		name   string
+		bridge string
+		want   []WebhookTarget
+	}{
+		{
+			name:   "webhook_url alone",
+			bridge: `{"webhook_url": "ws://a/ws"}`,
+			want:   []WebhookTarget{{URL: "ws://a/ws", Weight: 1}},
+		},
+		{
+			name:   "extra webhooks follow webhook_url",
+			bridge: `{"webhook_url": "ws://a/ws", "webhook_weight": 3, "webhooks": [{"url": "ws://b/ws"}, {"url": "ws://c/ws", "weight": 2}]}`,
+			want:   []WebhookTarget{{URL: "ws://a/ws", Weight: 3}, {URL: "ws://b/ws", Weight: 1}, {URL: "ws://c/ws", Weight: 2}},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setupConfigDir(t, map[string]string{"openclaw.json": testGatewayConfig, "bridge.json": tt.bridge})
+
+			cfg, err := LoadWithOverrides(Overrides{})
+			if err != nil {
+				t.Fatalf("LoadWithOverrides: %v", err)
+			}
+			if len(cfg.Webhooks) != len(tt.want) {
+				t.Fatalf("Webhooks = %v, want %v", cfg.Webhooks, tt.want)
+			}
+			for i := range tt.want {
+				if cfg.Webhooks[i] != tt.want[i] {
+					t.Errorf("Webhooks[%d] = %v, want %v", i, cfg.Webhooks[i], tt.want[i])
+				}
+			}
+		})
+	}
+}
@@ -0,0 +1,145 @@
+package metrics
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sync"
+	"sync/atomic"
+	"time"
+)
+
+// Counter names used by the bridge
+const (
+	MessagesTotal          = "messages_total"            // Messages received from the webhook
+	EventsTotal            = "events_total"              // Events received from the gateway
+	WebhookSendErrorsTotal = "webhook_send_errors_total" // Failed sends to the webhook
+	GatewaySendErrorsTotal = "gateway_send_errors_total" // Failed sends to the gateway
+
+	MessagesForwardedTotal = "messages_forwarded_total" // Webhook messages forwarded to the gateway
+	EventsForwardedTotal   = "events_forwarded_total"   // Gateway events converted and sent on to the webhook
+	GatewayReconnectsTotal = "gateway_reconnects_total" // Reconnect attempts by the gateway client
+	WebhookReconnectsTotal = "webhook_reconnects_total" // Reconnect attempts by the webhook client
+
+	WebhookFanoutDroppedTotal = "webhook_fanout_dropped_total" // Frames dropped for a webhook too slow to keep up
+
+	StoreCacheHitsTotal        = "session_store_cache_hits_total"        // Loads served from the in-memory cache
+	StoreCacheMissesTotal      = "session_store_cache_misses_total"      // Loads that read the file
+	StoreCacheEvictionsTotal   = "session_store_cache_evictions_total"   // Cached copies dropped as stale or on write
+	StoreSavesTotal            = "session_store_saves_total"             // Store files written
+	StoreLockAcquisitionsTotal = "session_store_lock_acquisitions_total" // File locks taken
+	StoreLockWaitMsTotal       = "session_store_lock_wait_ms_total"      // Time spent waiting for file locks
+)
+
+// Registry holds named monotonic counters
+type Registry struct {
+	mu       sync.RWMutex
+	counters map[string]*atomic.Uint64
+}
+
+// NewRegistry creates an empty registry
+func NewRegistry() *Registry {
+	return &Registry{
+		counters: make(map[string]*atomic.Uint64),
+	}
+}
+
+// Default is the process-wide registry
+var Default = NewRegistry()
+
+// Inc increments a counter in the default registry
+func Inc(name string) {
+	Default.Add(name, 1)
+}
+
+// Counter returns the named counter, creating it if needed. Hot paths can keep the
+// returned pointer to skip the name lookup on every increment.
+func (r *Registry) Counter(name string) *atomic.Uint64 {
+	r.mu.RLock()
+	c, ok := r.counters[name]
+	r.mu.RUnlock()
+	if ok {
+		return c
+	}
+
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if c, ok = r.counters[name]; !ok {
+		c = &atomic.Uint64{}
+		r.counters[name] = c
+	}
+	return c
+}
+
+// Add increases a counter by delta
+func (r *Registry) Add(name string, delta uint64) {
+	r.Counter(name).Add(delta)
+}
+
+// Snapshot returns a copy of all counter values
+func (r *Registry) Snapshot() map[string]uint64 {
+	r.mu.RLock()
+	defer r.mu.RUnlock()
+
+	snap := make(map[string]uint64, len(r.counters))
+	for name, c := range r.counters {
+		snap[name] = c.Load()
+	}
+	return snap
+}
+
+// Restore continues counters from a saved snapshot.
+// Saved values are added on top of anything counted since startup, so counters never go backwards.
+func (r *Registry) Restore(snap map[string]uint64) {
+	for name, value := range snap {
+		r.Add(name, value)
+	}
+}
+
+// snapshotFile is the on-disk format of a persisted snapshot
+type snapshotFile struct {
+	SavedAt  int64             `json:"savedAt"`
+	Counters map[string]uint64 `json:"counters"`
+}
+
+// SaveFile persists the current counters to path atomically
+func (r *Registry) SaveFile(path string) error {
+	data, err := json.MarshalIndent(snapshotFile{
+		SavedAt:  time.Now().UnixMilli(),
+		Counters: r.Snapshot(),
+	}, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to marshal metrics: %w", err)
+	}
+
+	if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+		return fmt.Errorf("failed to create metrics directory: %w", err)
+	}
+	tmpPath := path + ".tmp"
+	if err := os.WriteFile(tmpPath, data, 0600); err != nil {
+		return fmt.Errorf("failed to write metrics: %w", err)
+	}
+	if err := os.Rename(tmpPath, path); err != nil {
+		return fmt.Errorf("failed to rename metrics file: %w", err)
+	}
+	return nil
+}
+
+// LoadFile restores counters saved by SaveFile. A missing file is not an error.
+func (r *Registry) LoadFile(path string) error {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil
+		}
+		return fmt.Errorf("failed to read metrics: %w", err)
+	}
+
+	var snap snapshotFile
+	if err := json.Unmarshal(data, &snap); err != nil {
+		return fmt.Errorf("failed to parse metrics %s: %w", path, err)
+	}
+	r.Restore(snap.Counters)
+	return nil
+}
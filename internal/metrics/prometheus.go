@@ -0,0 +1,93 @@
+package metrics
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net"
+	"net/http"
+	"sort"
+	"strconv"
+)
+
+// prometheusPrefix namespaces every exported metric name
+const prometheusPrefix = "openclaw_bridge_"
+
+// Server exposes a registry over HTTP in the Prometheus text format at /metrics
+type Server struct {
+	registry *Registry
+	gauges   map[string]func() (float64, bool)
+	server   *http.Server
+}
+
+// NewServer creates a metrics server for the registry
+func NewServer(r *Registry) *Server {
+	return &Server{
+		registry: r,
+		gauges:   make(map[string]func() (float64, bool)),
+	}
+}
+
+// AddGauge exports a value computed at scrape time; value reports false to skip the
+// gauge for that scrape. Add gauges before Start.
+func (s *Server) AddGauge(name string, value func() (float64, bool)) {
+	s.gauges[name] = value
+}
+
+// Start listens on port on all interfaces and serves /metrics in the background
+func (s *Server) Start(port int) error {
+	listener, err := net.Listen("tcp", net.JoinHostPort("", strconv.Itoa(port)))
+	if err != nil {
+		return fmt.Errorf("failed to listen on port %d: %w", port, err)
+	}
+
+	mux := http.NewServeMux()
+	mux.HandleFunc("/metrics", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "text/plain; version=0.0.4")
+		s.WritePrometheus(w)
+	})
+	s.server = &http.Server{Handler: mux}
+
+	go func() {
+		if err := s.server.Serve(listener); err != nil && !errors.Is(err, http.ErrServerClosed) {
+			log.Printf("[Metrics] Server stopped: %v", err)
+		}
+	}()
+	log.Printf("[Metrics] Serving http://%s/metrics", listener.Addr())
+	return nil
+}
+
+// Close stops the server
+func (s *Server) Close() error {
+	if s.server == nil {
+		return nil
+	}
+	return s.server.Close()
+}
+
+// WritePrometheus writes every counter and gauge in the Prometheus text exposition format
+func (s *Server) WritePrometheus(w io.Writer) {
+	counters := s.registry.Snapshot()
+	names := make([]string, 0, len(counters))
+	for name := range counters {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		fmt.Fprintf(w, "# TYPE %s%s counter\n%s%s %d\n", prometheusPrefix, name, prometheusPrefix, name, counters[name])
+	}
+
+	names = names[:0]
+	for name := range s.gauges {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		value, ok := s.gauges[name]()
+		if !ok {
+			continue
+		}
+		fmt.Fprintf(w, "# TYPE %s%s gauge\n%s%s %s\n", prometheusPrefix, name, prometheusPrefix, name, strconv.FormatFloat(value, 'g', -1, 64))
+	}
+}
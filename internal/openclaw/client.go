@@ -2,14 +2,46 @@ package openclaw
 
 import (
 	"context"
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
 	"encoding/json"
+	"errors"
 	"fmt"
 	"log"
+	"net"
+	"net/http"
+	"strconv"
 	"sync"
 	"sync/atomic"
 	"time"
 
 	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/reconnect"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/version"
+)
+
+// defaultHandshakeTimeout bounds how long we wait for the gateway to answer the connect request
+const defaultHandshakeTimeout = 10 * time.Second
+
+// defaultGatewayHost is where the gateway is expected unless configured otherwise
+const defaultGatewayHost = "127.0.0.1"
+
+// challengeTimeout bounds how long we wait for the gateway's auth challenge after dialing
+const challengeTimeout = 10 * time.Second
+
+// dialTimeout bounds the TCP connect plus WebSocket upgrade to the gateway
+const dialTimeout = 5 * time.Second
+
+// defaultRedirectTTL is how long a gateway redirect is followed before reverting to the configured port
+const defaultRedirectTTL = 5 * time.Minute
+
+// Keepalive defaults: ping the gateway every defaultKeepaliveInterval and drop the
+// connection if nothing (not even a pong) arrives within defaultKeepaliveTimeout after that
+const (
+	defaultKeepaliveInterval = 30 * time.Second
+	defaultKeepaliveTimeout  = 10 * time.Second
 )
 
 // EventCallback is called for each event from OpenClaw Gateway
@@ -22,6 +54,29 @@ type Client struct {
 	token   string
 	agentID string
 
+	// Gateway host (default 127.0.0.1) and whether to connect with wss://
+	host   string
+	useTLS bool
+
+	// challengeSecret enables challenge-response auth when non-empty
+	challengeSecret string
+
+	// Redirect target set by the gateway, followed on reconnect until redirectUntil;
+	// now is the clock redirects are timed with, replaceable in tests
+	redirectMu    sync.Mutex
+	redirectHost  string
+	redirectPort  int
+	redirectUntil time.Time
+	redirectTTL   time.Duration
+	now           func() time.Time
+
+	// handshakeTimeout bounds the wait for the connect response (0 = don't wait for it)
+	handshakeTimeout time.Duration
+
+	// Keepalive pings (keepaliveInterval 0 = disabled)
+	keepaliveInterval time.Duration
+	keepaliveTimeout  time.Duration
+
 	// Persistent connection
 	conn      *websocket.Conn
 	connMu    sync.RWMutex
@@ -30,15 +85,39 @@ type Client struct {
 	cancel    context.CancelFunc
 	wg        sync.WaitGroup
 
+	// writeMu serializes writes to conn: gateway requests are answered from the read loop
+	// while agent requests are sent from other goroutines (WriteControl needs no lock)
+	writeMu sync.Mutex
+
+	// lifecycleMu guards ctx/cancel across Connect and Close; cancel is non-nil while
+	// a connection loop is running
+	lifecycleMu sync.Mutex
+
 	// Connection state notification
 	connCond *sync.Cond
 
-	// Event callback
-	onEvent EventCallback
+	// reconnectPolicy is consulted before each reconnect; established is set once a
+	// connection attempt succeeds, at connectedAt (all only touched by the connection loop goroutine)
+	reconnectPolicy reconnect.Policy
+	established     bool
+	connectedAt     time.Time
+
+	// onState is told about connection state transitions (nil = none)
+	onState reconnect.StateCallback
+
+	// Event callback. Events that arrive before it is set (e.g. right after the handshake)
+	// are held in earlyEvents and delivered when SetEventCallback installs it.
+	onEvent     EventCallback
+	earlyEvents [][]byte
+	onEventMu   sync.Mutex
 
 	// Pending requests (for request/response pattern)
 	pendingRequests   map[string]chan []byte
 	pendingRequestsMu sync.RWMutex
+
+	// Handlers for requests initiated by the gateway, keyed by method
+	requestHandlers   map[string]RequestHandler
+	requestHandlersMu sync.RWMutex
 }
 
 // requestPool is a sync.Pool for reusing request objects
@@ -65,22 +144,120 @@ type agentRequestParams struct {
 	SessionKey     string `json:"sessionKey"`
 	Deliver        bool   `json:"deliver"`
 	IdempotencyKey string `json:"idempotencyKey"`
+
+	// Context is optional metadata about the message (sender, chat, ...) for agents that accept it
+	Context map[string]string `json:"context,omitempty"`
+	// SessionFile lets the gateway resume server-side state it reported for this session
+	SessionFile string `json:"sessionFile,omitempty"`
+}
+
+// AgentRequestOptions are the optional parameters of an agent request
+type AgentRequestOptions struct {
+	Context     map[string]string // Sent as "context", left out when empty
+	SessionFile string            // Sent as "sessionFile", left out when empty
 }
 
 // NewClient creates a new OpenClaw Gateway client
 func NewClient(port int, token, agentID string) *Client {
-	return &Client{
+	c := &Client{
 		port:            port,
+		host:            defaultGatewayHost,
 		token:           token,
 		agentID:         agentID,
 		pendingRequests: make(map[string]chan []byte),
+		requestHandlers: make(map[string]RequestHandler),
 		connCond:        sync.NewCond(&sync.Mutex{}),
+		redirectTTL:     defaultRedirectTTL,
+		now:             time.Now,
+
+		keepaliveInterval: defaultKeepaliveInterval,
+		keepaliveTimeout:  defaultKeepaliveTimeout,
+		handshakeTimeout:  defaultHandshakeTimeout,
 	}
+	c.registerDefaultRequestHandlers()
+	return c
 }
 
-// SetEventCallback sets the callback for OpenClaw events
+// maxEarlyEvents bounds how many events are held while no callback is set
+const maxEarlyEvents = 256
+
+// SetEventCallback sets the callback for OpenClaw events. Events received before it
+// was set are delivered to it first, in order. Callbacks always run without onEventMu
+// held, so they may call back into the client.
 func (c *Client) SetEventCallback(cb EventCallback) {
-	c.onEvent = cb
+	for {
+		c.onEventMu.Lock()
+		early := c.earlyEvents
+		if cb == nil || len(early) == 0 {
+			c.onEvent = cb
+			c.onEventMu.Unlock()
+			return
+		}
+		// Events arriving while these are delivered are held too, and picked up next round
+		c.earlyEvents = nil
+		c.onEventMu.Unlock()
+
+		for _, message := range early {
+			cb(message)
+		}
+	}
+}
+
+// dispatchEvent forwards an event to the callback, or holds it until one is set
+func (c *Client) dispatchEvent(message []byte) {
+	c.onEventMu.Lock()
+	cb := c.onEvent
+	if cb == nil {
+		if len(c.earlyEvents) >= maxEarlyEvents {
+			c.onEventMu.Unlock()
+			log.Printf("[OpenClaw] No event callback set, dropping event")
+			return
+		}
+		c.earlyEvents = append(c.earlyEvents, message)
+		c.onEventMu.Unlock()
+		return
+	}
+	c.onEventMu.Unlock()
+
+	cb(message)
+}
+
+// SetChallengeAuth switches the handshake to challenge-response auth: after the socket
+// opens we wait for the gateway's nonce and answer with an HMAC-SHA256 keyed by secret
+func (c *Client) SetChallengeAuth(secret string) {
+	c.challengeSecret = secret
+}
+
+// SetGateway sets the host the gateway runs on and whether to connect over TLS (wss://).
+// Set it before Connect.
+func (c *Client) SetGateway(host string, useTLS bool) {
+	if host != "" {
+		c.host = host
+	}
+	c.useTLS = useTLS
+}
+
+// SetRedirectTTL sets how long a gateway redirect is honored before reconnecting
+// to the configured gateway again
+func (c *Client) SetRedirectTTL(ttl time.Duration) {
+	c.redirectMu.Lock()
+	c.redirectTTL = ttl
+	c.redirectMu.Unlock()
+}
+
+// SetHandshakeTimeout sets how long to wait for the gateway to answer the connect request
+// before treating the attempt as failed and reconnecting. 0 skips waiting for the answer.
+// Set it before Connect.
+func (c *Client) SetHandshakeTimeout(timeout time.Duration) {
+	c.handshakeTimeout = timeout
+}
+
+// SetKeepalive sets how often the gateway is pinged and how long to wait past that for
+// any traffic before dropping the connection. An interval of 0 disables pings.
+// Set it before Connect.
+func (c *Client) SetKeepalive(interval, timeout time.Duration) {
+	c.keepaliveInterval = interval
+	c.keepaliveTimeout = timeout
 }
 
 // AgentID returns the configured agent ID for this client.
@@ -88,24 +265,56 @@ func (c *Client) AgentID() string {
 	return c.agentID
 }
 
+// SetStateCallback registers a callback told about connecting, connected, disconnected
+// and reconnecting transitions. Set it before Connect.
+func (c *Client) SetStateCallback(cb reconnect.StateCallback) {
+	c.onState = cb
+}
+
+// notifyState reports a state transition to the state callback, if any
+func (c *Client) notifyState(change reconnect.StateChange) {
+	if c.onState != nil {
+		c.onState(change)
+	}
+}
+
+// SetReconnectPolicy installs a hook deciding whether to reconnect after each disconnect.
+// The default policy always reconnects. Set it before Connect.
+func (c *Client) SetReconnectPolicy(policy reconnect.Policy) {
+	c.reconnectPolicy = policy
+}
+
+// ErrAlreadyConnected is returned by Connect while a connection loop is already running
+var ErrAlreadyConnected = errors.New("client is already connected; call Close before connecting again")
+
+// ErrNotConnected is returned (wrapped) by sends made while no gateway connection is available
+var ErrNotConnected = errors.New("not connected to gateway")
+
 // Connect establishes a persistent WebSocket connection to the gateway
 func (c *Client) Connect(ctx context.Context) error {
+	c.lifecycleMu.Lock()
+	if c.cancel != nil {
+		c.lifecycleMu.Unlock()
+		return ErrAlreadyConnected
+	}
 	c.ctx, c.cancel = context.WithCancel(ctx)
+	loopCtx := c.ctx
 
 	// Start connection loop
 	c.wg.Add(1)
 	go c.connectionLoop()
+	c.lifecycleMu.Unlock()
 
 	// Wait for connection to be established using condition variable
 	c.connCond.L.Lock()
 	defer c.connCond.L.Unlock()
 
-	timeout := time.NewTimer(5 * time.Second)
+	timeout := time.NewTimer(c.connectWait())
 	defer timeout.Stop()
 
 	for !c.connected.Load() {
 		select {
-		case <-c.ctx.Done():
+		case <-loopCtx.Done():
 			return fmt.Errorf("context cancelled while waiting for connection")
 		case <-timeout.C:
 			return fmt.Errorf("timeout connecting to gateway")
@@ -121,7 +330,7 @@ func (c *Client) Connect(ctx context.Context) error {
 				// Woke up from Wait, check connected again
 			case <-timeout.C:
 				return fmt.Errorf("timeout connecting to gateway")
-			case <-c.ctx.Done():
+			case <-loopCtx.Done():
 				return fmt.Errorf("context cancelled while waiting for connection")
 			}
 		}
@@ -131,17 +340,36 @@ func (c *Client) Connect(ctx context.Context) error {
 	return nil
 }
 
-// Close gracefully shuts down the connection
+// connectWait is how long the first connection attempt may take: the dial, the auth
+// challenge and the answer to the connect request each have their own timeout
+func (c *Client) connectWait() time.Duration {
+	return dialTimeout + challengeTimeout + c.handshakeTimeout
+}
+
+// IsConnected reports whether a connection is currently established
+func (c *Client) IsConnected() bool {
+	return c.connected.Load()
+}
+
+// Close gracefully shuts down the connection and waits for the connection loop to exit.
+// It is safe to call before Connect or more than once; the client can be connected again afterwards.
 func (c *Client) Close() error {
+	// Never connected, or already closed
+	c.lifecycleMu.Lock()
+	cancel := c.cancel
+	c.lifecycleMu.Unlock()
+	if cancel == nil {
+		return nil
+	}
+
 	log.Printf("[OpenClaw] Closing connection...")
 
-	c.cancel()
+	cancel()
 
 	// Wake up any waiters
 	c.connCond.Broadcast()
 
-	c.wg.Wait()
-
+	// Close the socket first so a blocked read returns and the loop can exit
 	c.connMu.Lock()
 	if c.conn != nil {
 		c.conn.Close()
@@ -149,17 +377,38 @@ func (c *Client) Close() error {
 	}
 	c.connMu.Unlock()
 
+	// Connect keeps refusing until the loop has exited (the loop itself may need lifecycleMu)
+	c.wg.Wait()
+	c.lifecycleMu.Lock()
+	c.cancel = nil
+	c.lifecycleMu.Unlock()
+
 	c.connected.Store(false)
 	log.Printf("[OpenClaw] Connection closed")
 	return nil
 }
 
+// loopContext returns the context of the current connection loop, or nil before Connect
+func (c *Client) loopContext() context.Context {
+	c.lifecycleMu.Lock()
+	defer c.lifecycleMu.Unlock()
+	return c.ctx
+}
+
+// initialReconnectDelay is the first reconnect backoff, doubled on each failure
+const initialReconnectDelay = 1 * time.Second
+
+// immediateCloseWindow is how long a connection must last before a disconnect resets the
+// backoff; anything shorter counts as a failed attempt
+const immediateCloseWindow = 5 * time.Second
+
 // connectionLoop maintains a persistent connection with auto-reconnect
 func (c *Client) connectionLoop() {
 	defer c.wg.Done()
 
-	reconnectDelay := 1 * time.Second
+	reconnectDelay := initialReconnectDelay
 	maxReconnectDelay := 30 * time.Second
+	attempt := 0
 
 	for {
 		select {
@@ -169,74 +418,317 @@ func (c *Client) connectionLoop() {
 		default:
 		}
 
-		if err := c.connectAndRead(); err != nil {
+		c.established = false
+		c.notifyState(reconnect.StateChange{State: reconnect.StateConnecting})
+		err := c.connectAndRead()
+		if err != nil {
 			log.Printf("[OpenClaw] Connection error: %v", err)
+			if c.established {
+				c.notifyState(reconnect.StateChange{State: reconnect.StateDisconnected, Err: err})
+			}
+
+			// Shutting down, not a disconnect worth asking about
+			if c.ctx.Err() != nil {
+				return
+			}
+			// A connection the gateway closes right away (e.g. at capacity) is a soft
+			// failure: keep backing off instead of reconnecting in a tight loop
+			stable := c.established && time.Since(c.connectedAt) >= immediateCloseWindow
+			if c.established && !stable {
+				log.Printf("[OpenClaw] Connection closed %v after connecting, backing off", time.Since(c.connectedAt).Round(time.Millisecond))
+			}
+			if stable {
+				attempt = 0
+			}
+			attempt++
+			policy := c.reconnectPolicy
+			if policy == nil {
+				policy = reconnect.Always
+			}
+			if !policy(reconnect.NewDisconnectInfo(err, attempt)) {
+				log.Printf("[OpenClaw] Reconnect policy declined after %d attempt(s), giving up", attempt)
+				return
+			}
 
-			// Exponential backoff for reconnection
-			if reconnectDelay < maxReconnectDelay {
+			// Exponential backoff for reconnection, starting over after a stable connection
+			if stable {
+				reconnectDelay = initialReconnectDelay
+			} else if reconnectDelay < maxReconnectDelay {
 				reconnectDelay *= 2
 			}
 		} else {
 			// Successful connection, reset delay
-			reconnectDelay = 1 * time.Second
+			reconnectDelay = initialReconnectDelay
 		}
 
-		// Wait before reconnecting (or exit if context cancelled)
+		// Wait before reconnecting (or exit if context cancelled), jittered so a gateway
+		// restart doesn't bring every bridge back at once
+		wait := reconnect.Jitter(reconnectDelay)
+		c.notifyState(reconnect.StateChange{State: reconnect.StateReconnecting, Err: err, Attempt: attempt, Delay: wait})
 		select {
 		case <-c.ctx.Done():
 			return
-		case <-time.After(reconnectDelay):
+		case <-time.After(wait):
 			log.Printf("[OpenClaw] Reconnecting...")
+			metrics.Inc(metrics.GatewayReconnectsTotal)
 		}
 	}
 }
 
 // connectAndRead establishes connection and reads messages
 func (c *Client) connectAndRead() error {
-	url := fmt.Sprintf("ws://127.0.0.1:%d", c.port)
+	url := c.endpoint()
 
 	log.Printf("[OpenClaw] Connecting to %s", url)
-	conn, _, err := websocket.DefaultDialer.Dial(url, nil)
+	dialer := websocket.Dialer{
+		Proxy:            http.ProxyFromEnvironment,
+		HandshakeTimeout: dialTimeout,
+	}
+	conn, _, err := dialer.Dial(url, nil)
 	if err != nil {
 		return fmt.Errorf("failed to dial: %w", err)
 	}
 
 	c.connMu.Lock()
+	if c.ctx.Err() != nil {
+		// Close ran while we were dialing
+		c.connMu.Unlock()
+		conn.Close()
+		return fmt.Errorf("client closed")
+	}
 	c.conn = conn
 	c.connMu.Unlock()
 
+	// In challenge mode the gateway speaks first with a nonce we have to sign
+	nonce := ""
+	if c.challengeSecret != "" {
+		nonce, err = c.readChallenge(conn)
+		if err != nil {
+			conn.Close()
+			return fmt.Errorf("auth challenge failed: %w", err)
+		}
+	}
+
 	// Send connect request immediately
-	if err := c.sendConnectRequest(conn); err != nil {
+	if err := c.sendConnectRequest(conn, nonce); err != nil {
 		conn.Close()
 		return fmt.Errorf("failed to send connect request: %w", err)
 	}
 
+	// Frames that arrive before the connect response are handled once we are connected
+	early, err := c.readConnectResponse(conn)
+	if err != nil {
+		conn.Close()
+		return err
+	}
+
+	c.established = true
+	c.connectedAt = time.Now()
 	c.connected.Store(true)
 	c.connCond.Broadcast() // Wake up any waiters
+	c.notifyState(reconnect.StateChange{State: reconnect.StateConnected})
 	defer func() {
 		c.connected.Store(false)
 		c.connCond.Broadcast() // Wake up any waiters on disconnect
 	}()
 
+	stopKeepalive := c.startKeepalive(conn)
+	defer stopKeepalive()
+
+	for _, message := range early {
+		c.handleFrame(message)
+	}
+
 	// Read messages and forward to callback
 	for {
 		_, message, err := conn.ReadMessage()
 		if err != nil {
+			var netErr net.Error
+			if errors.As(err, &netErr) && netErr.Timeout() {
+				return fmt.Errorf("no traffic from gateway for %v, dropping connection", c.keepaliveInterval+c.keepaliveTimeout)
+			}
 			return fmt.Errorf("read error: %w", err)
 		}
+		c.extendReadDeadline(conn)
 
 		// Don't log message content for privacy
+		c.handleFrame(message)
+	}
+}
 
-		// Check if this is a response to a pending request
-		c.handlePossibleResponse(message)
+// handleFrame routes one frame received from the gateway
+func (c *Client) handleFrame(message []byte) {
+	// Redirect directives are for the client only
+	if c.handleRedirect(message) {
+		return
+	}
 
-		// Forward raw event to callback
-		if c.onEvent != nil {
-			c.onEvent(message)
+	// Requests from the gateway are answered here, never forwarded to chat
+	if c.handleGatewayRequest(message) {
+		return
+	}
+
+	// Check if this is a response to a pending request
+	c.handlePossibleResponse(message)
+
+	// Forward raw event to callback
+	c.dispatchEvent(message)
+}
+
+// errHandshakeTimeout means the gateway accepted the socket but never answered the connect request
+var errHandshakeTimeout = errors.New("gateway did not answer the connect request")
+
+// maxEarlyFrames bounds how many other frames are held while waiting for the connect response
+const maxEarlyFrames = 64
+
+// readConnectResponse waits up to handshakeTimeout for the answer to the connect request
+// and returns the other frames received meanwhile. A rejection, a silent gateway and a
+// socket error are reported as distinct errors.
+func (c *Client) readConnectResponse(conn *websocket.Conn) ([][]byte, error) {
+	if c.handshakeTimeout <= 0 {
+		return nil, nil
+	}
+	conn.SetReadDeadline(time.Now().Add(c.handshakeTimeout))
+	defer conn.SetReadDeadline(time.Time{})
+
+	var early [][]byte
+	for {
+		_, message, err := conn.ReadMessage()
+		if err != nil {
+			var netErr net.Error
+			if errors.As(err, &netErr) && netErr.Timeout() {
+				return nil, fmt.Errorf("%w within %v", errHandshakeTimeout, c.handshakeTimeout)
+			}
+			return nil, fmt.Errorf("read error during handshake: %w", err)
+		}
+
+		var frame struct {
+			Type  string `json:"type"`
+			ID    string `json:"id"`
+			OK    *bool  `json:"ok"`
+			Error *struct {
+				Message string `json:"message"`
+			} `json:"error"`
+		}
+		if err := json.Unmarshal(message, &frame); err != nil || frame.Type != "res" || frame.ID != "connect" {
+			if len(early) < maxEarlyFrames {
+				early = append(early, message)
+			}
+			continue
+		}
+
+		if (frame.OK != nil && !*frame.OK) || frame.Error != nil {
+			reason := "no reason given"
+			if frame.Error != nil && frame.Error.Message != "" {
+				reason = frame.Error.Message
+			}
+			return nil, fmt.Errorf("gateway rejected connect request: %s", reason)
+		}
+		return early, nil
+	}
+}
+
+// startKeepalive pings the gateway every keepaliveInterval and arms a read deadline that
+// any incoming message or pong pushes back, so a half-open connection turns into a read
+// error. It returns a func that stops the pings.
+func (c *Client) startKeepalive(conn *websocket.Conn) func() {
+	if c.keepaliveInterval <= 0 {
+		return func() {}
+	}
+	c.extendReadDeadline(conn)
+	conn.SetPongHandler(func(string) error {
+		c.extendReadDeadline(conn)
+		return nil
+	})
+
+	done := make(chan struct{})
+	go func() {
+		ticker := time.NewTicker(c.keepaliveInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-done:
+				return
+			case <-ticker.C:
+				// WriteControl is safe to call alongside the other writers
+				if err := conn.WriteControl(websocket.PingMessage, nil, time.Now().Add(c.keepaliveTimeout)); err != nil {
+					return
+				}
+			}
 		}
+	}()
+	return func() { close(done) }
+}
+
+// extendReadDeadline gives the gateway another keepalive period to send something
+func (c *Client) extendReadDeadline(conn *websocket.Conn) {
+	if c.keepaliveInterval > 0 {
+		conn.SetReadDeadline(time.Now().Add(c.keepaliveInterval + c.keepaliveTimeout))
 	}
 }
 
+// endpoint returns the gateway URL to dial, following an unexpired redirect
+func (c *Client) endpoint() string {
+	c.redirectMu.Lock()
+	defer c.redirectMu.Unlock()
+
+	if c.redirectHost != "" {
+		if c.now().Before(c.redirectUntil) {
+			return c.gatewayURL(c.redirectHost, c.redirectPort)
+		}
+		log.Printf("[OpenClaw] Redirect to %s:%d expired, reverting to configured gateway", c.redirectHost, c.redirectPort)
+		c.redirectHost = ""
+	}
+	return c.gatewayURL(c.host, c.port)
+}
+
+// gatewayURL builds the WebSocket URL of a gateway at host:port
+func (c *Client) gatewayURL(host string, port int) string {
+	scheme := "ws"
+	if c.useTLS {
+		scheme = "wss"
+	}
+	return fmt.Sprintf("%s://%s", scheme, net.JoinHostPort(host, strconv.Itoa(port)))
+}
+
+// handleRedirect records a gateway.redirect directive telling the client to reconnect
+// elsewhere. Returns true if the message was a redirect.
+func (c *Client) handleRedirect(message []byte) bool {
+	var frame struct {
+		Type    string `json:"type"`
+		Event   string `json:"event"`
+		Payload struct {
+			Host string `json:"host"`
+			Port int    `json:"port"`
+		} `json:"payload"`
+	}
+	if err := json.Unmarshal(message, &frame); err != nil {
+		return false
+	}
+	if frame.Type != "event" || frame.Event != "gateway.redirect" {
+		return false
+	}
+
+	host := frame.Payload.Host
+	if host == "" {
+		host = c.host
+	}
+	port := frame.Payload.Port
+	if port == 0 {
+		port = c.port
+	}
+
+	c.redirectMu.Lock()
+	c.redirectHost = host
+	c.redirectPort = port
+	c.redirectUntil = c.now().Add(c.redirectTTL)
+	ttl := c.redirectTTL
+	c.redirectMu.Unlock()
+
+	log.Printf("[OpenClaw] Gateway redirect to %s:%d (for %v), will use it on reconnect", host, port, ttl)
+	return true
+}
+
 // handlePossibleResponse checks if message is a response to a pending request
 func (c *Client) handlePossibleResponse(message []byte) {
 	var responseWrapper struct {
@@ -265,8 +757,51 @@ func (c *Client) handlePossibleResponse(message []byte) {
 	}
 }
 
-// sendConnectRequest sends the initial connect handshake
-func (c *Client) sendConnectRequest(conn *websocket.Conn) error {
+// readChallenge waits for the gateway's connect.challenge event and returns its nonce
+func (c *Client) readChallenge(conn *websocket.Conn) (string, error) {
+	conn.SetReadDeadline(time.Now().Add(challengeTimeout))
+	defer conn.SetReadDeadline(time.Time{})
+
+	for {
+		_, message, err := conn.ReadMessage()
+		if err != nil {
+			return "", fmt.Errorf("waiting for challenge: %w", err)
+		}
+
+		var frame struct {
+			Type    string `json:"type"`
+			Event   string `json:"event"`
+			Payload struct {
+				Nonce string `json:"nonce"`
+			} `json:"payload"`
+		}
+		if err := json.Unmarshal(message, &frame); err != nil {
+			continue
+		}
+		if frame.Type == "event" && frame.Event == "connect.challenge" && frame.Payload.Nonce != "" {
+			return frame.Payload.Nonce, nil
+		}
+	}
+}
+
+// signChallenge computes the hex-encoded HMAC-SHA256 of the nonce
+func (c *Client) signChallenge(nonce string) string {
+	mac := hmac.New(sha256.New, []byte(c.challengeSecret))
+	mac.Write([]byte(nonce))
+	return hex.EncodeToString(mac.Sum(nil))
+}
+
+// sendConnectRequest sends the initial connect handshake.
+// A non-empty nonce is answered with its signature for challenge-response auth.
+func (c *Client) sendConnectRequest(conn *websocket.Conn, nonce string) error {
+	auth := map[string]string{
+		"token": c.token,
+	}
+	if nonce != "" {
+		auth["nonce"] = nonce
+		auth["signature"] = c.signChallenge(nonce)
+	}
+
 	connectReq := map[string]interface{}{
 		"type":   "req",
 		"id":     "connect",
@@ -276,25 +811,30 @@ func (c *Client) sendConnectRequest(conn *websocket.Conn) error {
 			"maxProtocol": 3,
 			"client": map[string]string{
 				"id":       "gateway-client",
-				"version":  "0.2.0",
+				"version":  version.Version,
 				"platform": "linux",
 				"mode":     "backend",
 			},
-			"role":   "operator",
-			"scopes": []string{"operator.read", "operator.write", "operator.admin"},
-			"auth": map[string]string{
-				"token": c.token,
-			},
+			"role":      "operator",
+			"scopes":    []string{"operator.read", "operator.write", "operator.admin"},
+			"auth":      auth,
 			"locale":    "zh-CN",
-			"userAgent": "openclaw-bridge-go",
+			"userAgent": version.ClientName,
 		},
 	}
 
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
 	return conn.WriteJSON(connectReq)
 }
 
 // SendRaw sends raw JSON data to OpenClaw Gateway
 func (c *Client) SendRaw(data []byte) error {
+	ctx := c.loopContext()
+	if ctx == nil {
+		return ErrNotConnected
+	}
+
 	// Wait for connection with condition variable
 	c.connCond.L.Lock()
 	defer c.connCond.L.Unlock()
@@ -304,10 +844,10 @@ func (c *Client) SendRaw(data []byte) error {
 
 	for !c.connected.Load() {
 		select {
-		case <-c.ctx.Done():
+		case <-ctx.Done():
 			return fmt.Errorf("client closed")
 		case <-timeout.C:
-			return fmt.Errorf("timeout waiting for connection")
+			return fmt.Errorf("%w: timeout waiting for connection", ErrNotConnected)
 		default:
 			// Wait for signal with timeout
 			done := make(chan struct{})
@@ -319,8 +859,8 @@ func (c *Client) SendRaw(data []byte) error {
 			case <-done:
 				// Woke up from Wait, check connected again
 			case <-timeout.C:
-				return fmt.Errorf("timeout waiting for connection")
-			case <-c.ctx.Done():
+				return fmt.Errorf("%w: timeout waiting for connection", ErrNotConnected)
+			case <-ctx.Done():
 				return fmt.Errorf("client closed")
 			}
 		}
@@ -336,15 +876,33 @@ func (c *Client) SendRaw(data []byte) error {
 
 	// Don't log message content for privacy
 
-	if err := conn.WriteMessage(websocket.TextMessage, data); err != nil {
+	c.writeMu.Lock()
+	err := conn.WriteMessage(websocket.TextMessage, data)
+	c.writeMu.Unlock()
+	if err != nil {
 		return fmt.Errorf("failed to send: %w", err)
 	}
 
 	return nil
 }
 
-// SendAgentRequest sends an agent request to OpenClaw using object pooling
+// SendAgentRequest sends an agent request for the configured agent
 func (c *Client) SendAgentRequest(message, sessionKey string) error {
+	return c.SendAgentRequestTo(c.agentID, message, sessionKey)
+}
+
+// SendAgentRequestTo sends an agent request to OpenClaw for a specific agent using object
+// pooling. An empty agentID uses the configured agent.
+func (c *Client) SendAgentRequestTo(agentID, message, sessionKey string) error {
+	return c.SendAgentRequestWithOptions(agentID, message, sessionKey, AgentRequestOptions{})
+}
+
+// SendAgentRequestWithOptions is SendAgentRequestTo with optional request parameters
+func (c *Client) SendAgentRequestWithOptions(agentID, message, sessionKey string, opts AgentRequestOptions) error {
+	if agentID == "" {
+		agentID = c.agentID
+	}
+
 	// Get request from pool
 	req := requestPool.Get().(*agentRequest)
 	defer func() {
@@ -357,6 +915,8 @@ func (c *Client) SendAgentRequest(message, sessionKey string) error {
 			req.Params.AgentID = ""
 			req.Params.SessionKey = ""
 			req.Params.IdempotencyKey = ""
+			req.Params.Context = nil
+			req.Params.SessionFile = ""
 		}
 		requestPool.Put(req)
 	}()
@@ -367,10 +927,12 @@ func (c *Client) SendAgentRequest(message, sessionKey string) error {
 	req.ID = fmt.Sprintf("agent:%d", now)
 	req.Method = "agent"
 	req.Params.Message = message
-	req.Params.AgentID = c.agentID
+	req.Params.AgentID = agentID
 	req.Params.SessionKey = sessionKey
 	req.Params.Deliver = true
 	req.Params.IdempotencyKey = fmt.Sprintf("%d", now)
+	req.Params.Context = opts.Context
+	req.Params.SessionFile = opts.SessionFile
 
 	data, err := json.Marshal(req)
 	if err != nil {
@@ -382,8 +944,9 @@ func (c *Client) SendAgentRequest(message, sessionKey string) error {
 
 // sendRequestAndWait sends a request and waits for the response
 func (c *Client) sendRequestAndWait(method string, params interface{}, timeout time.Duration) ([]byte, error) {
-	if !c.connected.Load() {
-		return nil, fmt.Errorf("not connected to gateway")
+	ctx := c.loopContext()
+	if ctx == nil || !c.connected.Load() {
+		return nil, ErrNotConnected
 	}
 
 	requestID := fmt.Sprintf("%s:%d", method, time.Now().UnixNano())
@@ -424,7 +987,7 @@ func (c *Client) sendRequestAndWait(method string, params interface{}, timeout t
 		return response, nil
 	case <-time.After(timeout):
 		return nil, fmt.Errorf("request timeout")
-	case <-c.ctx.Done():
+	case <-ctx.Done():
 		return nil, fmt.Errorf("client closed")
 	}
 }
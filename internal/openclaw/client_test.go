@@ -0,0 +1,364 @@
+package openclaw
+
+import (
+	"context"
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"strconv"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/reconnect"
+)
+
+// newMockGateway starts a WebSocket server standing in for the gateway on 127.0.0.1 and
+// returns its port. Each accepted connection is passed to handle; done is closed when the
+// test ends, so handlers that hold a connection open can wait on it.
+func newMockGateway(t *testing.T, handle func(conn *websocket.Conn, done <-chan struct{})) int {
+	t.Helper()
+	done := make(chan struct{})
+	upgrader := websocket.Upgrader{}
+	srv := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		conn, err := upgrader.Upgrade(w, r, nil)
+		if err != nil {
+			return
+		}
+		defer conn.Close()
+		handle(conn, done)
+	}))
+	t.Cleanup(func() {
+		close(done)
+		srv.Close()
+	})
+
+	_, port, err := net.SplitHostPort(srv.Listener.Addr().String())
+	if err != nil {
+		t.Fatal(err)
+	}
+	n, err := strconv.Atoi(port)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return n
+}
+
+// connectRequest is the part of the client's connect request the tests look at
+type connectRequest struct {
+	Type   string `json:"type"`
+	ID     string `json:"id"`
+	Params struct {
+		Auth map[string]string `json:"auth"`
+	} `json:"params"`
+}
+
+// readConnect reads frames until the connect request arrives and returns it
+func readConnect(conn *websocket.Conn) (*connectRequest, error) {
+	for {
+		_, data, err := conn.ReadMessage()
+		if err != nil {
+			return nil, err
+		}
+		var req connectRequest
+		if json.Unmarshal(data, &req) == nil && req.Type == "req" && req.ID == "connect" {
+			return &req, nil
+		}
+	}
+}
+
+// answerConnect reads the connect request and accepts it
+func answerConnect(conn *websocket.Conn) error {
+	if _, err := readConnect(conn); err != nil {
+		return err
+	}
+	return conn.WriteMessage(websocket.TextMessage, []byte(`{"type":"res","id":"connect","ok":true}`))
+}
+
+// drain reads until the connection fails, so the client's pings and requests are consumed
+func drain(conn *websocket.Conn) {
+	for {
+		if _, _, err := conn.ReadMessage(); err != nil {
+			return
+		}
+	}
+}
+
+func TestEventRightAfterAcceptIsDelivered(t *testing.T) {
+	const event = `{"type":"agent","stream":"assistant","sessionKey":"s1","data":{"text":"early"}}`
+
+	tests := []struct {
+		name          string
+		beforeConnect bool // Install the callback before Connect instead of after it
+	}{
+		{name: "callback set before connect", beforeConnect: true},
+		{name: "callback set after connect"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			port := newMockGateway(t, func(conn *websocket.Conn, done <-chan struct{}) {
+				// The event goes out before the gateway has even read the connect request
+				if err := conn.WriteMessage(websocket.TextMessage, []byte(event)); err != nil {
+					return
+				}
+				if err := answerConnect(conn); err != nil {
+					return
+				}
+				drain(conn)
+			})
+
+			got := make(chan string, 4)
+			callback := func(data []byte) { got <- string(data) }
+
+			c := NewClient(port, "gw-token", "main")
+			if tt.beforeConnect {
+				c.SetEventCallback(callback)
+			}
+			if err := c.Connect(context.Background()); err != nil {
+				t.Fatalf("Connect: %v", err)
+			}
+			t.Cleanup(func() { c.Close() })
+			if !tt.beforeConnect {
+				c.SetEventCallback(callback)
+			}
+
+			select {
+			case data := <-got:
+				if data != event {
+					t.Errorf("callback got %s, want %s", data, event)
+				}
+			case <-time.After(5 * time.Second):
+				t.Fatal("event sent right after accept was never delivered")
+			}
+		})
+	}
+}
+
+func TestEventCallbackMayCallClient(t *testing.T) {
+	port := newMockGateway(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		if err := answerConnect(conn); err != nil {
+			return
+		}
+		for i := 0; i < 2; i++ {
+			msg := fmt.Sprintf(`{"type":"agent","stream":"assistant","sessionKey":"s1","data":{"text":"%d"}}`, i)
+			if err := conn.WriteMessage(websocket.TextMessage, []byte(msg)); err != nil {
+				return
+			}
+		}
+		drain(conn)
+	})
+
+	c := NewClient(port, "gw-token", "main")
+	got := make(chan string, 4)
+	second := func(data []byte) { got <- "second" }
+	// Replacing the callback from inside it deadlocked while dispatch held the callback lock
+	c.SetEventCallback(func(data []byte) {
+		got <- "first"
+		c.SetEventCallback(second)
+	})
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect: %v", err)
+	}
+	t.Cleanup(func() { c.Close() })
+
+	for _, want := range []string{"first", "second"} {
+		select {
+		case name := <-got:
+			if name != want {
+				t.Errorf("event went to the %s callback, want the %s", name, want)
+			}
+		case <-time.After(5 * time.Second):
+			t.Fatalf("event for the %s callback never delivered", want)
+		}
+	}
+}
+
+func TestConnectLifecycle(t *testing.T) {
+	conns := make(chan struct{}, 4)
+	waitConn := func(n int) {
+		t.Helper()
+		select {
+		case <-conns:
+		case <-time.After(5 * time.Second):
+			t.Fatalf("gateway never saw connection %d", n)
+		}
+	}
+	port := newMockGateway(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		conns <- struct{}{}
+		if err := answerConnect(conn); err != nil {
+			return
+		}
+		drain(conn)
+	})
+
+	c := NewClient(port, "gw-token", "main")
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect: %v", err)
+	}
+	waitConn(1)
+	if err := c.Connect(context.Background()); !errors.Is(err, ErrAlreadyConnected) {
+		t.Errorf("second Connect error = %v, want %v", err, ErrAlreadyConnected)
+	}
+
+	c.Close()
+	if c.IsConnected() {
+		t.Error("still connected after Close")
+	}
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect after Close: %v", err)
+	}
+	defer c.Close()
+	if !c.IsConnected() {
+		t.Error("not connected after reconnecting")
+	}
+	waitConn(2)
+}
+
+// firstFailure runs Connect in the background and returns the error of the first failed
+// connection attempt, as reported to the state callback, then closes the client
+func firstFailure(t *testing.T, c *Client) error {
+	t.Helper()
+	failures := make(chan error, 1)
+	c.SetStateCallback(func(change reconnect.StateChange) {
+		if change.State == reconnect.StateReconnecting && change.Err != nil {
+			select {
+			case failures <- change.Err:
+			default:
+			}
+		}
+	})
+
+	connectDone := make(chan error, 1)
+	go func() { connectDone <- c.Connect(context.Background()) }()
+	defer func() {
+		c.Close()
+		if err := <-connectDone; err == nil {
+			t.Error("Connect succeeded, want it to fail")
+		}
+	}()
+
+	select {
+	case err := <-failures:
+		return err
+	case <-time.After(5 * time.Second):
+		t.Fatal("connection attempt never failed")
+		return nil
+	}
+}
+
+func TestChallengeAuth(t *testing.T) {
+	const (
+		secret = "shared-secret"
+		nonce  = "nonce-123"
+	)
+	mac := hmac.New(sha256.New, []byte(secret))
+	mac.Write([]byte(nonce))
+	wantSignature := hex.EncodeToString(mac.Sum(nil))
+
+	// The gateway sends a challenge and accepts the connect request only if it is signed with secret
+	port := newMockGateway(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		challenge := fmt.Sprintf(`{"type":"event","event":"connect.challenge","payload":{"nonce":%q}}`, nonce)
+		if err := conn.WriteMessage(websocket.TextMessage, []byte(challenge)); err != nil {
+			return
+		}
+		req, err := readConnect(conn)
+		if err != nil {
+			return
+		}
+		reply := `{"type":"res","id":"connect","ok":true}`
+		if req.Params.Auth["nonce"] != nonce || req.Params.Auth["signature"] != wantSignature {
+			reply = `{"type":"res","id":"connect","ok":false,"error":{"message":"bad signature"}}`
+		}
+		if err := conn.WriteMessage(websocket.TextMessage, []byte(reply)); err != nil {
+			return
+		}
+		drain(conn)
+	})
+
+	t.Run("correct secret connects", func(t *testing.T) {
+		c := NewClient(port, "gw-token", "main")
+		c.SetChallengeAuth(secret)
+		if err := c.Connect(context.Background()); err != nil {
+			t.Fatalf("Connect: %v", err)
+		}
+		c.Close()
+	})
+
+	t.Run("wrong secret is rejected", func(t *testing.T) {
+		c := NewClient(port, "gw-token", "main")
+		c.SetChallengeAuth("wrong-secret")
+		err := firstFailure(t, c)
+		if err == nil || !strings.Contains(err.Error(), "gateway rejected connect request: bad signature") {
+			t.Errorf("attempt failed with %v, want the gateway's rejection", err)
+		}
+	})
+}
+
+func TestRedirect(t *testing.T) {
+	clock := time.Unix(1700000000, 0)
+	c := NewClient(18789, "gw-token", "main")
+	c.now = func() time.Time { return clock }
+	c.SetRedirectTTL(time.Minute)
+
+	if c.handleRedirect([]byte(`{"type":"event","event":"connect.challenge","payload":{"nonce":"n"}}`)) {
+		t.Error("handleRedirect claimed a frame that is not a redirect")
+	}
+	if got, want := c.endpoint(), "ws://127.0.0.1:18789"; got != want {
+		t.Fatalf("endpoint = %s before any redirect, want %s", got, want)
+	}
+
+	if !c.handleRedirect([]byte(`{"type":"event","event":"gateway.redirect","payload":{"host":"10.0.0.2","port":18800}}`)) {
+		t.Fatal("handleRedirect did not take the gateway.redirect event")
+	}
+
+	// steps advance the clock from the redirect and check where the client would reconnect
+	steps := []struct {
+		name    string
+		advance time.Duration
+		want    string
+	}{
+		{name: "redirect is followed", want: "ws://10.0.0.2:18800"},
+		{name: "still followed just before the TTL", advance: time.Minute - time.Second, want: "ws://10.0.0.2:18800"},
+		{name: "reverts once the TTL has passed", advance: time.Second, want: "ws://127.0.0.1:18789"},
+		{name: "stays reverted", advance: time.Hour, want: "ws://127.0.0.1:18789"},
+	}
+	for _, step := range steps {
+		clock = clock.Add(step.advance)
+		if got := c.endpoint(); got != step.want {
+			t.Errorf("%s: endpoint = %s, want %s", step.name, got, step.want)
+		}
+	}
+}
+
+func TestRedirectDefaultsToConfiguredGateway(t *testing.T) {
+	c := NewClient(18789, "gw-token", "main")
+	c.handleRedirect([]byte(`{"type":"event","event":"gateway.redirect","payload":{"port":18800}}`))
+	if got, want := c.endpoint(), "ws://127.0.0.1:18800"; got != want {
+		t.Errorf("endpoint = %s, want %s", got, want)
+	}
+}
+
+func TestHandshakeTimeout(t *testing.T) {
+	// The gateway accepts the socket and reads the connect request but never answers it
+	port := newMockGateway(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		if _, err := readConnect(conn); err != nil {
+			return
+		}
+		drain(conn)
+	})
+
+	c := NewClient(port, "gw-token", "main")
+	c.SetHandshakeTimeout(100 * time.Millisecond)
+	err := firstFailure(t, c)
+	if !errors.Is(err, errHandshakeTimeout) {
+		t.Errorf("attempt failed with %v, want %v", err, errHandshakeTimeout)
+	}
+}
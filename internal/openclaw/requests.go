@@ -0,0 +1,100 @@
+package openclaw
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"sort"
+	"time"
+
+	"github.com/sternelee/openclaw-webhook-bridge/internal/version"
+)
+
+// RequestHandler answers a gateway-initiated request. The returned payload is sent back
+// in a "res" frame; a non-nil error is sent back as a failed response instead.
+type RequestHandler func(params json.RawMessage) (interface{}, error)
+
+// HandleRequest registers a handler for gateway-initiated requests with the given method,
+// replacing any existing handler. Requests without a handler are answered with an error.
+func (c *Client) HandleRequest(method string, handler RequestHandler) {
+	c.requestHandlersMu.Lock()
+	c.requestHandlers[method] = handler
+	c.requestHandlersMu.Unlock()
+}
+
+// registerDefaultRequestHandlers installs the handlers every client answers
+func (c *Client) registerDefaultRequestHandlers() {
+	c.HandleRequest("ping", func(json.RawMessage) (interface{}, error) {
+		return map[string]interface{}{"pong": true, "ts": time.Now().UnixMilli()}, nil
+	})
+	c.HandleRequest("capabilities", func(json.RawMessage) (interface{}, error) {
+		return map[string]interface{}{
+			"client":  version.ClientName,
+			"methods": c.requestMethods(),
+		}, nil
+	})
+}
+
+// requestMethods lists the methods with a registered handler, sorted
+func (c *Client) requestMethods() []string {
+	c.requestHandlersMu.RLock()
+	defer c.requestHandlersMu.RUnlock()
+
+	methods := make([]string, 0, len(c.requestHandlers))
+	for method := range c.requestHandlers {
+		methods = append(methods, method)
+	}
+	sort.Strings(methods)
+	return methods
+}
+
+// handleGatewayRequest answers a "req" frame sent by the gateway. Returns true if the
+// message was a request, so it is never forwarded to the event callback.
+func (c *Client) handleGatewayRequest(message []byte) bool {
+	var req struct {
+		Type   string          `json:"type"`
+		ID     string          `json:"id"`
+		Method string          `json:"method"`
+		Params json.RawMessage `json:"params"`
+	}
+	if err := json.Unmarshal(message, &req); err != nil || req.Type != "req" {
+		return false
+	}
+
+	c.requestHandlersMu.RLock()
+	handler := c.requestHandlers[req.Method]
+	c.requestHandlersMu.RUnlock()
+
+	res := map[string]interface{}{
+		"type": "res",
+		"id":   req.ID,
+	}
+	if handler == nil {
+		log.Printf("[OpenClaw] Gateway request with unknown method %q (id=%s)", req.Method, req.ID)
+		res["ok"] = false
+		res["error"] = map[string]string{
+			"code":    "METHOD_NOT_FOUND",
+			"message": fmt.Sprintf("unknown method: %s", req.Method),
+		}
+	} else if payload, err := handler(req.Params); err != nil {
+		log.Printf("[OpenClaw] Gateway request %s (id=%s) failed: %v", req.Method, req.ID, err)
+		res["ok"] = false
+		res["error"] = map[string]string{
+			"code":    "REQUEST_FAILED",
+			"message": err.Error(),
+		}
+	} else {
+		res["ok"] = true
+		res["payload"] = payload
+	}
+
+	data, err := json.Marshal(res)
+	if err != nil {
+		log.Printf("[OpenClaw] Failed to encode response to %s: %v", req.Method, err)
+		return true
+	}
+	if err := c.SendRaw(data); err != nil {
+		log.Printf("[OpenClaw] Failed to respond to gateway request %s: %v", req.Method, err)
+	}
+	return true
+}
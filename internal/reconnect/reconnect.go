@@ -0,0 +1,83 @@
+// Package reconnect holds the reconnect decision hooks shared by the gateway and webhook clients.
+package reconnect
+
+import (
+	"errors"
+	"math/rand"
+	"time"
+
+	"github.com/gorilla/websocket"
+)
+
+// DisconnectInfo describes a dropped connection or failed connection attempt
+type DisconnectInfo struct {
+	CloseCode int   // WebSocket close code, 0 if no close frame was received
+	Err       error // The error that ended the connection
+	Attempt   int   // Consecutive failed attempts since the last successful connection, starting at 1
+}
+
+// Policy decides whether a client should reconnect after a disconnect.
+// It is consulted before every reconnect attempt.
+type Policy func(info DisconnectInfo) bool
+
+// Always is the default policy: keep reconnecting forever
+func Always(DisconnectInfo) bool {
+	return true
+}
+
+// Jitter applies full jitter to a backoff delay, returning a random duration in [0, delay]
+// so clients that lost the same server don't all reconnect at the same moment
+func Jitter(delay time.Duration) time.Duration {
+	if delay <= 0 {
+		return 0
+	}
+	return time.Duration(rand.Int63n(int64(delay) + 1))
+}
+
+// NewDisconnectInfo builds a DisconnectInfo, extracting the close code from err if present
+func NewDisconnectInfo(err error, attempt int) DisconnectInfo {
+	info := DisconnectInfo{Err: err, Attempt: attempt}
+	var closeErr *websocket.CloseError
+	if errors.As(err, &closeErr) {
+		info.CloseCode = closeErr.Code
+	}
+	return info
+}
+
+// State is a stage in a client's connection lifecycle: dialing the server, connected,
+// an established connection was lost, or waiting before the next attempt
+type State int
+
+const (
+	StateConnecting State = iota
+	StateConnected
+	StateDisconnected
+	StateReconnecting
+)
+
+func (s State) String() string {
+	switch s {
+	case StateConnecting:
+		return "connecting"
+	case StateConnected:
+		return "connected"
+	case StateDisconnected:
+		return "disconnected"
+	case StateReconnecting:
+		return "reconnecting"
+	default:
+		return "unknown"
+	}
+}
+
+// StateChange describes a connection state transition
+type StateChange struct {
+	State   State
+	Err     error         // What ended the connection (StateDisconnected) or the last attempt (StateReconnecting)
+	Attempt int           // Consecutive failed attempts so far (StateReconnecting)
+	Delay   time.Duration // Wait before the next attempt (StateReconnecting)
+}
+
+// StateCallback is told about every connection state transition. It runs on the
+// client's connection loop, so it must not block.
+type StateCallback func(change StateChange)
@@ -0,0 +1,23 @@
+package sessions
+
+// SessionBackend is the session persistence used by the bridge. Store (sessions.json)
+// is the only implementation so far.
+type SessionBackend interface {
+	// Load returns a copy of every session
+	Load() (map[string]*SessionEntry, error)
+	// Update applies mutator to the whole store atomically
+	Update(mutator func(map[string]*SessionEntry) error) error
+
+	GetEntry(sessionKey string) (*SessionEntry, error)
+	UpdateEntry(sessionKey string, update func(*SessionEntry) (*SessionEntry, error)) (*SessionEntry, error)
+	DeleteEntry(sessionKey string) (bool, error)
+	ListEntries(opts ListOptions) ([]KeyedEntry, error)
+
+	RecordInboundMeta(sessionKey string, webhookMsgID string, deliveryCtx *DeliveryContext) (*SessionEntry, error)
+	UpdateLastRoute(sessionKey string, deliveryCtx *DeliveryContext) (*SessionEntry, error)
+	EvictOlderThan(ttls SessionTTLs) ([]string, error)
+
+	Close() error
+}
+
+var _ SessionBackend = (*Store)(nil)
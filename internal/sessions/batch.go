@@ -0,0 +1,99 @@
+package sessions
+
+import (
+	"log"
+	"sync"
+	"time"
+)
+
+// writeBatcher coalesces the UpdateEntry calls (RecordInboundMeta, UpdateLastRoute, ...)
+// made within a short window into a single locked read-modify-write of the store, so
+// concurrent writers share one lock, serialize and file write instead of paying for one
+// each. Every caller still waits for, and gets, the result of its own update.
+type writeBatcher struct {
+	store  *Store
+	window time.Duration
+
+	mu      sync.Mutex
+	pending []*batchedUpdate
+	timer   *time.Timer
+}
+
+// batchedUpdate is one queued UpdateEntry call and its outcome
+type batchedUpdate struct {
+	sessionKey string
+	update     func(*SessionEntry) (*SessionEntry, error)
+	result     *SessionEntry
+	err        error
+	done       chan struct{}
+}
+
+// SetWriteBatching makes UpdateEntry (and the methods built on it) collect the updates
+// made within window and write them together. Each call then takes up to window longer,
+// so this only pays off when messages are handled concurrently. 0 disables batching.
+// Set it before the store is used.
+func (s *Store) SetWriteBatching(window time.Duration) {
+	if window <= 0 {
+		s.batcher = nil
+		return
+	}
+	s.batcher = &writeBatcher{store: s, window: window}
+	log.Printf("[SessionStore] Batching writes within %v", window)
+}
+
+// do queues an update and waits for the batch it lands in to be written
+func (w *writeBatcher) do(sessionKey string, update func(*SessionEntry) (*SessionEntry, error)) (*SessionEntry, error) {
+	u := &batchedUpdate{sessionKey: sessionKey, update: update, done: make(chan struct{})}
+
+	w.mu.Lock()
+	w.pending = append(w.pending, u)
+	if len(w.pending) == 1 {
+		w.timer = time.AfterFunc(w.window, w.flush)
+	}
+	w.mu.Unlock()
+
+	<-u.done
+	return u.result, u.err
+}
+
+// flush writes every queued update in one Update, applying them in the order they were made.
+// An update that fails is skipped (its caller gets the error); the rest are still written.
+func (w *writeBatcher) flush() {
+	w.mu.Lock()
+	batch := w.pending
+	w.pending = nil
+	if w.timer != nil {
+		w.timer.Stop()
+		w.timer = nil
+	}
+	w.mu.Unlock()
+	if len(batch) == 0 {
+		return
+	}
+
+	err := w.store.Update(func(store map[string]*SessionEntry) error {
+		for _, u := range batch {
+			existing := store[u.sessionKey]
+			patch, err := u.update(existing)
+			if err != nil {
+				u.err = err
+				continue
+			}
+			if patch == nil {
+				u.result = existing
+				continue
+			}
+			merged := MergeSessionEntry(existing, patch)
+			store[u.sessionKey] = merged
+			u.result = merged
+		}
+		return nil
+	})
+
+	for _, u := range batch {
+		if err != nil && u.err == nil {
+			u.result, u.err = nil, err
+		}
+		close(u.done)
+	}
+}
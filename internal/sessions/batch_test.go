@@ -0,0 +1,155 @@
+package sessions
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"path/filepath"
+	"sync"
+	"sync/atomic"
+	"testing"
+	"time"
+)
+
+func TestWriteBatching(t *testing.T) {
+	errRejected := errors.New("rejected")
+
+	tests := []struct {
+		name    string
+		keys    []string
+		failKey string // Key whose update returns an error
+	}{
+		{name: "concurrent updates are all written", keys: []string{"webhook:a", "webhook:b", "webhook:c", "webhook:d"}},
+		{name: "a failing update doesn't stop the others", keys: []string{"webhook:a", "webhook:b", "webhook:c"}, failKey: "webhook:b"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestStore(t, StoreFormatJSON, true)
+			s.SetWriteBatching(50 * time.Millisecond)
+			savesBefore := storeSaves.Load()
+
+			var wg sync.WaitGroup
+			results := make([]*SessionEntry, len(tt.keys))
+			errs := make([]error, len(tt.keys))
+			for i, key := range tt.keys {
+				wg.Add(1)
+				go func(i int, key string) {
+					defer wg.Done()
+					results[i], errs[i] = s.UpdateEntry(key, func(*SessionEntry) (*SessionEntry, error) {
+						if key == tt.failKey {
+							return nil, errRejected
+						}
+						return &SessionEntry{SessionID: "sess_" + key, UpdatedAt: 1}, nil
+					})
+				}(i, key)
+			}
+			wg.Wait()
+
+			if saves := storeSaves.Load() - savesBefore; saves >= uint64(len(tt.keys)) {
+				t.Errorf("%d updates took %d writes, want them batched", len(tt.keys), saves)
+			}
+
+			store, err := s.Load()
+			if err != nil {
+				t.Fatalf("Load: %v", err)
+			}
+			for i, key := range tt.keys {
+				if key == tt.failKey {
+					if !errors.Is(errs[i], errRejected) {
+						t.Errorf("%s: error = %v, want %v", key, errs[i], errRejected)
+					}
+					if store[key] != nil {
+						t.Errorf("%s: failed update was written", key)
+					}
+					continue
+				}
+				if errs[i] != nil {
+					t.Errorf("%s: error = %v", key, errs[i])
+				}
+				if results[i] == nil || results[i].SessionID != "sess_"+key {
+					t.Errorf("%s: result %+v, want its own entry", key, results[i])
+				}
+				if store[key] == nil {
+					t.Errorf("%s: not written", key)
+				}
+			}
+		})
+	}
+}
+
+func TestWriteBatchingCloseFlushes(t *testing.T) {
+	s := newTestStore(t, StoreFormatJSON, true)
+	s.SetWriteBatching(time.Hour)
+
+	done := make(chan error, 1)
+	go func() {
+		_, err := s.UpdateEntry("webhook:a", func(*SessionEntry) (*SessionEntry, error) {
+			return &SessionEntry{SessionID: "sess_1", UpdatedAt: 1}, nil
+		})
+		done <- err
+	}()
+
+	// Wait for the update to be queued, then Close must write it without the window passing
+	for deadline := time.Now().Add(time.Second); ; time.Sleep(time.Millisecond) {
+		s.batcher.mu.Lock()
+		queued := len(s.batcher.pending)
+		s.batcher.mu.Unlock()
+		if queued > 0 {
+			break
+		}
+		if time.Now().After(deadline) {
+			t.Fatal("update was never queued")
+		}
+	}
+	s.Close()
+
+	select {
+	case err := <-done:
+		if err != nil {
+			t.Fatalf("UpdateEntry: %v", err)
+		}
+	case <-time.After(time.Second):
+		t.Fatal("Close did not flush the queued update")
+	}
+	if entry, err := s.GetEntry("webhook:a"); err != nil || entry == nil {
+		t.Errorf("GetEntry = %+v, %v, want the flushed entry", entry, err)
+	}
+}
+
+func BenchmarkUpdateEntryBatched(b *testing.B) {
+	benchmarkUpdateEntry(b, 2*time.Millisecond)
+}
+
+func BenchmarkUpdateEntryUnbatched(b *testing.B) {
+	benchmarkUpdateEntry(b, 0)
+}
+
+// benchmarkUpdateEntry runs concurrent UpdateEntry calls on distinct keys, as parallel
+// message handlers do
+func benchmarkUpdateEntry(b *testing.B, window time.Duration) {
+	defer log.SetOutput(log.Writer())
+	log.SetOutput(io.Discard)
+
+	s := NewStore(&StoreConfig{
+		StorePath:   filepath.Join(b.TempDir(), "sessions.json"),
+		LockTimeout: time.Minute,
+		Format:      StoreFormatJSON,
+	})
+	s.SetWriteBatching(window)
+
+	var n atomic.Int64
+	b.ResetTimer()
+	b.RunParallel(func(pb *testing.PB) {
+		for pb.Next() {
+			key := fmt.Sprintf("webhook:%d", n.Add(1)%64)
+			if _, err := s.UpdateEntry(key, func(*SessionEntry) (*SessionEntry, error) {
+				return &SessionEntry{SessionID: "sess", UpdatedAt: time.Now().UnixMilli()}, nil
+			}); err != nil {
+				b.Error(err)
+				return
+			}
+		}
+	})
+}
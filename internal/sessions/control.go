@@ -17,6 +17,10 @@ const (
 	ControlMessageSessionReset ControlMessageType = "session.reset"
 	// ControlMessageSessionDelete deletes a session
 	ControlMessageSessionDelete ControlMessageType = "session.delete"
+	// ControlMessageSessionLabel sets or clears a session's label
+	ControlMessageSessionLabel ControlMessageType = "session.label"
+	// ControlMessageSessionReplay re-sends a session's recent prompts to the gateway
+	ControlMessageSessionReplay ControlMessageType = "session.replay"
 )
 
 // SessionControlMessage represents a session control message
@@ -25,6 +29,16 @@ type SessionControlMessage struct {
 	Key    string             `json:"key,omitempty"`    // Session key
 	ID     string             `json:"id,omitempty"`     // Session ID (alternative to key)
 	Action string             `json:"action,omitempty"` // Action to perform
+
+	// Label for session.label (empty = clear the label)
+	Label string `json:"label,omitempty"`
+
+	// Count of recent prompts to re-send for session.replay (0 = all recorded)
+	Count int `json:"count,omitempty"`
+
+	// Pagination for session.list (Limit 0 = all remaining sessions)
+	Offset int `json:"offset,omitempty"`
+	Limit  int `json:"limit,omitempty"`
 }
 
 // SessionInfoResponse contains session information
@@ -36,12 +50,16 @@ type SessionInfoResponse struct {
 	LastChannel     string           `json:"lastChannel,omitempty"`
 	LastTo          string           `json:"lastTo,omitempty"`
 	MessageCount    int              `json:"messageCount,omitempty"`
+	Label           string           `json:"label,omitempty"`
 }
 
-// SessionListResponse contains a list of sessions
+// SessionListResponse contains a page of sessions, most recently updated first
 type SessionListResponse struct {
 	Sessions []SessionInfoResponse `json:"sessions"`
-	Count    int                   `json:"count"`
+	Count    int                   `json:"count"`  // Sessions in this page
+	Total    int                   `json:"total"`  // Sessions in the store
+	Offset   int                   `json:"offset"` // Index of the first session in this page
+	Limit    int                   `json:"limit"`  // Requested page size (0 = unlimited)
 }
 
 // IsSessionControlMessage checks if a message is a session control message
@@ -55,7 +73,9 @@ func IsSessionControlMessage(data []byte) bool {
 	return msg.Type == string(ControlMessageSessionGet) ||
 		msg.Type == string(ControlMessageSessionList) ||
 		msg.Type == string(ControlMessageSessionReset) ||
-		msg.Type == string(ControlMessageSessionDelete)
+		msg.Type == string(ControlMessageSessionDelete) ||
+		msg.Type == string(ControlMessageSessionLabel) ||
+		msg.Type == string(ControlMessageSessionReplay)
 }
 
 // ParseSessionControlMessage parses a session control message
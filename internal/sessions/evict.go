@@ -0,0 +1,119 @@
+package sessions
+
+import (
+	"strings"
+	"time"
+)
+
+// Session kinds, as classified by SessionKind.
+// SessionKindDefault covers keys that match no more specific kind.
+const (
+	SessionKindDefault = "default"
+	SessionKindDM      = "dm"
+	SessionKindGroup   = "group"
+	SessionKindChannel = "channel"
+	SessionKindGlobal  = "global"
+)
+
+// SessionKind classifies a session key by the peer kind encoded in it
+func SessionKind(sessionKey string) string {
+	switch {
+	case sessionKey == "global":
+		return SessionKindGlobal
+	case strings.Contains(sessionKey, ":group:"):
+		return SessionKindGroup
+	case strings.Contains(sessionKey, ":channel:"):
+		return SessionKindChannel
+	case strings.Contains(sessionKey, ":dm:"), strings.HasPrefix(sessionKey, "webhook:"):
+		return SessionKindDM
+	}
+	return SessionKindDefault
+}
+
+// EntrySessionKind classifies a stored session by the chat type recorded from its last
+// inbound message, falling back to SessionKind(sessionKey) when none was recorded. The
+// global session stays global whoever last wrote to it.
+func EntrySessionKind(sessionKey string, entry *SessionEntry) string {
+	kind := SessionKind(sessionKey)
+	if entry == nil || kind == SessionKindGlobal {
+		return kind
+	}
+	switch entry.LastPeerKind {
+	case SessionKindDM, SessionKindGroup, SessionKindChannel:
+		return entry.LastPeerKind
+	}
+	return kind
+}
+
+// SessionTTLs maps a session kind to how long its sessions may sit idle before eviction.
+// Kinds without an entry use the SessionKindDefault TTL; a zero TTL never evicts.
+type SessionTTLs map[string]time.Duration
+
+// For returns the TTL that applies to a session key
+func (t SessionTTLs) For(sessionKey string) time.Duration {
+	return t.forKind(SessionKind(sessionKey))
+}
+
+// forKind returns the TTL for a session kind, or the default TTL
+func (t SessionTTLs) forKind(kind string) time.Duration {
+	if ttl, ok := t[kind]; ok {
+		return ttl
+	}
+	return t[SessionKindDefault]
+}
+
+// expired reports whether an entry has been idle longer than its kind's TTL
+func (t SessionTTLs) expired(sessionKey string, entry *SessionEntry, nowMs int64) bool {
+	if entry == nil {
+		return false
+	}
+	ttl := t.forKind(EntrySessionKind(sessionKey, entry))
+	if ttl <= 0 {
+		return false
+	}
+	return nowMs-entry.UpdatedAt > ttl.Milliseconds()
+}
+
+// EvictOlderThan removes sessions that have been idle longer than the TTL for their kind
+// and returns the evicted keys. The store is only rewritten when something expired.
+func (s *Store) EvictOlderThan(ttls SessionTTLs) ([]string, error) {
+	nowMs := time.Now().UnixMilli()
+
+	// Check the (cached) store first so an idle sweep doesn't take the lock or rewrite the file
+	store, err := s.Load()
+	if err != nil {
+		return nil, err
+	}
+	found := false
+	for key, entry := range store {
+		if ttls.expired(key, entry, nowMs) {
+			found = true
+			break
+		}
+	}
+	if !found {
+		return nil, nil
+	}
+
+	var evicted []string
+	err = s.Update(func(store map[string]*SessionEntry) error {
+		for key, entry := range store {
+			if ttls.expired(key, entry, nowMs) {
+				delete(store, key)
+				evicted = append(evicted, key)
+			}
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return evicted, nil
+}
+
+// PruneExpired removes every session idle longer than maxAge, regardless of kind, and
+// returns how many were removed
+func (s *Store) PruneExpired(maxAge time.Duration) (int, error) {
+	evicted, err := s.EvictOlderThan(SessionTTLs{SessionKindDefault: maxAge})
+	return len(evicted), err
+}
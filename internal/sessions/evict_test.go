@@ -0,0 +1,86 @@
+package sessions
+
+import (
+	"sort"
+	"testing"
+	"time"
+)
+
+func TestEntrySessionKind(t *testing.T) {
+	tests := []struct {
+		name  string
+		key   string
+		entry *SessionEntry
+		want  string
+	}{
+		{name: "no entry uses the key", key: "webhook:m1", want: SessionKindDM},
+		{name: "no recorded kind uses the key", key: "agent:main:webhook:group:g1", entry: &SessionEntry{}, want: SessionKindGroup},
+		{name: "recorded kind beats the key", key: "webhook:m1", entry: &SessionEntry{LastPeerKind: "group"}, want: SessionKindGroup},
+		{name: "unknown recorded kind uses the key", key: "webhook:m1", entry: &SessionEntry{LastPeerKind: "forum"}, want: SessionKindDM},
+		{name: "global stays global", key: "global", entry: &SessionEntry{LastPeerKind: "dm"}, want: SessionKindGlobal},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := EntrySessionKind(tt.key, tt.entry); got != tt.want {
+				t.Errorf("EntrySessionKind(%q) = %q, want %q", tt.key, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestEvictOlderThanUsesStoredKind(t *testing.T) {
+	s := newTestStore(t, StoreFormatJSON, true)
+	now := time.Now()
+	idle := func(d time.Duration, peerKind string) *SessionEntry {
+		return &SessionEntry{SessionID: "sess", UpdatedAt: now.Add(-d).UnixMilli(), LastPeerKind: peerKind}
+	}
+	if err := s.Save(map[string]*SessionEntry{
+		"webhook:dm-by-key":           idle(2*time.Hour, ""),
+		"webhook:stored-group":        idle(2*time.Hour, "group"),
+		"webhook:stored-dm":           idle(2*time.Hour, "dm"),
+		"agent:main:webhook:group:g1": idle(2*time.Hour, ""),
+		"agent:main:webhook:group:g2": idle(4*time.Hour, ""),
+		"agent:main:webhook:dm:u1":    idle(4*time.Hour, "channel"),
+		"webhook:fresh":               idle(time.Minute, ""),
+	}); err != nil {
+		t.Fatalf("Save: %v", err)
+	}
+
+	evicted, err := s.EvictOlderThan(SessionTTLs{
+		SessionKindDM:      time.Hour,
+		SessionKindGroup:   3 * time.Hour,
+		SessionKindChannel: 0, // Never evicted
+	})
+	if err != nil {
+		t.Fatalf("EvictOlderThan: %v", err)
+	}
+
+	sort.Strings(evicted)
+	want := []string{"agent:main:webhook:group:g2", "webhook:dm-by-key", "webhook:stored-dm"}
+	if len(evicted) != len(want) {
+		t.Fatalf("evicted %q, want %q", evicted, want)
+	}
+	for i := range want {
+		if evicted[i] != want[i] {
+			t.Fatalf("evicted %q, want %q", evicted, want)
+		}
+	}
+}
+
+func TestRecordInboundMetaKeepsPeerKind(t *testing.T) {
+	s := newTestStore(t, StoreFormatJSON, true)
+	const key = "webhook:m1"
+
+	if _, err := s.RecordInboundMeta(key, "m1", &DeliveryContext{Channel: "webhook", PeerKind: "group"}); err != nil {
+		t.Fatalf("RecordInboundMeta: %v", err)
+	}
+	// A later message without a chat type keeps the recorded kind
+	entry, err := s.RecordInboundMeta(key, "m2", &DeliveryContext{Channel: "webhook"})
+	if err != nil {
+		t.Fatalf("RecordInboundMeta: %v", err)
+	}
+	if entry.LastPeerKind != "group" {
+		t.Errorf("LastPeerKind = %q, want %q", entry.LastPeerKind, "group")
+	}
+}
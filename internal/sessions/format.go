@@ -0,0 +1,58 @@
+package sessions
+
+import (
+	"bytes"
+	"encoding/gob"
+	"encoding/json"
+	"fmt"
+)
+
+// StoreFormat selects how the session store is serialized on disk
+type StoreFormat string
+
+const (
+	// StoreFormatJSON writes JSON, indented unless StoreConfig.Pretty is false (default)
+	StoreFormatJSON StoreFormat = "json"
+	// StoreFormatGob writes Go's binary gob encoding, smaller and faster for large stores
+	StoreFormatGob StoreFormat = "gob"
+)
+
+// gobMagic prefixes gob-encoded stores so reads can tell the formats apart by content.
+// Reads accept either format regardless of the configured one, so switching formats
+// migrates the file on the next write.
+var gobMagic = []byte("OCSTORE-GOB1\n")
+
+// encodeStore serializes the store in the configured format
+func (s *Store) encodeStore(store map[string]*SessionEntry) ([]byte, error) {
+	switch s.config.Format {
+	case StoreFormatGob:
+		// gob can't encode nil map values
+		entries := make(map[string]*SessionEntry, len(store))
+		for key, entry := range store {
+			if entry != nil {
+				entries[key] = entry
+			}
+		}
+		var buf bytes.Buffer
+		buf.Write(gobMagic)
+		if err := gob.NewEncoder(&buf).Encode(entries); err != nil {
+			return nil, err
+		}
+		return buf.Bytes(), nil
+	case StoreFormatJSON, "":
+		if s.config.Pretty {
+			return json.MarshalIndent(store, "", "  ")
+		}
+		return json.Marshal(store)
+	default:
+		return nil, fmt.Errorf("unknown store format %q", s.config.Format)
+	}
+}
+
+// decodeStore parses a store file in whichever format it was written
+func decodeStore(data []byte, store *map[string]*SessionEntry) error {
+	if bytes.HasPrefix(data, gobMagic) {
+		return gob.NewDecoder(bytes.NewReader(data[len(gobMagic):])).Decode(store)
+	}
+	return json.Unmarshal(data, store)
+}
@@ -1,8 +1,11 @@
 package sessions
 
 import (
+	"crypto/sha256"
+	"encoding/hex"
 	"fmt"
 	"strings"
+	"unicode/utf8"
 )
 
 const (
@@ -10,13 +13,22 @@ const (
 	DefaultAgentID = "main"
 	// DefaultMainKey is the default main session key
 	DefaultMainKey = "main"
+
+	// MinSessionKeyLength is the smallest usable session key limit: shorter limits would
+	// leave no room for the "agent:<id>:webhook:<kind>" prefix next to the hashed tail
+	MinSessionKeyLength = 64
+
+	// sessionKeyHashLen is the number of hex characters used for a hashed key tail
+	sessionKeyHashLen = 16
 )
 
 // WebhookMessage represents an incoming webhook message
 type WebhookMessage struct {
-	ID      string
-	Content string
-	Session string
+	ID       string
+	Content  string
+	Session  string
+	ChatID   string // Chat the message was sent in, for per-chat scope
+	ChatType string // "group", "channel" or "dm" (default "group")
 }
 
 // ResolveSessionKey resolves the session key for a webhook message
@@ -24,15 +36,21 @@ type WebhookMessage struct {
 // - If an explicit session is provided in the message, use it
 // - For per-sender scope, use webhook:{id} pattern
 // - For global scope, use "global"
+// - For per-chat scope, key by chat (falling back to per-sender without a chatId)
 func ResolveSessionKey(scope SessionScope, msg *WebhookMessage) string {
 	// Check for explicit session key in the message
 	if msg.Session != "" {
-		return strings.ToLower(strings.TrimSpace(msg.Session))
+		return NormalizeSessionKey(msg.Session)
 	}
 
 	switch scope {
 	case SessionScopeGlobal:
 		return "global"
+	case SessionScopePerChat:
+		if key, ok := ChatSessionKey("", msg.ChatID, msg.ChatType); ok {
+			return key
+		}
+		fallthrough
 	case SessionScopePerSender:
 		fallthrough
 	default:
@@ -46,6 +64,22 @@ func ResolveSessionKey(scope SessionScope, msg *WebhookMessage) string {
 	}
 }
 
+// ChatSessionKey builds the key shared by every participant of a chat (per-chat scope).
+// An unknown or empty chat type is treated as a group. Returns ("", false) without a chat ID.
+func ChatSessionKey(agentID, chatID, chatType string) (string, bool) {
+	chatType = strings.ToLower(strings.TrimSpace(chatType))
+	switch chatType {
+	case "group", "channel", "dm":
+	default:
+		chatType = "group"
+	}
+	return BuildWebhookSessionKey(WebhookSessionParams{
+		AgentID:  agentID,
+		PeerKind: chatType,
+		PeerID:   chatID,
+	})
+}
+
 // WebhookSessionParams defines optional routing metadata for webhook sessions.
 type WebhookSessionParams struct {
 	AgentID  string
@@ -133,3 +167,28 @@ func ParseSessionKey(sessionKey string) (agentID, sessionType, identifier string
 func NormalizeSessionKey(sessionKey string) string {
 	return strings.ToLower(strings.TrimSpace(sessionKey))
 }
+
+// LimitSessionKey caps a session key at limit bytes (0 = unlimited), keeping the readable
+// prefix and replacing the rest with a short hash of the removed tail, so the same key
+// always maps to the same result. Limits below MinSessionKeyLength are raised to it.
+func LimitSessionKey(sessionKey string, limit int) string {
+	if limit <= 0 {
+		return sessionKey
+	}
+	if limit < MinSessionKeyLength {
+		limit = MinSessionKeyLength
+	}
+	if len(sessionKey) <= limit {
+		return sessionKey
+	}
+
+	keep := limit - sessionKeyHashLen - 1
+
+	// Don't split a multi-byte character
+	for keep > 0 && !utf8.RuneStart(sessionKey[keep]) {
+		keep--
+	}
+
+	sum := sha256.Sum256([]byte(sessionKey[keep:]))
+	return sessionKey[:keep] + ":" + hex.EncodeToString(sum[:])[:sessionKeyHashLen]
+}
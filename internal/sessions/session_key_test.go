@@ -0,0 +1,149 @@
+package sessions
+
+import (
+	"strings"
+	"testing"
+	"unicode/utf8"
+)
+
+func TestResolveSessionKey(t *testing.T) {
+	tests := []struct {
+		name  string
+		scope SessionScope
+		msg   WebhookMessage
+		want  string
+	}{
+		{
+			name:  "explicit session wins over the scope",
+			scope: SessionScopePerChat,
+			msg:   WebhookMessage{ID: "m1", Session: " Agent:Main:Custom ", ChatID: "c1"},
+			want:  "agent:main:custom",
+		},
+		{
+			name:  "per-sender keys by message ID",
+			scope: SessionScopePerSender,
+			msg:   WebhookMessage{ID: "m1", ChatID: "c1"},
+			want:  "webhook:m1",
+		},
+		{
+			name:  "per-sender without an ID uses the main session",
+			scope: SessionScopePerSender,
+			msg:   WebhookMessage{},
+			want:  "agent:main:main",
+		},
+		{
+			name:  "global shares one session",
+			scope: SessionScopeGlobal,
+			msg:   WebhookMessage{ID: "m1", ChatID: "c1"},
+			want:  "global",
+		},
+		{
+			name:  "per-chat defaults to a group chat",
+			scope: SessionScopePerChat,
+			msg:   WebhookMessage{ID: "m1", ChatID: "Room-42"},
+			want:  "agent:main:webhook:group:room-42",
+		},
+		{
+			name:  "per-chat keeps the chat type",
+			scope: SessionScopePerChat,
+			msg:   WebhookMessage{ID: "m1", ChatID: "alice", ChatType: " DM "},
+			want:  "agent:main:webhook:dm:alice",
+		},
+		{
+			name:  "per-chat treats an unknown chat type as a group",
+			scope: SessionScopePerChat,
+			msg:   WebhookMessage{ID: "m1", ChatID: "c1", ChatType: "forum"},
+			want:  "agent:main:webhook:group:c1",
+		},
+		{
+			name:  "per-chat without a chat ID falls back to per-sender",
+			scope: SessionScopePerChat,
+			msg:   WebhookMessage{ID: "m1"},
+			want:  "webhook:m1",
+		},
+		{
+			name: "unset scope behaves like per-sender",
+			msg:  WebhookMessage{ID: "m1", ChatID: "c1"},
+			want: "webhook:m1",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := ResolveSessionKey(tt.scope, &tt.msg); got != tt.want {
+				t.Errorf("ResolveSessionKey(%q) = %q, want %q", tt.scope, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestResolveSessionKeySameChatSharesSession(t *testing.T) {
+	first := ResolveSessionKey(SessionScopePerChat, &WebhookMessage{ID: "m1", ChatID: "c1"})
+	second := ResolveSessionKey(SessionScopePerChat, &WebhookMessage{ID: "m2", ChatID: "c1"})
+	other := ResolveSessionKey(SessionScopePerChat, &WebhookMessage{ID: "m3", ChatID: "c2"})
+
+	if first != second {
+		t.Errorf("messages in the same chat got different sessions: %q and %q", first, second)
+	}
+	if first == other {
+		t.Errorf("messages in different chats share session %q", first)
+	}
+}
+
+func TestLimitSessionKey(t *testing.T) {
+	const prefix = "agent:main:webhook:group:"
+	long := prefix + strings.Repeat("x", 200)
+
+	tests := []struct {
+		name  string
+		key   string
+		other string // Key differing only in its tail, must not collide with key
+		limit int
+		max   int // Expected maximum length
+	}{
+		{name: "long group key", key: long + "a", other: long + "b", limit: 100, max: 100},
+		{name: "limit at the minimum", key: long + "a", other: long + "b", limit: MinSessionKeyLength, max: MinSessionKeyLength},
+		{name: "limit below the minimum is raised", key: long + "a", other: long + "b", limit: 17, max: MinSessionKeyLength},
+		{name: "multi-byte tail", key: prefix + strings.Repeat("é", 100) + "a", other: prefix + strings.Repeat("é", 100) + "b", limit: 80, max: 80},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got := LimitSessionKey(tt.key, tt.limit)
+			if again := LimitSessionKey(tt.key, tt.limit); again != got {
+				t.Errorf("same key limited to %q, then %q", got, again)
+			}
+			if len(got) > tt.max {
+				t.Errorf("len(%q) = %d, want at most %d", got, len(got), tt.max)
+			}
+			if !utf8.ValidString(got) {
+				t.Errorf("%q is not valid UTF-8", got)
+			}
+			if !strings.HasPrefix(got, prefix) {
+				t.Errorf("%q lost the %q prefix", got, prefix)
+			}
+			if agentID, sessionType, _ := ParseSessionKey(got); agentID != "main" || sessionType != "webhook" {
+				t.Errorf("ParseSessionKey(%q) = %q, %q, want main, webhook", got, agentID, sessionType)
+			}
+			if kind := SessionKind(got); kind != SessionKindGroup {
+				t.Errorf("SessionKind(%q) = %q, want %q", got, kind, SessionKindGroup)
+			}
+			if other := LimitSessionKey(tt.other, tt.limit); other == got {
+				t.Errorf("keys with different tails both limited to %q", got)
+			}
+		})
+	}
+}
+
+func TestLimitSessionKeyKeepsShortKeys(t *testing.T) {
+	key := "agent:main:webhook:dm:u1"
+	for _, limit := range []int{0, 17, MinSessionKeyLength} {
+		if got := LimitSessionKey(key, limit); got != key {
+			t.Errorf("LimitSessionKey(%q, %d) = %q, want it unchanged", key, limit, got)
+		}
+	}
+	long := strings.Repeat("x", 500)
+	if got := LimitSessionKey(long, 0); got != long {
+		t.Errorf("limit 0 changed a %d byte key", len(long))
+	}
+}
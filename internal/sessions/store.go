@@ -1,17 +1,31 @@
 package sessions
 
 import (
-	"encoding/json"
 	"fmt"
 	"log"
 	"os"
 	"path/filepath"
+	"sort"
 	"sync"
 	"time"
 
 	"github.com/gofrs/flock"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
 )
 
+// Store operation counters, resolved once so instrumentation only costs an atomic add
+var (
+	storeCacheHits        = metrics.Default.Counter(metrics.StoreCacheHitsTotal)
+	storeCacheMisses      = metrics.Default.Counter(metrics.StoreCacheMissesTotal)
+	storeCacheEvictions   = metrics.Default.Counter(metrics.StoreCacheEvictionsTotal)
+	storeSaves            = metrics.Default.Counter(metrics.StoreSavesTotal)
+	storeLockAcquisitions = metrics.Default.Counter(metrics.StoreLockAcquisitionsTotal)
+	storeLockWaitMs       = metrics.Default.Counter(metrics.StoreLockWaitMsTotal)
+)
+
+// maxClockSkew is how far in the future an updatedAt may be before it's treated as bogus
+const maxClockSkew = 5 * time.Minute
+
 // Store manages session persistence with in-memory caching and file locking
 type Store struct {
 	config      *StoreConfig
@@ -20,20 +34,31 @@ type Store struct {
 	lockDir     string
 	enableCache bool
 
-	// Cached file mtime with periodic refresh
-	mtimeCache    int64
-	mtimeCacheMu  sync.RWMutex
-	mtimeCacheExp time.Time
+	// Cached file stamp with periodic refresh
+	stampCache    fileStamp
+	stampCacheMu  sync.RWMutex
+	stampCacheExp time.Time
+
+	// batcher coalesces concurrent UpdateEntry calls into one write (nil = disabled)
+	batcher *writeBatcher
 }
 
 // StoreCache holds cached session data
 type StoreCache struct {
 	store     map[string]*SessionEntry
 	loadedAt  time.Time
-	mtimeMs   int64
+	stamp     fileStamp
 	validOnce bool
 }
 
+// fileStamp identifies a version of the store file. Size is compared along with the
+// mtime so a rewrite by another process (the gateway, a second bridge) is noticed even
+// on filesystems with coarse timestamps.
+type fileStamp struct {
+	mtimeNs int64
+	size    int64
+}
+
 // ReadonlyStore provides a read-only view of the session store
 type ReadonlyStore struct {
 	store map[string]*SessionEntry
@@ -69,17 +94,27 @@ func (s *Store) Load() (map[string]*SessionEntry, error) {
 	if s.enableCache {
 		s.cacheMu.RLock()
 		if s.cache != nil && s.isCacheValid(s.cache) {
-			// Check if file hasn't been modified (using cached mtime)
-			if s.getFileMtimeMsCached() == s.cache.mtimeMs {
+			// Check if file hasn't been modified, even within the TTL (using the cached stamp)
+			if s.getFileStampCached() == s.cache.stamp {
 				// Return a copy for backward compatibility
 				result := s.copyStore(s.cache.store)
 				s.cacheMu.RUnlock()
+				storeCacheHits.Add(1)
 				log.Printf("[SessionStore] Loaded from cache (%d sessions)", len(result))
 				return result, nil
 			}
 		}
+		if s.cache != nil {
+			// Present but stale (expired or file changed underneath)
+			storeCacheEvictions.Add(1)
+		}
 		s.cacheMu.RUnlock()
 	}
+	storeCacheMisses.Add(1)
+
+	// Stat before reading so a concurrent writer's newer file can never be cached
+	// under our (older) contents
+	stamp := s.getFileStamp()
 
 	// Load from disk
 	store := make(map[string]*SessionEntry)
@@ -93,10 +128,13 @@ func (s *Store) Load() (map[string]*SessionEntry, error) {
 		return nil, fmt.Errorf("failed to read store: %w", err)
 	}
 
-	if err := json.Unmarshal(data, &store); err != nil {
-		log.Printf("[SessionStore] Failed to parse store, starting fresh: %v", err)
-		return make(map[string]*SessionEntry), nil
+	// Never hand out an empty store in place of one we couldn't read; the next write backs
+	// the corrupt file up before replacing it (see loadUnlocked)
+	if err := decodeStore(data, &store); err != nil {
+		log.Printf("[SessionStore] ERROR: failed to parse %s: %v", s.config.StorePath, err)
+		return nil, fmt.Errorf("failed to parse store %s: %w", s.config.StorePath, err)
 	}
+	clampFutureTimestamps(store)
 
 	// Update cache
 	if s.enableCache {
@@ -104,7 +142,7 @@ func (s *Store) Load() (map[string]*SessionEntry, error) {
 		s.cache = &StoreCache{
 			store:    s.copyStore(store),
 			loadedAt: time.Now(),
-			mtimeMs:  s.getFileMtimeMsCached(),
+			stamp:    stamp,
 		}
 		s.cacheMu.Unlock()
 	}
@@ -113,6 +151,47 @@ func (s *Store) Load() (map[string]*SessionEntry, error) {
 	return store, nil
 }
 
+// KeyedEntry is a session entry together with its key
+type KeyedEntry struct {
+	Key   string
+	Entry *SessionEntry
+}
+
+// ListOptions controls the order and size of ListEntries results
+type ListOptions struct {
+	// NewestFirst sorts by UpdatedAt descending; otherwise entries are sorted by key
+	NewestFirst bool
+	// Limit caps the number of entries returned (0 = all)
+	Limit int
+}
+
+// ListEntries returns all sessions as key/entry pairs, using the cache like Load.
+// Ties are broken by key so the order is stable between calls.
+func (s *Store) ListEntries(opts ListOptions) ([]KeyedEntry, error) {
+	store, err := s.Load()
+	if err != nil {
+		return nil, err
+	}
+
+	entries := make([]KeyedEntry, 0, len(store))
+	for key, entry := range store {
+		if entry != nil {
+			entries = append(entries, KeyedEntry{Key: key, Entry: entry})
+		}
+	}
+	sort.Slice(entries, func(i, j int) bool {
+		if opts.NewestFirst && entries[i].Entry.UpdatedAt != entries[j].Entry.UpdatedAt {
+			return entries[i].Entry.UpdatedAt > entries[j].Entry.UpdatedAt
+		}
+		return entries[i].Key < entries[j].Key
+	})
+
+	if opts.Limit > 0 && len(entries) > opts.Limit {
+		entries = entries[:opts.Limit]
+	}
+	return entries, nil
+}
+
 // LoadReadonly loads the session store and returns a read-only view
 // This is more efficient than Load() for read-heavy workloads
 func (s *Store) LoadReadonly() (*ReadonlyStore, error) {
@@ -166,7 +245,10 @@ func (s *Store) Save(store map[string]*SessionEntry) error {
 	})
 }
 
-// Update atomically updates the session store
+// Update atomically updates the session store.
+// The store is re-read from disk under the exclusive file lock (bypassing the cache), mutated,
+// and written back before the lock is released, so multiple processes sharing one store file
+// merge their changes instead of overwriting each other with stale copies.
 func (s *Store) Update(mutator func(map[string]*SessionEntry) error) error {
 	return s.withLock(func() error {
 		// Always re-read inside the lock to avoid clobbering concurrent writers
@@ -194,6 +276,10 @@ func (s *Store) GetEntry(sessionKey string) (*SessionEntry, error) {
 
 // UpdateEntry updates a single session entry
 func (s *Store) UpdateEntry(sessionKey string, update func(*SessionEntry) (*SessionEntry, error)) (*SessionEntry, error) {
+	if s.batcher != nil {
+		return s.batcher.do(sessionKey, update)
+	}
+
 	var result *SessionEntry
 	err := s.Update(func(store map[string]*SessionEntry) error {
 		existing := store[sessionKey]
@@ -215,9 +301,33 @@ func (s *Store) UpdateEntry(sessionKey string, update func(*SessionEntry) (*Sess
 	return result, err
 }
 
+// DeleteEntry removes a session under the exclusive lock and reports whether it existed.
+// The store is only rewritten (and the cache dropped) when something was removed.
+func (s *Store) DeleteEntry(sessionKey string) (bool, error) {
+	removed := false
+	err := s.withLock(func() error {
+		store, err := s.loadUnlocked()
+		if err != nil {
+			return err
+		}
+		if _, ok := store[sessionKey]; !ok {
+			return nil
+		}
+		delete(store, sessionKey)
+		removed = true
+		return s.saveUnlocked(store)
+	})
+	return removed, err
+}
+
 // RecordInboundMeta records session metadata from an incoming webhook message
 func (s *Store) RecordInboundMeta(sessionKey string, webhookMsgID string, deliveryCtx *DeliveryContext) (*SessionEntry, error) {
-	return s.UpdateEntry(sessionKey, func(existing *SessionEntry) (*SessionEntry, error) {
+	return s.UpdateEntry(sessionKey, inboundMetaUpdate(sessionKey, webhookMsgID, deliveryCtx))
+}
+
+// inboundMetaUpdate is the UpdateEntry function behind RecordInboundMeta, shared by all backends
+func inboundMetaUpdate(sessionKey string, webhookMsgID string, deliveryCtx *DeliveryContext) func(*SessionEntry) (*SessionEntry, error) {
+	return func(existing *SessionEntry) (*SessionEntry, error) {
 		now := time.Now().UnixMilli()
 
 		if existing != nil {
@@ -231,6 +341,7 @@ func (s *Store) RecordInboundMeta(sessionKey string, webhookMsgID string, delive
 				LastTo:           deliveryTo(deliveryCtx),
 				LastAccountId:    deliveryAccountId(deliveryCtx),
 				LastThreadId:     deliveryThreadId(deliveryCtx),
+				LastPeerKind:     deliveryPeerKind(deliveryCtx),
 				WebhookMessageID: webhookMsgID,
 				WebhookSessionID: sessionKey,
 				// Preserve existing state
@@ -254,15 +365,21 @@ func (s *Store) RecordInboundMeta(sessionKey string, webhookMsgID string, delive
 			LastTo:           deliveryTo(deliveryCtx),
 			LastAccountId:    deliveryAccountId(deliveryCtx),
 			LastThreadId:     deliveryThreadId(deliveryCtx),
+			LastPeerKind:     deliveryPeerKind(deliveryCtx),
 			WebhookMessageID: webhookMsgID,
 			WebhookSessionID: sessionKey,
 		}, nil
-	})
+	}
 }
 
 // UpdateLastRoute updates the last delivery route for a session
 func (s *Store) UpdateLastRoute(sessionKey string, deliveryCtx *DeliveryContext) (*SessionEntry, error) {
-	return s.UpdateEntry(sessionKey, func(existing *SessionEntry) (*SessionEntry, error) {
+	return s.UpdateEntry(sessionKey, lastRouteUpdate(deliveryCtx))
+}
+
+// lastRouteUpdate is the UpdateEntry function behind UpdateLastRoute, shared by all backends
+func lastRouteUpdate(deliveryCtx *DeliveryContext) func(*SessionEntry) (*SessionEntry, error) {
+	return func(existing *SessionEntry) (*SessionEntry, error) {
 		patch := &SessionEntry{
 			UpdatedAt:       time.Now().UnixMilli(),
 			DeliveryContext: deliveryCtx,
@@ -272,7 +389,15 @@ func (s *Store) UpdateLastRoute(sessionKey string, deliveryCtx *DeliveryContext)
 			LastThreadId:    deliveryThreadId(deliveryCtx),
 		}
 		return patch, nil
-	})
+	}
+}
+
+// Close writes any batched updates still waiting (see SetWriteBatching)
+func (s *Store) Close() error {
+	if s.batcher != nil {
+		s.batcher.flush()
+	}
+	return nil
 }
 
 // loadUnlocked loads without cache checks (must be called with lock held)
@@ -286,35 +411,76 @@ func (s *Store) loadUnlocked() (map[string]*SessionEntry, error) {
 		return nil, fmt.Errorf("failed to read store: %w", err)
 	}
 
-	if err := json.Unmarshal(data, &store); err != nil {
-		log.Printf("[SessionStore] Failed to parse store: %v", err)
+	// Keep a copy of a corrupt store before starting over, and refuse to continue if that
+	// fails rather than write an empty store over entries we couldn't read
+	if err := decodeStore(data, &store); err != nil {
+		backupPath, backupErr := s.backupCorruptStore(data)
+		if backupErr != nil {
+			return nil, fmt.Errorf("failed to parse store %s (%v) or back it up: %w", s.config.StorePath, err, backupErr)
+		}
+		log.Printf("[SessionStore] ERROR: failed to parse %s: %v. Saved a copy to %s and starting with an empty store; restore sessions from it by hand",
+			s.config.StorePath, err, backupPath)
 		return make(map[string]*SessionEntry), nil
 	}
+	clampFutureTimestamps(store)
 
 	return store, nil
 }
 
+// backupCorruptStore copies an unreadable store file to <store>.bak, or to a timestamped
+// name when an earlier backup exists, and returns the backup path
+func (s *Store) backupCorruptStore(data []byte) (string, error) {
+	backupPath := s.config.StorePath + ".bak"
+	if _, err := os.Stat(backupPath); err == nil {
+		backupPath = fmt.Sprintf("%s.bak.%d", s.config.StorePath, time.Now().UnixMilli())
+	}
+	if err := writeFileSync(backupPath, data, 0600); err != nil {
+		return "", err
+	}
+	return backupPath, nil
+}
+
+// clampFutureTimestamps resets updatedAt values implausibly far in the future to now.
+// They come from clock jumps (VM restores, NTP corrections) and would otherwise make an
+// entry look newest forever. Clamped values are persisted by the next write.
+func clampFutureTimestamps(store map[string]*SessionEntry) {
+	now := time.Now().UnixMilli()
+	limit := now + maxClockSkew.Milliseconds()
+	for key, entry := range store {
+		if entry != nil && entry.UpdatedAt > limit {
+			log.Printf("[SessionStore] Warning: session %s has updatedAt %s in the future, clamping to now",
+				key, time.Duration(entry.UpdatedAt-now)*time.Millisecond)
+			entry.UpdatedAt = now
+		}
+	}
+}
+
 // saveUnlocked saves without locking (must be called with lock held)
 func (s *Store) saveUnlocked(store map[string]*SessionEntry) error {
-	// Invalidate cache and mtime cache on write
+	// Invalidate cache and stamp cache on write
 	s.cacheMu.Lock()
+	if s.cache != nil {
+		storeCacheEvictions.Add(1)
+	}
 	s.cache = nil
 	s.cacheMu.Unlock()
 
-	s.mtimeCacheMu.Lock()
-	s.mtimeCache = 0
-	s.mtimeCacheExp = time.Time{}
-	s.mtimeCacheMu.Unlock()
+	s.stampCacheMu.Lock()
+	s.stampCache = fileStamp{}
+	s.stampCacheExp = time.Time{}
+	s.stampCacheMu.Unlock()
 
 	// Serialize
-	data, err := json.MarshalIndent(store, "", "  ")
+	data, err := s.encodeStore(store)
 	if err != nil {
 		return fmt.Errorf("failed to marshal store: %w", err)
 	}
 
-	// Write atomically using temp file
+	// Write atomically using temp file, flushed to disk before it replaces the store so a
+	// crash can leave either the old or the new contents but never a truncated file
 	tmpPath := s.config.StorePath + ".tmp"
-	if err := os.WriteFile(tmpPath, data, 0600); err != nil {
+	if err := writeFileSync(tmpPath, data, 0600); err != nil {
+		os.Remove(tmpPath)
 		return fmt.Errorf("failed to write temp file: %w", err)
 	}
 
@@ -323,11 +489,41 @@ func (s *Store) saveUnlocked(store map[string]*SessionEntry) error {
 		os.Remove(tmpPath) // Clean up temp file
 		return fmt.Errorf("failed to rename store file: %w", err)
 	}
+	syncDir(filepath.Dir(s.config.StorePath))
 
+	storeSaves.Add(1)
 	log.Printf("[SessionStore] Saved %d sessions", len(store))
 	return nil
 }
 
+// writeFileSync is os.WriteFile followed by an fsync, so the data is on disk once it returns
+func writeFileSync(path string, data []byte, perm os.FileMode) error {
+	f, err := os.OpenFile(path, os.O_WRONLY|os.O_CREATE|os.O_TRUNC, perm)
+	if err != nil {
+		return err
+	}
+	if _, err := f.Write(data); err != nil {
+		f.Close()
+		return err
+	}
+	if err := f.Sync(); err != nil {
+		f.Close()
+		return err
+	}
+	return f.Close()
+}
+
+// syncDir flushes a directory so a rename inside it survives a crash. Best effort:
+// some platforms (Windows) can't open or sync directories.
+func syncDir(dir string) {
+	d, err := os.Open(dir)
+	if err != nil {
+		return
+	}
+	d.Sync()
+	d.Close()
+}
+
 // isCacheValid checks if a cache entry is still valid
 func (s *Store) isCacheValid(cache *StoreCache) bool {
 	if cache == nil {
@@ -354,35 +550,35 @@ func (s *Store) copyStore(store map[string]*SessionEntry) map[string]*SessionEnt
 	return result
 }
 
-// getFileMtimeMsCached gets the file modification time with caching
+// getFileStampCached gets the file stamp with caching
 // Cache expires after 1 second to reduce syscalls while staying fresh
-func (s *Store) getFileMtimeMsCached() int64 {
-	s.mtimeCacheMu.RLock()
-	if time.Now().Before(s.mtimeCacheExp) && s.mtimeCache > 0 {
-		mtime := s.mtimeCache
-		s.mtimeCacheMu.RUnlock()
-		return mtime
+func (s *Store) getFileStampCached() fileStamp {
+	s.stampCacheMu.RLock()
+	if time.Now().Before(s.stampCacheExp) && s.stampCache.mtimeNs > 0 {
+		stamp := s.stampCache
+		s.stampCacheMu.RUnlock()
+		return stamp
 	}
-	s.mtimeCacheMu.RUnlock()
+	s.stampCacheMu.RUnlock()
 
 	// Cache miss or expired, get fresh value
-	mtime := s.getFileMtimeMs()
+	stamp := s.getFileStamp()
 
-	s.mtimeCacheMu.Lock()
-	s.mtimeCache = mtime
-	s.mtimeCacheExp = time.Now().Add(time.Second)
-	s.mtimeCacheMu.Unlock()
+	s.stampCacheMu.Lock()
+	s.stampCache = stamp
+	s.stampCacheExp = time.Now().Add(time.Second)
+	s.stampCacheMu.Unlock()
 
-	return mtime
+	return stamp
 }
 
-// getFileMtimeMs gets the file modification time in milliseconds
-func (s *Store) getFileMtimeMs() int64 {
+// getFileStamp stats the store file (zero stamp if it doesn't exist)
+func (s *Store) getFileStamp() fileStamp {
 	info, err := os.Stat(s.config.StorePath)
 	if err != nil {
-		return 0
+		return fileStamp{}
 	}
-	return info.ModTime().UnixMilli()
+	return fileStamp{mtimeNs: info.ModTime().UnixNano(), size: info.Size()}
 }
 
 // withLock executes a function with the store lock held using flock
@@ -410,6 +606,8 @@ func (s *Store) withLock(fn func() error) error {
 		}
 		if locked {
 			defer fileLock.Unlock()
+			storeLockAcquisitions.Add(1)
+			storeLockWaitMs.Add(uint64(time.Since(startedAt).Milliseconds()))
 			// Check for stale lock info and log
 			if info, err := os.Stat(lockPath); err == nil {
 				age := time.Since(info.ModTime())
@@ -459,6 +657,13 @@ func deliveryThreadId(ctx *DeliveryContext) string {
 	return ""
 }
 
+func deliveryPeerKind(ctx *DeliveryContext) string {
+	if ctx != nil {
+		return ctx.PeerKind
+	}
+	return ""
+}
+
 // generateSessionID generates a unique session ID
 // Using timestamp-based ID for simplicity (can be replaced with UUID)
 func generateSessionID() string {
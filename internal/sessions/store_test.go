@@ -0,0 +1,266 @@
+package sessions
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sync"
+	"testing"
+	"time"
+)
+
+// newTestStore returns a store in a fresh temporary directory, without caching so every
+// Load reads the file
+func newTestStore(t *testing.T, format StoreFormat, pretty bool) *Store {
+	t.Helper()
+	return NewStore(&StoreConfig{
+		StorePath:   filepath.Join(t.TempDir(), "sessions.json"),
+		LockTimeout: time.Second,
+		Pretty:      pretty,
+		Format:      format,
+	})
+}
+
+func TestStoreSaveReplacesFileAtomically(t *testing.T) {
+	tests := []struct {
+		name   string
+		format StoreFormat
+		pretty bool
+	}{
+		{name: "indented json", format: StoreFormatJSON, pretty: true},
+		{name: "compact json", format: StoreFormatJSON},
+		{name: "gob", format: StoreFormatGob},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestStore(t, tt.format, tt.pretty)
+
+			// Write twice so the second save replaces an existing file
+			for _, id := range []string{"sess_1", "sess_2"} {
+				if err := s.Save(map[string]*SessionEntry{"webhook:a": {SessionID: id, UpdatedAt: 1}}); err != nil {
+					t.Fatalf("Save: %v", err)
+				}
+			}
+
+			if _, err := os.Stat(s.config.StorePath + ".tmp"); !os.IsNotExist(err) {
+				t.Errorf("temp file left behind (stat error %v)", err)
+			}
+			store, err := s.Load()
+			if err != nil {
+				t.Fatalf("Load: %v", err)
+			}
+			if got := store["webhook:a"]; got == nil || got.SessionID != "sess_2" {
+				t.Errorf("loaded entry %+v, want session sess_2", got)
+			}
+		})
+	}
+}
+
+func TestStoreUpdateEntryMergesPatch(t *testing.T) {
+	s := newTestStore(t, StoreFormatJSON, true)
+
+	if _, err := s.UpdateEntry("webhook:a", func(*SessionEntry) (*SessionEntry, error) {
+		return &SessionEntry{SessionID: "sess_1", UpdatedAt: 1, Label: "vip"}, nil
+	}); err != nil {
+		t.Fatalf("UpdateEntry: %v", err)
+	}
+	got, err := s.UpdateEntry("webhook:a", func(existing *SessionEntry) (*SessionEntry, error) {
+		if existing == nil || existing.SessionID != "sess_1" {
+			t.Errorf("update got existing entry %+v, want session sess_1", existing)
+		}
+		return &SessionEntry{UpdatedAt: 2, LastChannel: "webhook"}, nil
+	})
+	if err != nil {
+		t.Fatalf("UpdateEntry: %v", err)
+	}
+
+	want := SessionEntry{SessionID: "sess_1", UpdatedAt: 2, Label: "vip", LastChannel: "webhook"}
+	if got == nil || got.SessionID != want.SessionID || got.UpdatedAt != want.UpdatedAt ||
+		got.Label != want.Label || got.LastChannel != want.LastChannel {
+		t.Errorf("merged entry %+v, want %+v", got, want)
+	}
+}
+
+func TestStoreHandlesShareFile(t *testing.T) {
+	// Two handles on one file stand in for two bridge processes sharing sessions.json
+	path := filepath.Join(t.TempDir(), "sessions.json")
+	handles := []*Store{
+		NewStore(&StoreConfig{StorePath: path, LockTimeout: 10 * time.Second, Format: StoreFormatJSON}),
+		NewStore(&StoreConfig{StorePath: path, LockTimeout: 10 * time.Second, Format: StoreFormatJSON}),
+	}
+
+	const perHandle = 20
+	var wg sync.WaitGroup
+	errs := make(chan error, 2*perHandle)
+	for h, s := range handles {
+		for i := 0; i < perHandle; i++ {
+			wg.Add(1)
+			go func(s *Store, key string, whole bool) {
+				defer wg.Done()
+				entry := &SessionEntry{SessionID: "sess_" + key, UpdatedAt: 1}
+				if whole {
+					errs <- s.Update(func(store map[string]*SessionEntry) error {
+						store[key] = entry
+						return nil
+					})
+					return
+				}
+				_, err := s.UpdateEntry(key, func(*SessionEntry) (*SessionEntry, error) {
+					return entry, nil
+				})
+				errs <- err
+			}(s, fmt.Sprintf("webhook:%d-%d", h, i), i%2 == 0)
+		}
+	}
+	wg.Wait()
+	close(errs)
+	for err := range errs {
+		if err != nil {
+			t.Fatalf("update: %v", err)
+		}
+	}
+
+	for _, s := range handles {
+		store, err := s.Load()
+		if err != nil {
+			t.Fatalf("Load: %v", err)
+		}
+		for h := range handles {
+			for i := 0; i < perHandle; i++ {
+				key := fmt.Sprintf("webhook:%d-%d", h, i)
+				if store[key] == nil || store[key].SessionID != "sess_"+key {
+					t.Errorf("%s lost, got %+v", key, store[key])
+				}
+			}
+		}
+	}
+}
+
+func TestStoreBacksUpCorruptFile(t *testing.T) {
+	corrupt := []byte(`{"webhook:a": {"sessionId": "sess_1",`)
+
+	tests := []struct {
+		name          string
+		earlierBackup bool
+	}{
+		{name: "first backup goes to .bak"},
+		{name: "an earlier backup is kept", earlierBackup: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestStore(t, StoreFormatJSON, true)
+			path := s.config.StorePath
+			if err := os.WriteFile(path, corrupt, 0600); err != nil {
+				t.Fatal(err)
+			}
+			if tt.earlierBackup {
+				if err := os.WriteFile(path+".bak", []byte("earlier"), 0600); err != nil {
+					t.Fatal(err)
+				}
+			}
+
+			// Reads refuse to pass off the unreadable file as an empty store
+			if _, err := s.Load(); err == nil {
+				t.Fatal("Load of a corrupt store succeeded")
+			}
+
+			// A write backs the file up and starts over
+			if _, err := s.UpdateEntry("webhook:b", func(*SessionEntry) (*SessionEntry, error) {
+				return &SessionEntry{SessionID: "sess_2", UpdatedAt: 1}, nil
+			}); err != nil {
+				t.Fatalf("UpdateEntry: %v", err)
+			}
+
+			backups, err := filepath.Glob(path + ".bak*")
+			if err != nil {
+				t.Fatal(err)
+			}
+			wantBackups := 1
+			if tt.earlierBackup {
+				wantBackups = 2
+			}
+			if len(backups) != wantBackups {
+				t.Fatalf("backups %v, want %d", backups, wantBackups)
+			}
+			foundCorrupt := false
+			for _, backup := range backups {
+				data, err := os.ReadFile(backup)
+				if err != nil {
+					t.Fatal(err)
+				}
+				switch string(data) {
+				case string(corrupt):
+					foundCorrupt = true
+				case "earlier":
+					if backup != path+".bak" {
+						t.Errorf("earlier backup moved to %s", backup)
+					}
+				default:
+					t.Errorf("unexpected backup %s: %q", backup, data)
+				}
+			}
+			if !foundCorrupt {
+				t.Error("corrupt store was not backed up")
+			}
+
+			store, err := s.Load()
+			if err != nil {
+				t.Fatalf("Load after recovery: %v", err)
+			}
+			if len(store) != 1 || store["webhook:b"] == nil {
+				t.Errorf("store after recovery = %v, want only webhook:b", store)
+			}
+		})
+	}
+}
+
+func TestClampFutureTimestamps(t *testing.T) {
+	tests := []struct {
+		name    string
+		offset  time.Duration // updatedAt relative to now
+		clamped bool
+	}{
+		{name: "past timestamp is kept", offset: -time.Hour},
+		{name: "small skew is tolerated", offset: time.Minute},
+		{name: "far future is clamped", offset: 24 * time.Hour, clamped: true},
+		{name: "just past the allowed skew is clamped", offset: maxClockSkew + time.Second, clamped: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			before := time.Now().UnixMilli()
+			updatedAt := before + tt.offset.Milliseconds()
+			store := map[string]*SessionEntry{"webhook:a": {UpdatedAt: updatedAt}, "webhook:nil": nil}
+
+			clampFutureTimestamps(store)
+			after := time.Now().UnixMilli()
+
+			got := store["webhook:a"].UpdatedAt
+			if tt.clamped {
+				if got < before || got > after {
+					t.Errorf("updatedAt = %d, want clamped to now (%d..%d)", got, before, after)
+				}
+			} else if got != updatedAt {
+				t.Errorf("updatedAt = %d, want it kept at %d", got, updatedAt)
+			}
+		})
+	}
+}
+
+func TestStoreLoadClampsFutureTimestamps(t *testing.T) {
+	s := newTestStore(t, StoreFormatJSON, true)
+	future := time.Now().Add(48 * time.Hour).UnixMilli()
+	if err := s.Save(map[string]*SessionEntry{"webhook:a": {SessionID: "sess_1", UpdatedAt: future}}); err != nil {
+		t.Fatalf("Save: %v", err)
+	}
+
+	store, err := s.Load()
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+	if got := store["webhook:a"].UpdatedAt; got >= future {
+		t.Errorf("loaded updatedAt %d was not clamped", got)
+	}
+}
@@ -1,6 +1,9 @@
 package sessions
 
-import "time"
+import (
+	"fmt"
+	"time"
+)
 
 // SessionScope defines how sessions are scoped
 type SessionScope string
@@ -10,8 +13,19 @@ const (
 	SessionScopePerSender SessionScope = "per-sender"
 	// SessionScopeGlobal uses a single shared session for all users
 	SessionScopeGlobal SessionScope = "global"
+	// SessionScopePerChat shares one session among everyone in the same chat (chatId)
+	SessionScopePerChat SessionScope = "per-chat"
 )
 
+// ParseSessionScope parses a session scope name, rejecting unknown values
+func ParseSessionScope(s string) (SessionScope, error) {
+	switch scope := SessionScope(s); scope {
+	case SessionScopePerSender, SessionScopeGlobal, SessionScopePerChat:
+		return scope, nil
+	}
+	return "", fmt.Errorf("invalid session scope %q (expected \"per-sender\", \"global\" or \"per-chat\")", s)
+}
+
 // SessionEntry represents a stored session with its state
 type SessionEntry struct {
 	// SessionID is the unique identifier for this session
@@ -31,6 +45,8 @@ type SessionEntry struct {
 	LastAccountId string `json:"lastAccountId,omitempty"`
 	// LastThreadId is the last thread ID for this session
 	LastThreadId string `json:"lastThreadId,omitempty"`
+	// LastPeerKind is the chat type ("dm", "group" or "channel") of the last inbound message
+	LastPeerKind string `json:"lastPeerKind,omitempty"`
 
 	// Webhook-specific fields
 	WebhookMessageID string `json:"webhookMessageId,omitempty"`
@@ -49,6 +65,16 @@ type SessionEntry struct {
 	// Model overrides
 	ModelOverride    string `json:"modelOverride,omitempty"`
 	ProviderOverride string `json:"providerOverride,omitempty"`
+
+	// AgentID is the agent this session is routed to; once set it sticks for later messages
+	AgentID string `json:"agentId,omitempty"`
+
+	// Label is a free-form operator annotation (e.g. "VIP", "bug-repro")
+	Label string `json:"label,omitempty"`
+
+	// RecentPrompts holds the last forwarded prompts, oldest first, for session.replay
+	// (only recorded when replay_history is enabled)
+	RecentPrompts []string `json:"recentPrompts,omitempty"`
 }
 
 // DeliveryContext contains information needed to route responses
@@ -57,6 +83,7 @@ type DeliveryContext struct {
 	To        string `json:"to,omitempty"`
 	AccountId string `json:"accountId,omitempty"`
 	ThreadId  string `json:"threadId,omitempty"`
+	PeerKind  string `json:"peerKind,omitempty"` // Chat type of the inbound message, when known
 }
 
 // StoreConfig holds configuration for the session store
@@ -67,6 +94,10 @@ type StoreConfig struct {
 	CacheTTL time.Duration
 	// LockTimeout is how long to wait for a lock
 	LockTimeout time.Duration
+	// Pretty writes indented JSON; false writes compact single-line JSON for large stores
+	Pretty bool
+	// Format is the on-disk serialization (StoreFormatJSON or StoreFormatGob)
+	Format StoreFormat
 }
 
 // DefaultStoreConfig returns the default store configuration
@@ -75,6 +106,8 @@ func DefaultStoreConfig(storePath string) *StoreConfig {
 		StorePath:   storePath,
 		CacheTTL:    45 * time.Second,
 		LockTimeout: 10 * time.Second,
+		Pretty:      true,
+		Format:      StoreFormatJSON,
 	}
 }
 
@@ -128,6 +161,9 @@ func MergeSessionEntry(existing *SessionEntry, patch *SessionEntry) *SessionEntr
 	if patch.LastThreadId != "" {
 		result.LastThreadId = patch.LastThreadId
 	}
+	if patch.LastPeerKind != "" {
+		result.LastPeerKind = patch.LastPeerKind
+	}
 	if patch.WebhookMessageID != "" {
 		result.WebhookMessageID = patch.WebhookMessageID
 	}
@@ -162,6 +198,15 @@ func MergeSessionEntry(existing *SessionEntry, patch *SessionEntry) *SessionEntr
 	if patch.ProviderOverride != "" {
 		result.ProviderOverride = patch.ProviderOverride
 	}
+	if patch.AgentID != "" {
+		result.AgentID = patch.AgentID
+	}
+	if patch.Label != "" {
+		result.Label = patch.Label
+	}
+	if patch.RecentPrompts != nil {
+		result.RecentPrompts = patch.RecentPrompts
+	}
 
 	return result
 }
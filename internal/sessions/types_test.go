@@ -0,0 +1,30 @@
+package sessions
+
+import "testing"
+
+func TestParseSessionScope(t *testing.T) {
+	tests := []struct {
+		in      string
+		want    SessionScope
+		wantErr bool
+	}{
+		{in: "per-sender", want: SessionScopePerSender},
+		{in: "global", want: SessionScopeGlobal},
+		{in: "per-chat", want: SessionScopePerChat},
+		{in: "", wantErr: true},
+		{in: "Global", wantErr: true},
+		{in: "per-user", wantErr: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.in, func(t *testing.T) {
+			got, err := ParseSessionScope(tt.in)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("ParseSessionScope(%q) error = %v, want error %v", tt.in, err, tt.wantErr)
+			}
+			if got != tt.want {
+				t.Errorf("ParseSessionScope(%q) = %q, want %q", tt.in, got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,26 @@
+// Package version identifies this build of the bridge
+package version
+
+import "runtime"
+
+// Version is the bridge version, overridable at build time with
+// -ldflags "-X github.com/sternelee/openclaw-webhook-bridge/internal/version.Version=..."
+var Version = "0.2.0"
+
+// ClientName is how the bridge names itself to the servers it connects to
+const ClientName = "openclaw-bridge-go"
+
+// Client returns the client identifier, e.g. "openclaw-bridge-go/0.2.0"
+func Client() string {
+	return ClientName + "/" + Version
+}
+
+// Platform returns the OS and architecture, e.g. "linux/amd64"
+func Platform() string {
+	return runtime.GOOS + "/" + runtime.GOARCH
+}
+
+// UserAgent returns a User-Agent header value, e.g. "openclaw-bridge-go/0.2.0 (linux/amd64)"
+func UserAgent() string {
+	return Client() + " (" + Platform() + ")"
+}
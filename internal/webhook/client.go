@@ -2,17 +2,52 @@ package webhook
 
 import (
 	"context"
+	"crypto/tls"
+	"crypto/x509"
+	"errors"
 	"fmt"
 	"log"
+	"net"
+	"net/http"
 	"net/url"
+	"os"
 	"strings"
 	"sync"
 	"sync/atomic"
 	"time"
 
 	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/metrics"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/reconnect"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/version"
 )
 
+// Keepalive defaults: ping the server every defaultPingInterval and reconnect when no
+// frame (including pongs) has arrived for defaultIdleTimeout
+const (
+	defaultPingInterval = 30 * time.Second
+	defaultIdleTimeout  = 90 * time.Second
+)
+
+// Each connection attempt gets defaultDialTimeout for the TCP connect and then
+// defaultUpgradeTimeout for the TLS handshake and WebSocket upgrade. Connect waits for
+// the first connection as long as both phases together.
+const (
+	defaultDialTimeout    = 5 * time.Second
+	defaultUpgradeTimeout = 10 * time.Second
+)
+
+// Phases of a connection attempt, tracked in Client.dialPhase
+const (
+	phaseIdle int32 = iota
+	phaseConnecting
+	phaseUpgrading
+)
+
+// defaultPongTimeout is how long a keepalive pong may take before an idle send treats the
+// connection as dead (see SetIdleSendCheck)
+const defaultPongTimeout = 5 * time.Second
+
 // MessageHandler is called when a message is received from the webhook
 // The data is raw JSON bytes that will be forwarded directly to OpenClaw
 type MessageHandler func(data []byte) error
@@ -33,8 +68,76 @@ type Client struct {
 	cancel    context.CancelFunc
 	wg        sync.WaitGroup
 
+	// lifecycleMu guards ctx/cancel across Connect and Close; cancel is non-nil while
+	// a connection loop is running
+	lifecycleMu sync.Mutex
+
+	// writeMu serializes frame writes (the connection allows one writer at a time) and
+	// guards the send queue, which holds frames while disconnected (sendQueueCap 0 = disabled)
+	// and is mirrored to spoolPath when set. inOnConnect lets the onConnect callback write
+	// ahead of queued frames.
+	writeMu      sync.Mutex
+	sendQueue    []spooledFrame
+	sendQueueCap int
+	spoolPath    string
+	inOnConnect  bool
+
+	// maxQueuedAge drops queued frames older than this when they are flushed (0 = keep
+	// all); now is the clock frames are stamped and aged with, replaceable in tests
+	maxQueuedAge time.Duration
+	now          func() time.Time
+
+	// idleSendCheck makes Send validate a connection that has sent nothing for that long
+	// (0 = never); lastSendAt is guarded by writeMu. lastReadAt is the UnixNano time of the
+	// last frame or pong the read loop received.
+	idleSendCheck time.Duration
+	lastSendAt    time.Time
+	lastReadAt    atomic.Int64
+	pongTimeout   time.Duration
+
+	// sendRTT bounds each frame write by a timeout adapted to the ping RTT (nil = no deadline)
+	sendRTT *rttEstimator
+
 	// Connection state notification
 	connCond *sync.Cond
+
+	// reconnectPolicy is consulted before each reconnect; established is set once a
+	// connection attempt succeeds (both only touched by the connection loop goroutine)
+	reconnectPolicy reconnect.Policy
+	established     bool
+
+	// onState is told about connection state transitions (nil = none)
+	onState reconnect.StateCallback
+
+	// dialPhase is the phase of the current connection attempt (phaseIdle when none);
+	// lastErr is the last attempt's error
+	dialPhase atomic.Int32
+	lastErr   error
+	lastErrMu sync.Mutex
+
+	// identification controls how the bridge version/platform is sent on the upgrade
+	identification ClientIdentification
+
+	// authToken is sent as a bearer token on the upgrade request (empty = none)
+	authToken string
+
+	// Keepalive pings (pingInterval 0 = disabled) and the read-idle deadline (0 = none)
+	pingInterval time.Duration
+	idleTimeout  time.Duration
+
+	// Per-attempt timeouts for the TCP connect and the TLS/WebSocket upgrade
+	dialTimeout    time.Duration
+	upgradeTimeout time.Duration
+
+	// TLS settings for wss:// URLs (nil tlsConfig = Go defaults)
+	tlsConfig  *tls.Config
+	requireTLS bool
+
+	// fatalErr stops the connection loop for good (e.g. an untrusted certificate); guarded by lastErrMu
+	fatalErr error
+
+	// onConnect runs on the connection loop after each successful connect, before reading
+	onConnect func()
 }
 
 // NewClient creates a new webhook client
@@ -44,9 +147,179 @@ func NewClient(url string, handler MessageHandler, uid string) *Client {
 		uid:      uid,
 		handler:  handler,
 		connCond: sync.NewCond(&sync.Mutex{}),
+		now:      time.Now,
+
+		pingInterval: defaultPingInterval,
+		idleTimeout:  defaultIdleTimeout,
+		pongTimeout:  defaultPongTimeout,
+
+		dialTimeout:    defaultDialTimeout,
+		upgradeTimeout: defaultUpgradeTimeout,
+	}
+}
+
+// SetKeepalive sets how often the server is pinged (0 disables pings) and how long the
+// connection may go without any incoming frame before it is dropped and re-established
+// (0 disables the idle check). Set it before Connect.
+func (c *Client) SetKeepalive(pingInterval, idleTimeout time.Duration) {
+	c.pingInterval = pingInterval
+	c.idleTimeout = idleTimeout
+}
+
+// SetIdleSendCheck makes Send check the connection first when nothing has been sent for
+// idle, since a connection the server dropped silently often only fails on the next write.
+// If the server has not answered the keepalive pings, the connection is dropped and the
+// message queued for the next one. The check needs a send queue (see SetSendQueue) and
+// keepalive pings, and does nothing without them. 0 disables it. Set it before Connect.
+func (c *Client) SetIdleSendCheck(idle time.Duration) {
+	c.idleSendCheck = idle
+}
+
+// SetAdaptiveSendTimeout bounds every frame write by a timeout derived from the round trip
+// of the keepalive pings, kept between floor and ceiling (the ceiling applies until the
+// first pong). A write that times out drops the connection so it is re-established.
+// Needs keepalive pings (see SetKeepalive). Set it before Connect.
+func (c *Client) SetAdaptiveSendTimeout(floor, ceiling time.Duration) {
+	c.sendRTT = newRTTEstimator(floor, ceiling)
+}
+
+// SetStateCallback registers a callback told about connecting, connected, disconnected
+// and reconnecting transitions. Set it before Connect.
+func (c *Client) SetStateCallback(cb reconnect.StateCallback) {
+	c.onState = cb
+}
+
+// notifyState reports a state transition to the state callback, if any
+func (c *Client) notifyState(change reconnect.StateChange) {
+	if c.onState != nil {
+		c.onState(change)
+	}
+}
+
+// SetReconnectPolicy installs a hook deciding whether to reconnect after each disconnect.
+// The default policy always reconnects. Set it before Connect.
+func (c *Client) SetReconnectPolicy(policy reconnect.Policy) {
+	c.reconnectPolicy = policy
+}
+
+// ClientIdentification controls how the bridge identifies itself on the upgrade request
+type ClientIdentification string
+
+const (
+	IdentifyQuery  ClientIdentification = "query"  // client= and platform= query params (default)
+	IdentifyHeader ClientIdentification = "header" // User-Agent header
+	IdentifyOff    ClientIdentification = "off"    // Only the uid param
+)
+
+// SetClientIdentification sets how the bridge version and platform are reported to the
+// webhook server. Set it before Connect.
+func (c *Client) SetClientIdentification(mode ClientIdentification) {
+	c.identification = mode
+}
+
+// SetAuthToken sends "Authorization: Bearer <token>" on every upgrade request.
+// Set it before Connect.
+func (c *Client) SetAuthToken(token string) {
+	c.authToken = token
+}
+
+// TLSConfig configures certificate verification for wss:// webhook URLs
+type TLSConfig struct {
+	CAFile     string // PEM bundle of root CAs trusted in addition to the system roots
+	RequireTLS bool   // Refuse plaintext ws:// URLs
+}
+
+// SetTLSConfig applies TLS settings, failing if the CA bundle can't be loaded.
+// Set it before Connect.
+func (c *Client) SetTLSConfig(cfg TLSConfig) error {
+	c.requireTLS = cfg.RequireTLS
+	if cfg.CAFile == "" {
+		return nil
+	}
+
+	pem, err := os.ReadFile(cfg.CAFile)
+	if err != nil {
+		return fmt.Errorf("failed to read CA bundle: %w", err)
+	}
+	roots, err := x509.SystemCertPool()
+	if err != nil {
+		roots = x509.NewCertPool()
+	}
+	if !roots.AppendCertsFromPEM(pem) {
+		return fmt.Errorf("no PEM certificates found in %s", cfg.CAFile)
+	}
+	c.tlsConfig = &tls.Config{RootCAs: roots}
+	return nil
+}
+
+// SetSendQueue makes Send hold up to capacity frames while the connection is down and
+// deliver them in order after the next connect, dropping the oldest when full.
+// A capacity of 0 (the default) makes Send fail with ErrNotConnected instead.
+// Set it before Connect.
+func (c *Client) SetSendQueue(capacity int) {
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+	c.sendQueueCap = capacity
+}
+
+// SetMaxQueuedAge drops queued frames that waited longer than age by the time they would
+// be sent, since a reply replayed long after the fact only confuses. 0 keeps every frame.
+// Set it before Connect.
+func (c *Client) SetMaxQueuedAge(age time.Duration) {
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+	c.maxQueuedAge = age
+}
+
+// SetSendSpool keeps the send queue in a file at path so frames queued when the bridge
+// stops are sent after the next start. Frames left from a previous run are queued
+// immediately. Requires a send queue (see SetSendQueue); set it before Connect.
+func (c *Client) SetSendSpool(path string) error {
+	frames, err := loadSpool(path)
+	if err != nil {
+		return err
+	}
+
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+	if c.sendQueueCap == 0 {
+		return fmt.Errorf("a send spool needs a send queue")
+	}
+	c.spoolPath = path
+	if dropped := len(frames) - c.sendQueueCap; dropped > 0 {
+		log.Printf("[Webhook] Spool holds more than the send queue, dropped the %d oldest message(s)", dropped)
+		frames = frames[dropped:]
+	}
+	c.sendQueue = append(frames, c.sendQueue...)
+	if len(frames) > 0 {
+		log.Printf("[Webhook] Restored %d undelivered message(s) from %s", len(frames), path)
+	}
+	return nil
+}
+
+// persistSendQueue mirrors the send queue to the spool file, if any. Call with writeMu held.
+func (c *Client) persistSendQueue() {
+	if c.spoolPath == "" {
+		return
+	}
+	if err := saveSpool(c.spoolPath, c.sendQueue); err != nil {
+		log.Printf("[Webhook] Failed to update send spool: %v", err)
 	}
 }
 
+// SetOnConnect registers a callback run after every successful (re)connect, before any
+// inbound frames are read. Sends made from it go out first on the new connection.
+// Set it before Connect.
+func (c *Client) SetOnConnect(fn func()) {
+	c.onConnect = fn
+}
+
+// ErrAlreadyConnected is returned by Connect while a connection loop is already running
+var ErrAlreadyConnected = errors.New("client is already connected; call Close before connecting again")
+
+// ErrNotConnected is returned by Send while there is no webhook connection
+var ErrNotConnected = errors.New("not connected")
+
 // Connect establishes a WebSocket connection to the webhook server
 func (c *Client) Connect(ctx context.Context) error {
 	// Validate UID is required
@@ -54,22 +327,66 @@ func (c *Client) Connect(ctx context.Context) error {
 		return fmt.Errorf("UID is required for connection. Please configure a unique UID for this bridge instance")
 	}
 
+	if c.requireTLS && !strings.HasPrefix(strings.ToLower(c.url), "wss://") {
+		return fmt.Errorf("refusing plaintext webhook URL %s: TLS is required (use wss://)", c.url)
+	}
+
+	c.lifecycleMu.Lock()
+	if c.cancel != nil {
+		c.lifecycleMu.Unlock()
+		return ErrAlreadyConnected
+	}
 	c.ctx, c.cancel = context.WithCancel(ctx)
+	c.lastErrMu.Lock()
+	c.fatalErr = nil
+	c.lastErrMu.Unlock()
+	loopCtx := c.ctx
 
 	// Start connection loop
 	c.wg.Add(1)
 	go c.connectionLoop()
+	c.lifecycleMu.Unlock()
+
+	if err := c.waitConnected(loopCtx); err != nil {
+		// Stop the loop so a stalled attempt doesn't linger after Connect gave up
+		phase, lastErr := c.dialPhase.Load(), c.lastError()
+		c.Close()
+		switch phase {
+		case phaseConnecting:
+			return fmt.Errorf("webhook server TCP connect timed out after %v", c.dialTimeout)
+		case phaseUpgrading:
+			return fmt.Errorf("webhook server upgrade timed out after %v", c.upgradeTimeout)
+		}
+		if lastErr != nil {
+			return fmt.Errorf("%w: %v", err, lastErr)
+		}
+		return err
+	}
+
+	log.Printf("[Webhook] Connected to %s (UID: %s)", c.url, c.uid)
+	return nil
+}
 
+// IsConnected reports whether a connection is currently established
+func (c *Client) IsConnected() bool {
+	return c.connected.Load()
+}
+
+// waitConnected blocks until the connection loop is connected or both attempt phases' timeouts pass
+func (c *Client) waitConnected(loopCtx context.Context) error {
 	// Wait for connection to be established using condition variable
 	c.connCond.L.Lock()
 	defer c.connCond.L.Unlock()
 
-	timeout := time.NewTimer(5 * time.Second)
+	timeout := time.NewTimer(c.dialTimeout + c.upgradeTimeout)
 	defer timeout.Stop()
 
 	for !c.connected.Load() {
+		if err := c.fatalError(); err != nil {
+			return err
+		}
 		select {
-		case <-c.ctx.Done():
+		case <-loopCtx.Done():
 			return fmt.Errorf("context cancelled while waiting for connection")
 		case <-timeout.C:
 			return fmt.Errorf("timeout connecting to webhook server")
@@ -85,27 +402,58 @@ func (c *Client) Connect(ctx context.Context) error {
 				// Woke up from Wait, check connected again
 			case <-timeout.C:
 				return fmt.Errorf("timeout connecting to webhook server")
-			case <-c.ctx.Done():
+			case <-loopCtx.Done():
 				return fmt.Errorf("context cancelled while waiting for connection")
 			}
 		}
 	}
-
-	log.Printf("[Webhook] Connected to %s (UID: %s)", c.url, c.uid)
 	return nil
 }
 
-// Close gracefully shuts down the connection
+// fatalError returns the error that stopped the connection loop for good, if any
+func (c *Client) fatalError() error {
+	c.lastErrMu.Lock()
+	defer c.lastErrMu.Unlock()
+	return c.fatalErr
+}
+
+// isCertificateError reports whether err is a TLS certificate verification failure,
+// which retrying can't fix
+func isCertificateError(err error) bool {
+	var verifyErr *tls.CertificateVerificationError
+	var authorityErr x509.UnknownAuthorityError
+	var hostnameErr x509.HostnameError
+	var invalidErr x509.CertificateInvalidError
+	return errors.As(err, &verifyErr) || errors.As(err, &authorityErr) ||
+		errors.As(err, &hostnameErr) || errors.As(err, &invalidErr)
+}
+
+// lastError returns the error of the most recent failed connection attempt
+func (c *Client) lastError() error {
+	c.lastErrMu.Lock()
+	defer c.lastErrMu.Unlock()
+	return c.lastErr
+}
+
+// Close gracefully shuts down the connection and waits for the connection loop to exit.
+// It is safe to call before Connect or more than once; the client can be connected again afterwards.
 func (c *Client) Close() error {
+	// Never connected, or already closed
+	c.lifecycleMu.Lock()
+	cancel := c.cancel
+	c.lifecycleMu.Unlock()
+	if cancel == nil {
+		return nil
+	}
+
 	log.Printf("[Webhook] Closing connection...")
 
-	c.cancel()
+	cancel()
 
 	// Wake up any waiters
 	c.connCond.Broadcast()
 
-	c.wg.Wait()
-
+	// Close the socket first so a blocked read returns and the loop can exit
 	c.connMu.Lock()
 	if c.conn != nil {
 		c.conn.Close()
@@ -113,6 +461,12 @@ func (c *Client) Close() error {
 	}
 	c.connMu.Unlock()
 
+	// Connect keeps refusing until the loop has exited (the loop itself may need lifecycleMu)
+	c.wg.Wait()
+	c.lifecycleMu.Lock()
+	c.cancel = nil
+	c.lifecycleMu.Unlock()
+
 	c.connected.Store(false)
 	log.Printf("[Webhook] Connection closed")
 	return nil
@@ -124,6 +478,7 @@ func (c *Client) connectionLoop() {
 
 	reconnectDelay := 2 * time.Second
 	maxReconnectDelay := 30 * time.Second
+	attempt := 0
 
 	for {
 		select {
@@ -133,8 +488,42 @@ func (c *Client) connectionLoop() {
 		default:
 		}
 
-		if err := c.connectAndRead(); err != nil {
+		c.established = false
+		c.notifyState(reconnect.StateChange{State: reconnect.StateConnecting})
+		err := c.connectAndRead()
+		if err != nil {
 			log.Printf("[Webhook] Connection error: %v", err)
+			if c.established {
+				c.notifyState(reconnect.StateChange{State: reconnect.StateDisconnected, Err: err})
+			}
+			c.lastErrMu.Lock()
+			c.lastErr = err
+			c.lastErrMu.Unlock()
+
+			// Shutting down, not a disconnect worth asking about
+			if c.ctx.Err() != nil {
+				return
+			}
+			if isCertificateError(err) {
+				log.Printf("[Webhook] TLS certificate verification failed, not reconnecting: %v", err)
+				c.lastErrMu.Lock()
+				c.fatalErr = fmt.Errorf("webhook server certificate rejected: %w", err)
+				c.lastErrMu.Unlock()
+				c.connCond.Broadcast() // Let Connect report it instead of timing out
+				return
+			}
+			if c.established {
+				attempt = 0
+			}
+			attempt++
+			policy := c.reconnectPolicy
+			if policy == nil {
+				policy = reconnect.Always
+			}
+			if !policy(reconnect.NewDisconnectInfo(err, attempt)) {
+				log.Printf("[Webhook] Reconnect policy declined after %d attempt(s), giving up", attempt)
+				return
+			}
 
 			// Exponential backoff for reconnection
 			if reconnectDelay < maxReconnectDelay {
@@ -145,53 +534,152 @@ func (c *Client) connectionLoop() {
 			reconnectDelay = 2 * time.Second
 		}
 
-		// Wait before reconnecting (or exit if context cancelled)
+		// Wait before reconnecting (or exit if context cancelled), jittered so a gateway
+		// restart doesn't bring every bridge back at once
+		wait := reconnect.Jitter(reconnectDelay)
+		c.notifyState(reconnect.StateChange{State: reconnect.StateReconnecting, Err: err, Attempt: attempt, Delay: wait})
 		select {
 		case <-c.ctx.Done():
 			return
-		case <-time.After(reconnectDelay):
+		case <-time.After(wait):
 			log.Printf("[Webhook] Reconnecting...")
+			metrics.Inc(metrics.WebhookReconnectsTotal)
 		}
 	}
 }
 
-// connectAndRead establishes connection and reads messages
-func (c *Client) connectAndRead() error {
-	// Append UID to URL for identification
-	wsURL := c.url
+// upgradeRequest returns the URL and headers for the upgrade, identifying this bridge
+func (c *Client) upgradeRequest() (string, http.Header) {
+	// Append UID (and client info) to URL for identification
+	params := url.Values{}
 	if c.uid != "" {
+		params.Set("uid", c.uid)
+	}
+	header := http.Header{}
+	if c.authToken != "" {
+		header.Set("Authorization", "Bearer "+c.authToken)
+	}
+	switch c.identification {
+	case IdentifyOff:
+	case IdentifyHeader:
+		header.Set("User-Agent", version.UserAgent())
+	default:
+		params.Set("client", version.Client())
+		params.Set("platform", version.Platform())
+	}
+
+	wsURL := c.url
+	if len(params) > 0 {
 		// Check if URL already has query params
 		separator := "?"
 		if strings.Contains(wsURL, "?") {
 			separator = "&"
 		}
-		wsURL = wsURL + separator + "uid=" + url.QueryEscape(c.uid)
+		wsURL = wsURL + separator + params.Encode()
 	}
+	return wsURL, header
+}
+
+// connectAndRead establishes connection and reads messages
+func (c *Client) connectAndRead() error {
+	wsURL, header := c.upgradeRequest()
 
 	log.Printf("[Webhook] Connecting to %s (UID: %s)", wsURL, c.uid)
 
-	conn, _, err := websocket.DefaultDialer.Dial(wsURL, nil)
+	// Dial with the loop context so Close aborts an attempt stuck in the upgrade. The TCP
+	// connect has its own timeout; the handshake timeout covers both phases. The dialer
+	// only applies deadlines to the socket, so closing it when the loop context ends is
+	// what lets Close interrupt a server that accepted the connection but never answers.
+	var stopAbort func() bool
+	netDialer := &net.Dialer{Timeout: c.dialTimeout}
+	dialer := websocket.Dialer{
+		Proxy: http.ProxyFromEnvironment,
+		NetDialContext: func(ctx context.Context, network, addr string) (net.Conn, error) {
+			conn, err := netDialer.DialContext(ctx, network, addr)
+			if err == nil {
+				c.dialPhase.Store(phaseUpgrading)
+				stopAbort = context.AfterFunc(c.ctx, func() { conn.Close() })
+			}
+			return conn, err
+		},
+		HandshakeTimeout: c.dialTimeout + c.upgradeTimeout,
+		TLSClientConfig:  c.tlsConfig,
+	}
+	c.dialPhase.Store(phaseConnecting)
+	conn, _, err := dialer.DialContext(c.ctx, wsURL, header)
+	if stopAbort != nil {
+		stopAbort()
+	}
+	phase := c.dialPhase.Swap(phaseIdle)
 	if err != nil {
+		var netErr net.Error
+		if errors.As(err, &netErr) && netErr.Timeout() {
+			if phase == phaseConnecting {
+				return fmt.Errorf("TCP connect timed out after %v: %w", c.dialTimeout, err)
+			}
+			return fmt.Errorf("upgrade timed out after %v: %w", c.upgradeTimeout, err)
+		}
 		return fmt.Errorf("failed to dial: %w", err)
 	}
 
 	c.connMu.Lock()
+	if c.ctx.Err() != nil {
+		// Close ran while we were dialing
+		c.connMu.Unlock()
+		conn.Close()
+		return fmt.Errorf("client closed")
+	}
 	c.conn = conn
 	c.connMu.Unlock()
 
+	c.established = true
 	c.connected.Store(true)
 	c.connCond.Broadcast() // Wake up any waiters
+	c.notifyState(reconnect.StateChange{State: reconnect.StateConnected})
 	defer func() {
 		c.connected.Store(false)
 		c.connCond.Broadcast() // Wake up any waiters on disconnect
 	}()
 
+	// A fresh connection needs no validation before the next send
+	c.writeMu.Lock()
+	c.lastSendAt = time.Now()
+	c.writeMu.Unlock()
+	c.markRead()
+
+	if c.onConnect != nil {
+		c.writeMu.Lock()
+		c.inOnConnect = true
+		c.writeMu.Unlock()
+		c.onConnect()
+		c.writeMu.Lock()
+		c.inOnConnect = false
+		c.writeMu.Unlock()
+	}
+	c.flushSendQueue(conn)
+
+	stopPings := c.startKeepalive(conn)
+	defer stopPings()
+
 	// Read messages
 	for {
-		_, message, err := conn.ReadMessage()
+		messageType, message, err := conn.ReadMessage()
 		if err != nil {
+			var netErr net.Error
+			if errors.As(err, &netErr) && netErr.Timeout() {
+				return fmt.Errorf("no frames from webhook server for %v, dropping connection", c.idleTimeout)
+			}
 			return fmt.Errorf("read error: %w", err)
 		}
+		c.markRead()
+		c.extendReadDeadline(conn)
+
+		// Large payloads may arrive as gzip-compressed binary frames
+		message, err = decodeFrame(messageType, message)
+		if err != nil {
+			log.Printf("[Webhook] Dropping frame: %v", err)
+			continue
+		}
 
 		// Don't log message content for privacy - message content may be sensitive
 
@@ -204,26 +692,178 @@ func (c *Client) connectAndRead() error {
 	}
 }
 
-// Send forwards raw JSON data to the webhook (from OpenClaw)
-func (c *Client) Send(data []byte) error {
-	// Early return if not connected to avoid acquiring lock unnecessarily
-	if !c.connected.Load() {
-		return fmt.Errorf("not connected")
+// startKeepalive arms the read-idle deadline, which every incoming frame or pong pushes
+// back, and pings the server every pingInterval. It returns a func that stops the pings.
+func (c *Client) startKeepalive(conn *websocket.Conn) func() {
+	c.extendReadDeadline(conn)
+	conn.SetPongHandler(func(appData string) error {
+		c.markRead()
+		c.extendReadDeadline(conn)
+		if rtt, ok := pongRTT(appData); ok && c.sendRTT != nil {
+			c.sendRTT.observe(rtt)
+		}
+		return nil
+	})
+	if c.pingInterval <= 0 {
+		return func() {}
+	}
+
+	done := make(chan struct{})
+	go func() {
+		ticker := time.NewTicker(c.pingInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-done:
+				return
+			case <-ticker.C:
+				// WriteControl is safe to call alongside Send
+				if err := conn.WriteControl(websocket.PingMessage, pingPayload(), time.Now().Add(c.pingInterval)); err != nil {
+					return
+				}
+			}
+		}
+	}()
+	return func() { close(done) }
+}
+
+// markRead records that the server just sent something
+func (c *Client) markRead() {
+	c.lastReadAt.Store(time.Now().UnixNano())
+}
+
+// serverSilent reports whether the server has gone longer than idleSendCheck, and longer
+// than a keepalive ping should take to be answered, without sending anything
+func (c *Client) serverSilent() (time.Duration, bool) {
+	silence := time.Since(time.Unix(0, c.lastReadAt.Load()))
+	limit := c.idleSendCheck
+	if answered := c.pingInterval + c.pongTimeout; answered > limit {
+		limit = answered
 	}
+	return silence, silence > limit
+}
 
-	c.connMu.RLock()
-	conn := c.conn
-	c.connMu.RUnlock()
+// extendReadDeadline gives the server another idle timeout to send something
+func (c *Client) extendReadDeadline(conn *websocket.Conn) {
+	if c.idleTimeout > 0 {
+		conn.SetReadDeadline(time.Now().Add(c.idleTimeout))
+	}
+}
 
-	if conn == nil {
-		return fmt.Errorf("not connected")
+// Send forwards raw JSON data to the webhook (from OpenClaw).
+// With a send queue (see SetSendQueue) frames that can't be written are queued instead.
+func (c *Client) Send(data []byte) error {
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+
+	var conn *websocket.Conn
+	if c.connected.Load() {
+		c.connMu.RLock()
+		conn = c.conn
+		c.connMu.RUnlock()
 	}
 
 	// Don't log message content for privacy
 
-	if err := conn.WriteMessage(websocket.TextMessage, data); err != nil {
-		return fmt.Errorf("failed to send: %w", err)
+	// A connection that sat idle may have been dropped silently. Send can run on the read
+	// loop (handler replies), so judge from the keepalive pongs it already received rather
+	// than waiting for a new one, and queue the frame for the reconnect.
+	if conn != nil && c.idleSendCheck > 0 && c.pingInterval > 0 && c.sendQueueCap > 0 && !c.inOnConnect &&
+		time.Since(c.lastSendAt) > c.idleSendCheck {
+		if silence, silent := c.serverSilent(); silent {
+			log.Printf("[Webhook] Connection idle for %v has not heard from the server for %v, reconnecting",
+				time.Since(c.lastSendAt).Round(time.Second), silence.Round(time.Second))
+			conn.Close() // The read loop sees the error and reconnects
+			conn = nil
+		}
 	}
 
+	// Frames still waiting for the flush go first, except the onConnect callback's
+	if conn != nil && (len(c.sendQueue) == 0 || c.inOnConnect) {
+		err := c.writeFrame(conn, data)
+		if err == nil {
+			c.lastSendAt = time.Now()
+			return nil
+		}
+		if c.sendQueueCap == 0 {
+			return fmt.Errorf("failed to send: %w", err)
+		}
+		log.Printf("[Webhook] Send failed, queueing for the next connection: %v", err)
+	} else if c.sendQueueCap == 0 {
+		return ErrNotConnected
+	}
+
+	if len(c.sendQueue) >= c.sendQueueCap {
+		c.sendQueue = c.sendQueue[1:]
+		log.Printf("[Webhook] Send queue full (%d), dropped the oldest message", c.sendQueueCap)
+	}
+	c.sendQueue = append(c.sendQueue, newSpooledFrame(data, c.now()))
+	c.persistSendQueue()
 	return nil
 }
+
+// dropStaleQueued removes the queued frames older than maxQueuedAge and returns how many
+// it removed. Callers hold writeMu.
+func (c *Client) dropStaleQueued() int {
+	if c.maxQueuedAge <= 0 {
+		return 0
+	}
+	cutoff := c.now().Add(-c.maxQueuedAge).UnixMilli()
+	kept := c.sendQueue[:0]
+	for _, frame := range c.sendQueue {
+		if frame.queuedAt >= cutoff {
+			kept = append(kept, frame)
+		}
+	}
+	dropped := len(c.sendQueue) - len(kept)
+	c.sendQueue = kept
+	return dropped
+}
+
+// writeFrame writes one text frame, within the adaptive send timeout if one is set.
+// Callers hold writeMu.
+func (c *Client) writeFrame(conn *websocket.Conn, data []byte) error {
+	if c.sendRTT == nil {
+		return conn.WriteMessage(websocket.TextMessage, data)
+	}
+	timeout := c.sendRTT.timeout()
+	conn.SetWriteDeadline(time.Now().Add(timeout))
+	err := conn.WriteMessage(websocket.TextMessage, data)
+	var netErr net.Error
+	if errors.As(err, &netErr) && netErr.Timeout() {
+		// A timed-out write leaves the connection unusable; the read loop reconnects
+		log.Printf("[Webhook] Send timed out after %v, reconnecting", timeout)
+		conn.Close()
+	}
+	return err
+}
+
+// flushSendQueue writes the frames queued while disconnected, in order. Frames that
+// can't be written stay queued for the next connection.
+func (c *Client) flushSendQueue(conn *websocket.Conn) {
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+
+	if len(c.sendQueue) == 0 {
+		return
+	}
+	// Frames leave the spool only once written, so a crash mid-flush resends rather than loses them
+	defer c.persistSendQueue()
+	if dropped := c.dropStaleQueued(); dropped > 0 {
+		log.Printf("[Webhook] Dropped %d queued message(s) older than %v", dropped, c.maxQueuedAge)
+	}
+	if len(c.sendQueue) == 0 {
+		c.sendQueue = nil
+		return
+	}
+	log.Printf("[Webhook] Sending %d message(s) queued while disconnected", len(c.sendQueue))
+	for len(c.sendQueue) > 0 {
+		if err := c.writeFrame(conn, c.sendQueue[0].data); err != nil {
+			log.Printf("[Webhook] Failed to send queued messages, keeping %d: %v", len(c.sendQueue), err)
+			return
+		}
+		c.sendQueue = c.sendQueue[1:]
+		c.lastSendAt = time.Now()
+	}
+	c.sendQueue = nil
+}
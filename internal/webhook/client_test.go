@@ -0,0 +1,373 @@
+package webhook
+
+import (
+	"context"
+	"errors"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/gorilla/websocket"
+	"github.com/sternelee/openclaw-webhook-bridge/internal/version"
+)
+
+// newTestServer starts a WebSocket server that passes each accepted connection to handle
+// and returns its ws:// URL. done is closed when the test ends, so handlers that hold a
+// connection open can wait on it.
+func newTestServer(t *testing.T, handle func(conn *websocket.Conn, done <-chan struct{})) string {
+	t.Helper()
+	done := make(chan struct{})
+	upgrader := websocket.Upgrader{}
+	srv := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		conn, err := upgrader.Upgrade(w, r, nil)
+		if err != nil {
+			return
+		}
+		defer conn.Close()
+		handle(conn, done)
+	}))
+	t.Cleanup(func() {
+		close(done)
+		srv.Close()
+	})
+	return "ws" + strings.TrimPrefix(srv.URL, "http") + "/ws"
+}
+
+func TestDropStaleQueued(t *testing.T) {
+	tests := []struct {
+		name   string
+		maxAge time.Duration
+		ages   []time.Duration // How long ago each frame was queued, oldest first
+		want   []string
+	}{
+		{
+			name: "no limit keeps everything",
+			ages: []time.Duration{time.Hour, time.Minute, 0},
+			want: []string{"0", "1", "2"},
+		},
+		{
+			name:   "frames past the limit are dropped",
+			maxAge: 10 * time.Minute,
+			ages:   []time.Duration{time.Hour, 11 * time.Minute, 9 * time.Minute, time.Second},
+			want:   []string{"2", "3"},
+		},
+		{
+			name:   "a frame exactly at the limit is kept",
+			maxAge: 10 * time.Minute,
+			ages:   []time.Duration{10 * time.Minute},
+			want:   []string{"0"},
+		},
+		{
+			name:   "everything stale",
+			maxAge: time.Minute,
+			ages:   []time.Duration{time.Hour, 2 * time.Minute},
+			want:   []string{},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			now := time.Unix(1700000000, 0)
+			c := NewClient("ws://localhost:0/ws", nil, "uid")
+			c.now = func() time.Time { return now }
+			c.SetSendQueue(len(tt.ages))
+			c.SetMaxQueuedAge(tt.maxAge)
+
+			// Queue while disconnected, moving the clock so each frame gets its age
+			start := now
+			for i, age := range tt.ages {
+				now = start.Add(-age)
+				if err := c.Send([]byte{byte('0' + i)}); err != nil {
+					t.Fatalf("Send: %v", err)
+				}
+			}
+			now = start
+
+			c.writeMu.Lock()
+			dropped := c.dropStaleQueued()
+			queue := c.sendQueue
+			c.writeMu.Unlock()
+
+			if dropped != len(tt.ages)-len(tt.want) {
+				t.Errorf("dropped %d frames, want %d", dropped, len(tt.ages)-len(tt.want))
+			}
+			if len(queue) != len(tt.want) {
+				t.Fatalf("%d frames left, want %d", len(queue), len(tt.want))
+			}
+			for i, frame := range queue {
+				if string(frame.data) != tt.want[i] {
+					t.Errorf("frame %d = %q, want %q", i, frame.data, tt.want[i])
+				}
+			}
+		})
+	}
+}
+
+func TestSendAfterIdleReconnects(t *testing.T) {
+	var conns atomic.Int32
+	frames := make(chan string, 4)
+	url := newTestServer(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		if conns.Add(1) == 1 {
+			// The first connection goes silent: never reading means pings get no pong
+			<-done
+			return
+		}
+		for {
+			_, data, err := conn.ReadMessage()
+			if err != nil {
+				return
+			}
+			frames <- string(data)
+		}
+	})
+
+	c := NewClient(url, nil, "uid")
+	c.SetKeepalive(20*time.Millisecond, 0)
+	c.pongTimeout = 20 * time.Millisecond
+	c.SetIdleSendCheck(50 * time.Millisecond)
+	c.SetSendQueue(10)
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect: %v", err)
+	}
+	t.Cleanup(func() { c.Close() })
+
+	// Idle past the check while the server answers no pings
+	time.Sleep(200 * time.Millisecond)
+	if err := c.Send([]byte(`{"id":"after-idle"}`)); err != nil {
+		t.Fatalf("Send after idling: %v", err)
+	}
+
+	select {
+	case got := <-frames:
+		if got != `{"id":"after-idle"}` {
+			t.Errorf("server got %s, want the message sent after idling", got)
+		}
+	case <-time.After(10 * time.Second):
+		t.Fatal("message sent after idling never arrived over a new connection")
+	}
+}
+
+// newStalledListener accepts TCP connections but never answers the WebSocket upgrade.
+// Accepted connections are sent on the returned channel.
+func newStalledListener(t *testing.T) (string, <-chan net.Conn) {
+	t.Helper()
+	ln, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	accepted := make(chan net.Conn, 8)
+	t.Cleanup(func() {
+		ln.Close()
+		for {
+			select {
+			case conn := <-accepted:
+				conn.Close()
+			default:
+				return
+			}
+		}
+	})
+
+	go func() {
+		for {
+			conn, err := ln.Accept()
+			if err != nil {
+				return
+			}
+			accepted <- conn
+		}
+	}()
+	return "ws://" + ln.Addr().String() + "/ws", accepted
+}
+
+func TestConnectUpgradeTimeout(t *testing.T) {
+	url, _ := newStalledListener(t)
+
+	c := NewClient(url, nil, "uid")
+	c.dialTimeout = 100 * time.Millisecond
+	c.upgradeTimeout = 200 * time.Millisecond
+	err := c.Connect(context.Background())
+	if err == nil || !strings.Contains(err.Error(), "upgrade timed out") {
+		t.Fatalf("Connect error = %v, want an upgrade timeout", err)
+	}
+	if err := c.Connect(context.Background()); errors.Is(err, ErrAlreadyConnected) {
+		t.Error("connection loop still running after Connect gave up")
+	}
+	c.Close()
+}
+
+func TestCloseStopsStalledUpgrade(t *testing.T) {
+	url, accepted := newStalledListener(t)
+
+	c := NewClient(url, nil, "uid")
+	connectErr := make(chan error, 1)
+	go func() { connectErr <- c.Connect(context.Background()) }()
+
+	var serverConn net.Conn
+	select {
+	case serverConn = <-accepted:
+		defer serverConn.Close()
+	case <-time.After(5 * time.Second):
+		t.Fatal("client never dialed")
+	}
+
+	closed := make(chan struct{})
+	go func() {
+		c.Close()
+		close(closed)
+	}()
+	select {
+	case <-closed:
+	case <-time.After(2 * time.Second):
+		t.Fatal("Close did not return while the upgrade was stalled")
+	}
+	select {
+	case err := <-connectErr:
+		if err == nil {
+			t.Error("Connect succeeded against a server that never upgrades")
+		}
+	case <-time.After(2 * time.Second):
+		t.Fatal("Connect did not return after Close")
+	}
+
+	// The stalled socket must be closed, not left for the upgrade timeout
+	serverConn.SetReadDeadline(time.Now().Add(2 * time.Second))
+	buf := make([]byte, 4096)
+	for {
+		_, err := serverConn.Read(buf)
+		if err == nil {
+			continue // The upgrade request
+		}
+		var netErr net.Error
+		if errors.As(err, &netErr) && netErr.Timeout() {
+			t.Error("client left the stalled connection open after Close")
+		}
+		break
+	}
+}
+
+func TestKeepaliveReconnectsSilentServer(t *testing.T) {
+	conns := make(chan int32, 4)
+	var n atomic.Int32
+	url := newTestServer(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		id := n.Add(1)
+		conns <- id
+		if id == 1 {
+			// Stop reading: pings are never answered and nothing else is sent
+			<-done
+			return
+		}
+		for {
+			if _, _, err := conn.ReadMessage(); err != nil {
+				return
+			}
+		}
+	})
+
+	c := NewClient(url, nil, "uid")
+	c.SetKeepalive(20*time.Millisecond, 100*time.Millisecond)
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect: %v", err)
+	}
+	t.Cleanup(func() { c.Close() })
+
+	deadline := time.After(10 * time.Second)
+	for {
+		select {
+		case id := <-conns:
+			if id >= 2 {
+				return
+			}
+		case <-deadline:
+			t.Fatal("no reconnect after the server stopped answering pings")
+		}
+	}
+}
+
+func TestUpgradeRequest(t *testing.T) {
+	clientInfo := url.Values{"uid": {"u1"}, "client": {version.Client()}, "platform": {version.Platform()}}.Encode()
+
+	tests := []struct {
+		name      string
+		url       string
+		uid       string
+		mode      ClientIdentification
+		token     string
+		wantURL   string
+		wantAgent string // Expected User-Agent header, "" for none
+		wantAuth  string // Expected Authorization header, "" for none
+	}{
+		{name: "query mode", url: "ws://h/ws", uid: "u1", mode: IdentifyQuery, wantURL: "ws://h/ws?" + clientInfo},
+		{name: "header mode", url: "ws://h/ws", uid: "u1", mode: IdentifyHeader, wantURL: "ws://h/ws?uid=u1", wantAgent: version.UserAgent()},
+		{name: "off", url: "ws://h/ws", uid: "u1", mode: IdentifyOff, wantURL: "ws://h/ws?uid=u1"},
+		{name: "URL with a query string", url: "ws://h/ws?token=abc", uid: "u1", mode: IdentifyQuery, wantURL: "ws://h/ws?token=abc&" + clientInfo},
+		{name: "off without a uid leaves the URL alone", url: "ws://h/ws", mode: IdentifyOff, wantURL: "ws://h/ws"},
+		{name: "auth token", url: "ws://h/ws", uid: "u1", mode: IdentifyOff, token: "secret", wantURL: "ws://h/ws?uid=u1", wantAuth: "Bearer secret"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			c := NewClient(tt.url, nil, tt.uid)
+			c.SetClientIdentification(tt.mode)
+			c.SetAuthToken(tt.token)
+
+			gotURL, header := c.upgradeRequest()
+			if gotURL != tt.wantURL {
+				t.Errorf("URL = %s, want %s", gotURL, tt.wantURL)
+			}
+			if got := header.Get("User-Agent"); got != tt.wantAgent {
+				t.Errorf("User-Agent = %q, want %q", got, tt.wantAgent)
+			}
+			if got := header.Get("Authorization"); got != tt.wantAuth {
+				t.Errorf("Authorization = %q, want %q", got, tt.wantAuth)
+			}
+		})
+	}
+}
+
+func TestConnectLifecycle(t *testing.T) {
+	conns := make(chan struct{}, 4)
+	waitConn := func(n int) {
+		t.Helper()
+		select {
+		case <-conns:
+		case <-time.After(5 * time.Second):
+			t.Fatalf("server never saw connection %d", n)
+		}
+	}
+	wsURL := newTestServer(t, func(conn *websocket.Conn, done <-chan struct{}) {
+		conns <- struct{}{}
+		for {
+			if _, _, err := conn.ReadMessage(); err != nil {
+				return
+			}
+		}
+	})
+
+	c := NewClient(wsURL, nil, "uid")
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect: %v", err)
+	}
+	waitConn(1)
+	if err := c.Connect(context.Background()); !errors.Is(err, ErrAlreadyConnected) {
+		t.Errorf("second Connect error = %v, want %v", err, ErrAlreadyConnected)
+	}
+
+	c.Close()
+	if c.IsConnected() {
+		t.Error("still connected after Close")
+	}
+	if err := c.Connect(context.Background()); err != nil {
+		t.Fatalf("Connect after Close: %v", err)
+	}
+	defer c.Close()
+	if !c.IsConnected() {
+		t.Error("not connected after reconnecting")
+	}
+	waitConn(2)
+}
@@ -0,0 +1,39 @@
+package webhook
+
+import (
+	"bytes"
+	"compress/gzip"
+	"fmt"
+	"io"
+
+	"github.com/gorilla/websocket"
+)
+
+// gzipMagic is the header every gzip stream starts with
+var gzipMagic = []byte{0x1f, 0x8b}
+
+// maxDecompressedSize caps an inflated frame so a small compressed frame can't exhaust memory
+const maxDecompressedSize = 32 << 20
+
+// decodeFrame returns the JSON payload of an inbound frame. Binary frames starting with
+// the gzip magic bytes are decompressed; all other frames are returned unchanged.
+func decodeFrame(messageType int, data []byte) ([]byte, error) {
+	if messageType != websocket.BinaryMessage || !bytes.HasPrefix(data, gzipMagic) {
+		return data, nil
+	}
+
+	zr, err := gzip.NewReader(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("invalid gzip frame: %w", err)
+	}
+	defer zr.Close()
+
+	decoded, err := io.ReadAll(io.LimitReader(zr, maxDecompressedSize+1))
+	if err != nil {
+		return nil, fmt.Errorf("failed to decompress frame: %w", err)
+	}
+	if len(decoded) > maxDecompressedSize {
+		return nil, fmt.Errorf("decompressed frame exceeds %d bytes", maxDecompressedSize)
+	}
+	return decoded, nil
+}
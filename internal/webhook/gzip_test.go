@@ -0,0 +1,63 @@
+package webhook
+
+import (
+	"bytes"
+	"compress/gzip"
+	"strings"
+	"testing"
+
+	"github.com/gorilla/websocket"
+)
+
+// gzipBytes compresses data into a gzip stream
+func gzipBytes(t *testing.T, data []byte) []byte {
+	t.Helper()
+	var buf bytes.Buffer
+	zw := gzip.NewWriter(&buf)
+	if _, err := zw.Write(data); err != nil {
+		t.Fatal(err)
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	return buf.Bytes()
+}
+
+func TestDecodeFrame(t *testing.T) {
+	payload := []byte(`{"id":"1","content":"hello"}`)
+	compressed := gzipBytes(t, payload)
+
+	tests := []struct {
+		name        string
+		messageType int
+		data        []byte
+		want        []byte
+		wantErr     string // Substring of the expected error, "" for success
+	}{
+		{name: "gzip binary frame", messageType: websocket.BinaryMessage, data: compressed, want: payload},
+		{name: "plain text frame", messageType: websocket.TextMessage, data: payload, want: payload},
+		{name: "gzip bytes in a text frame are left alone", messageType: websocket.TextMessage, data: compressed, want: compressed},
+		{name: "binary frame without the magic", messageType: websocket.BinaryMessage, data: payload, want: payload},
+		{name: "corrupt gzip header", messageType: websocket.BinaryMessage, data: []byte{0x1f, 0x8b, 0x00, 0x01}, wantErr: "invalid gzip frame"},
+		{name: "truncated gzip stream", messageType: websocket.BinaryMessage, data: compressed[:len(compressed)-10], wantErr: "failed to decompress frame"},
+		{name: "inflates past the limit", messageType: websocket.BinaryMessage, data: gzipBytes(t, make([]byte, maxDecompressedSize+1)), wantErr: "exceeds"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := decodeFrame(tt.messageType, tt.data)
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("decodeFrame error = %v, want one containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("decodeFrame: %v", err)
+			}
+			if !bytes.Equal(got, tt.want) {
+				t.Errorf("decodeFrame = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,80 @@
+package webhook
+
+import (
+	"strconv"
+	"sync"
+	"time"
+)
+
+// rttVarianceFactor weighs the RTT deviation in the send timeout, as in TCP's RTO (RFC 6298)
+const rttVarianceFactor = 4
+
+// rttEstimator smooths the round-trip times measured with keepalive pings and turns them
+// into a send timeout: the smoothed RTT plus rttVarianceFactor deviations, clamped to
+// [floor, ceiling]. Before the first sample the timeout is the ceiling.
+type rttEstimator struct {
+	mu      sync.Mutex
+	floor   time.Duration
+	ceiling time.Duration
+	srtt    time.Duration // Smoothed RTT, 0 until the first sample
+	rttvar  time.Duration // Smoothed mean deviation of the samples
+}
+
+func newRTTEstimator(floor, ceiling time.Duration) *rttEstimator {
+	return &rttEstimator{floor: floor, ceiling: ceiling}
+}
+
+// observe folds one measured round trip into the estimate
+func (e *rttEstimator) observe(rtt time.Duration) {
+	if rtt <= 0 {
+		return
+	}
+	e.mu.Lock()
+	defer e.mu.Unlock()
+
+	if e.srtt == 0 {
+		e.srtt = rtt
+		e.rttvar = rtt / 2
+		return
+	}
+	diff := e.srtt - rtt
+	if diff < 0 {
+		diff = -diff
+	}
+	e.rttvar = (3*e.rttvar + diff) / 4
+	e.srtt = (7*e.srtt + rtt) / 8
+}
+
+// timeout returns the send timeout for the current estimate
+func (e *rttEstimator) timeout() time.Duration {
+	e.mu.Lock()
+	defer e.mu.Unlock()
+
+	if e.srtt == 0 {
+		return e.ceiling
+	}
+	timeout := e.srtt + rttVarianceFactor*e.rttvar
+	if timeout < e.floor {
+		return e.floor
+	}
+	if timeout > e.ceiling {
+		return e.ceiling
+	}
+	return timeout
+}
+
+// pingPayload stamps a keepalive ping with the time it was sent, so the pong (which
+// echoes the payload) yields a round-trip sample
+func pingPayload() []byte {
+	return []byte(strconv.FormatInt(time.Now().UnixNano(), 10))
+}
+
+// pongRTT returns the round trip of the ping a pong answers, false if the pong carries
+// no timestamp (e.g. an unsolicited pong)
+func pongRTT(appData string) (time.Duration, bool) {
+	sentAt, err := strconv.ParseInt(appData, 10, 64)
+	if err != nil {
+		return 0, false
+	}
+	return time.Since(time.Unix(0, sentAt)), true
+}
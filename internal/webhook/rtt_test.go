@@ -0,0 +1,97 @@
+package webhook
+
+import (
+	"testing"
+	"time"
+)
+
+func TestRTTEstimatorTimeout(t *testing.T) {
+	repeat := func(rtt time.Duration, n int) []time.Duration {
+		samples := make([]time.Duration, n)
+		for i := range samples {
+			samples[i] = rtt
+		}
+		return samples
+	}
+
+	tests := []struct {
+		name     string
+		floor    time.Duration
+		ceiling  time.Duration
+		samples  []time.Duration
+		min, max time.Duration
+	}{
+		{
+			name:    "no samples uses the ceiling",
+			floor:   10 * time.Millisecond,
+			ceiling: 2 * time.Second,
+			min:     2 * time.Second,
+			max:     2 * time.Second,
+		},
+		{
+			name:    "steady RTT converges on it",
+			floor:   10 * time.Millisecond,
+			ceiling: 10 * time.Second,
+			samples: repeat(100*time.Millisecond, 20),
+			min:     100 * time.Millisecond,
+			max:     110 * time.Millisecond,
+		},
+		{
+			name:    "fast link is held at the floor",
+			floor:   50 * time.Millisecond,
+			ceiling: 10 * time.Second,
+			samples: repeat(time.Millisecond, 20),
+			min:     50 * time.Millisecond,
+			max:     50 * time.Millisecond,
+		},
+		{
+			name:    "slow link is held at the ceiling",
+			floor:   50 * time.Millisecond,
+			ceiling: 2 * time.Second,
+			samples: repeat(5*time.Second, 20),
+			min:     2 * time.Second,
+			max:     2 * time.Second,
+		},
+		{
+			name:    "follows a latency increase",
+			floor:   10 * time.Millisecond,
+			ceiling: 10 * time.Second,
+			samples: append(repeat(100*time.Millisecond, 20), repeat(500*time.Millisecond, 30)...),
+			min:     450 * time.Millisecond,
+			max:     700 * time.Millisecond,
+		},
+		{
+			name:    "ignores non-positive samples",
+			floor:   10 * time.Millisecond,
+			ceiling: 2 * time.Second,
+			samples: []time.Duration{0, -time.Second},
+			min:     2 * time.Second,
+			max:     2 * time.Second,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			e := newRTTEstimator(tt.floor, tt.ceiling)
+			for _, rtt := range tt.samples {
+				e.observe(rtt)
+			}
+			if got := e.timeout(); got < tt.min || got > tt.max {
+				t.Errorf("timeout() = %v, want between %v and %v", got, tt.min, tt.max)
+			}
+		})
+	}
+}
+
+func TestPongRTT(t *testing.T) {
+	if _, ok := pongRTT(""); ok {
+		t.Error("pongRTT accepted a pong without a timestamp")
+	}
+	rtt, ok := pongRTT(string(pingPayload()))
+	if !ok {
+		t.Fatal("pongRTT rejected a ping payload")
+	}
+	if rtt < 0 || rtt > time.Second {
+		t.Errorf("pongRTT = %v for a ping sent just now", rtt)
+	}
+}
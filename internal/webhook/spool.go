@@ -0,0 +1,94 @@
+package webhook
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"os"
+	"time"
+)
+
+// spoolRecord is one line of the outbound spool file
+type spoolRecord struct {
+	QueuedAt int64           `json:"ts"`              // Time the frame was queued, Unix milliseconds
+	Frame    json.RawMessage `json:"frame,omitempty"` // The frame, if it is valid JSON
+	Raw      string          `json:"raw,omitempty"`   // The frame text otherwise
+}
+
+// spooledFrame is a queued outbound frame with the time it was queued
+type spooledFrame struct {
+	data     []byte
+	queuedAt int64
+}
+
+// loadSpool reads the frames left in a spool file, oldest first. A missing file is empty.
+func loadSpool(path string) ([]spooledFrame, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, nil
+		}
+		return nil, fmt.Errorf("failed to read spool: %w", err)
+	}
+
+	var frames []spooledFrame
+	scanner := bufio.NewScanner(bytes.NewReader(data))
+	scanner.Buffer(make([]byte, 64*1024), 16*1024*1024)
+	for scanner.Scan() {
+		var rec spoolRecord
+		if err := json.Unmarshal(scanner.Bytes(), &rec); err != nil {
+			continue // A torn last line from a crash mid-write
+		}
+		frame := []byte(rec.Raw)
+		if len(rec.Frame) > 0 {
+			frame = []byte(rec.Frame)
+		}
+		frames = append(frames, spooledFrame{data: frame, queuedAt: rec.QueuedAt})
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("failed to parse spool %s: %w", path, err)
+	}
+	return frames, nil
+}
+
+// saveSpool atomically replaces the spool file with frames, removing it when empty
+func saveSpool(path string, frames []spooledFrame) error {
+	if len(frames) == 0 {
+		if err := os.Remove(path); err != nil && !os.IsNotExist(err) {
+			return fmt.Errorf("failed to remove spool: %w", err)
+		}
+		return nil
+	}
+
+	var buf bytes.Buffer
+	for _, f := range frames {
+		rec := spoolRecord{QueuedAt: f.queuedAt}
+		if json.Valid(f.data) {
+			rec.Frame = f.data
+		} else {
+			rec.Raw = string(f.data)
+		}
+		line, err := json.Marshal(rec)
+		if err != nil {
+			return fmt.Errorf("failed to encode spooled frame: %w", err)
+		}
+		buf.Write(line)
+		buf.WriteByte('\n')
+	}
+
+	tmpPath := path + ".tmp"
+	if err := os.WriteFile(tmpPath, buf.Bytes(), 0600); err != nil {
+		return fmt.Errorf("failed to write spool: %w", err)
+	}
+	if err := os.Rename(tmpPath, path); err != nil {
+		os.Remove(tmpPath)
+		return fmt.Errorf("failed to rename spool: %w", err)
+	}
+	return nil
+}
+
+// newSpooledFrame stamps a frame with the time it was queued
+func newSpooledFrame(data []byte, now time.Time) spooledFrame {
+	return spooledFrame{data: data, queuedAt: now.UnixMilli()}
+}
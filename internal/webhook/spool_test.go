@@ -0,0 +1,165 @@
+package webhook
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestSpoolRoundTrip(t *testing.T) {
+	tests := []struct {
+		name   string
+		frames []spooledFrame
+	}{
+		{
+			name:   "JSON frames",
+			frames: []spooledFrame{{data: []byte(`{"id":"1","content":"hi"}`), queuedAt: 1000}, {data: []byte(`[1,2]`), queuedAt: 2000}},
+		},
+		{
+			name:   "non-JSON frames",
+			frames: []spooledFrame{{data: []byte("plain text\nwith a newline"), queuedAt: 1000}},
+		},
+		{
+			name:   "mixed frames keep their order",
+			frames: []spooledFrame{{data: []byte("raw"), queuedAt: 1}, {data: []byte(`{"id":"2"}`), queuedAt: 2}, {data: []byte("raw again"), queuedAt: 3}},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			path := filepath.Join(t.TempDir(), "outbound-spool.ndjson")
+			if err := saveSpool(path, tt.frames); err != nil {
+				t.Fatalf("saveSpool: %v", err)
+			}
+			if _, err := os.Stat(path + ".tmp"); !os.IsNotExist(err) {
+				t.Errorf("temp file left behind (stat error %v)", err)
+			}
+
+			got, err := loadSpool(path)
+			if err != nil {
+				t.Fatalf("loadSpool: %v", err)
+			}
+			if len(got) != len(tt.frames) {
+				t.Fatalf("loaded %d frames, want %d", len(got), len(tt.frames))
+			}
+			for i, want := range tt.frames {
+				if string(got[i].data) != string(want.data) || got[i].queuedAt != want.queuedAt {
+					t.Errorf("frame %d = %q at %d, want %q at %d", i, got[i].data, got[i].queuedAt, want.data, want.queuedAt)
+				}
+			}
+		})
+	}
+}
+
+func TestLoadSpool(t *testing.T) {
+	tests := []struct {
+		name     string
+		contents *string // nil = no file
+		want     []string
+	}{
+		{name: "missing file is empty"},
+		{
+			name:     "torn last line is skipped",
+			contents: strPtr("{\"ts\":1,\"frame\":{\"id\":\"1\"}}\n{\"ts\":2,\"fra"),
+			want:     []string{`{"id":"1"}`},
+		},
+		{
+			name:     "raw frames",
+			contents: strPtr("{\"ts\":1,\"raw\":\"hello\"}\n"),
+			want:     []string{"hello"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			path := filepath.Join(t.TempDir(), "outbound-spool.ndjson")
+			if tt.contents != nil {
+				if err := os.WriteFile(path, []byte(*tt.contents), 0600); err != nil {
+					t.Fatal(err)
+				}
+			}
+
+			got, err := loadSpool(path)
+			if err != nil {
+				t.Fatalf("loadSpool: %v", err)
+			}
+			if len(got) != len(tt.want) {
+				t.Fatalf("loaded %d frames, want %d", len(got), len(tt.want))
+			}
+			for i, want := range tt.want {
+				if string(got[i].data) != want {
+					t.Errorf("frame %d = %q, want %q", i, got[i].data, want)
+				}
+			}
+		})
+	}
+}
+
+func TestSaveSpoolRemovesEmptySpool(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "outbound-spool.ndjson")
+	if err := saveSpool(path, []spooledFrame{{data: []byte(`{}`), queuedAt: 1}}); err != nil {
+		t.Fatalf("saveSpool: %v", err)
+	}
+	if err := saveSpool(path, nil); err != nil {
+		t.Fatalf("saveSpool of an empty queue: %v", err)
+	}
+	if _, err := os.Stat(path); !os.IsNotExist(err) {
+		t.Errorf("empty spool was not removed (stat error %v)", err)
+	}
+	// Removing an already missing spool is fine
+	if err := saveSpool(path, nil); err != nil {
+		t.Errorf("saveSpool without a file: %v", err)
+	}
+}
+
+func TestSetSendSpool(t *testing.T) {
+	tests := []struct {
+		name      string
+		queueSize int
+		spooled   []string
+		want      []string
+		wantErr   bool
+	}{
+		{name: "restores spooled frames", queueSize: 10, spooled: []string{"a", "b"}, want: []string{"a", "b"}},
+		{name: "keeps the newest that fit the queue", queueSize: 2, spooled: []string{"a", "b", "c"}, want: []string{"b", "c"}},
+		{name: "needs a send queue", spooled: []string{"a"}, wantErr: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			path := filepath.Join(t.TempDir(), "outbound-spool.ndjson")
+			frames := make([]spooledFrame, len(tt.spooled))
+			for i, data := range tt.spooled {
+				frames[i] = spooledFrame{data: []byte(data), queuedAt: int64(i + 1)}
+			}
+			if err := saveSpool(path, frames); err != nil {
+				t.Fatalf("saveSpool: %v", err)
+			}
+
+			c := NewClient("ws://localhost:0/ws", nil, "uid")
+			if tt.queueSize > 0 {
+				c.SetSendQueue(tt.queueSize)
+			}
+			err := c.SetSendSpool(path)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("SetSendSpool error = %v, want error %v", err, tt.wantErr)
+			}
+
+			c.writeMu.Lock()
+			queue := c.sendQueue
+			c.writeMu.Unlock()
+			if len(queue) != len(tt.want) {
+				t.Fatalf("%d frames queued, want %d", len(queue), len(tt.want))
+			}
+			for i, want := range tt.want {
+				if string(queue[i].data) != want {
+					t.Errorf("frame %d = %q, want %q", i, queue[i].data, want)
+				}
+			}
+		})
+	}
+}
+
+func strPtr(s string) *string {
+	return &s
+}